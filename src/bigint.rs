@@ -0,0 +1,525 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+};
+
+/// The most digits an exact power, left shift, or factorial can have. A bigger one is an error,
+/// so `2 ^ 10000000` doesn't spend minutes building an exact result
+pub const MAX_EXACT_DIGITS: usize = 10_000;
+
+// each limb holds 9 decimal digits, so converting to and from decimal text never needs a division
+const BASE: u64 = 1_000_000_000;
+const LIMB_DIGITS: usize = 9;
+
+/// An integer of any size, stored as its sign and base 10^9 digits, least significant first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigInt {
+    /// whether the integer is below zero. Zero is never negative
+    negative: bool,
+    /// the digits of the magnitude, without any zero digits at the most significant end
+    limbs: Vec<u32>,
+}
+impl BigInt {
+    /// Creates the integer equal to `integer`
+    pub fn from_i128(integer: i128) -> Self {
+        let mut magnitude = integer.unsigned_abs();
+        let mut limbs = Vec::new();
+        while magnitude > 0 {
+            limbs.push((magnitude % BASE as u128) as u32);
+            magnitude /= BASE as u128;
+        }
+        BigInt::from_parts(integer < 0, limbs)
+    }
+
+    /// Creates the integer written as decimal `digits`, with an optional leading `-`
+    /// # Returns
+    ///  - `Some(integer)`: when `digits` is a whole number
+    ///  - `None`: when `digits` is empty or contains anything but digits after the sign
+    pub fn parse(digits: &str) -> Option<Self> {
+        let (negative, digits) = match digits.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, digits),
+        };
+        if digits.is_empty() || !digits.bytes().all(|digit| digit.is_ascii_digit()) {
+            return None;
+        }
+
+        // take 9 digits at a time from the least significant end
+        let limbs = digits
+            .as_bytes()
+            .rchunks(LIMB_DIGITS)
+            .map(|chunk| chunk.iter().fold(0, |limb, digit| limb * 10 + (digit - b'0') as u32))
+            .collect();
+        Some(BigInt::from_parts(negative, limbs))
+    }
+
+    /// Creates the integer written as `digits` in base `radix`, like `FF` in base 16
+    /// # Returns
+    ///  - `Some(integer)`: when `digits` is a whole number in base `radix`
+    ///  - `None`: when `digits` is empty, contains a digit that isn't valid in base `radix`, or `radix` is over 36
+    pub fn parse_radix(digits: &str, radix: u32) -> Option<Self> {
+        if radix == 10 {
+            return BigInt::parse(digits);
+        }
+        if digits.is_empty() || radix > 36 {
+            return None;
+        }
+        let radix_integer = BigInt::from_i128(radix as i128);
+        digits.chars().try_fold(BigInt::from_i128(0), |integer, digit| {
+            Some(integer.mul(&radix_integer).add(&BigInt::from_i128(digit.to_digit(radix)? as i128)))
+        })
+    }
+
+    /// Whether the integer is zero
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Whether the integer is below zero
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// How many decimal digits the magnitude has, which is `1` for zero
+    pub fn digit_count(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => (self.limbs.len() - 1) * LIMB_DIGITS + top.to_string().len(),
+            None => 1,
+        }
+    }
+
+    /// The integer as an `i128`, when it fits
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .try_fold(0i128, |magnitude, limb| magnitude.checked_mul(BASE as i128)?.checked_add(*limb as i128))?;
+        Some(if self.negative { -magnitude } else { magnitude })
+    }
+
+    /// The nearest `f64`, which is infinite when the integer is beyond `f64::MAX`
+    pub fn to_f64(&self) -> f64 {
+        // parsing the decimal text rounds correctly, which summing the limbs as floats wouldn't
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// `|self|`
+    pub fn abs(&self) -> Self {
+        BigInt::from_parts(false, self.limbs.clone())
+    }
+
+    /// `-self`
+    pub fn neg(&self) -> Self {
+        BigInt::from_parts(!self.negative, self.limbs.clone())
+    }
+
+    /// `self + rhs`
+    pub fn add(&self, rhs: &BigInt) -> Self {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, add_magnitudes(&self.limbs, &rhs.limbs));
+        }
+
+        // adding numbers of opposite signs subtracts the smaller magnitude from the larger
+        match compare_magnitudes(&self.limbs, &rhs.limbs) {
+            Ordering::Less => BigInt::from_parts(rhs.negative, subtract_magnitudes(&rhs.limbs, &self.limbs)),
+            _ => BigInt::from_parts(self.negative, subtract_magnitudes(&self.limbs, &rhs.limbs)),
+        }
+    }
+
+    /// `self - rhs`
+    pub fn sub(&self, rhs: &BigInt) -> Self {
+        self.add(&rhs.neg())
+    }
+
+    /// `self * rhs`
+    pub fn mul(&self, rhs: &BigInt) -> Self {
+        BigInt::from_parts(self.negative != rhs.negative, multiply_magnitudes(&self.limbs, &rhs.limbs))
+    }
+
+    /// `self ^ exponent`
+    pub fn pow(&self, mut exponent: u32) -> Self {
+        let mut result = BigInt::from_i128(1);
+        let mut base = self.clone();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul(&base);
+            }
+        }
+        result
+    }
+
+    /// `self * 10 ^ places`
+    pub fn mul_pow10(&self, places: u32) -> Self {
+        self.mul(&BigInt::from_i128(10).pow(places))
+    }
+
+    /// The quotient and remainder of `self / rhs`, with the quotient rounded toward zero like Rust's `/` and `%`
+    /// # Returns
+    ///  - `Some((quotient, remainder))`: where the remainder has the sign of `self`
+    ///  - `None`: when `rhs` is zero
+    pub fn div_rem(&self, rhs: &BigInt) -> Option<(Self, Self)> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = divide_magnitudes(&self.limbs, &rhs.limbs);
+        Some((
+            BigInt::from_parts(self.negative != rhs.negative, quotient),
+            BigInt::from_parts(self.negative, remainder),
+        ))
+    }
+
+    /// The Euclidean quotient and remainder of `self / rhs`, matching `i128::div_euclid` and `i128::rem_euclid`
+    /// # Returns
+    ///  - `Some((quotient, remainder))`: where the remainder is always between `0` and `|rhs|`
+    ///  - `None`: when `rhs` is zero
+    pub fn div_rem_euclid(&self, rhs: &BigInt) -> Option<(Self, Self)> {
+        let (quotient, remainder) = self.div_rem(rhs)?;
+        if !remainder.is_negative() {
+            return Some((quotient, remainder));
+        }
+
+        // a negative remainder is moved up into range by taking one more, or one less, of `rhs`
+        let one = BigInt::from_i128(1);
+        if rhs.is_negative() {
+            Some((quotient.add(&one), remainder.sub(rhs)))
+        } else {
+            Some((quotient.sub(&one), remainder.add(rhs)))
+        }
+    }
+
+    /// `self & rhs`, on the two's complement bits of both, so `-1 & x` is `x`
+    pub fn and(&self, rhs: &BigInt) -> Self {
+        self.bitwise(rhs, |lhs, rhs| lhs & rhs)
+    }
+
+    /// `self | rhs`, on the two's complement bits of both
+    pub fn or(&self, rhs: &BigInt) -> Self {
+        self.bitwise(rhs, |lhs, rhs| lhs | rhs)
+    }
+
+    /// `self xor rhs`, on the two's complement bits of both
+    pub fn xor(&self, rhs: &BigInt) -> Self {
+        self.bitwise(rhs, |lhs, rhs| lhs ^ rhs)
+    }
+
+    /// `~self`, which flips every two's complement bit, so it is `-self - 1`
+    pub fn not(&self) -> Self {
+        self.neg().sub(&BigInt::from_i128(1))
+    }
+
+    /// `self << places`, which is `self * 2 ^ places`
+    pub fn shl(&self, places: u32) -> Self {
+        self.mul(&BigInt::from_i128(2).pow(places))
+    }
+
+    /// `self >> places`, which keeps the sign, so it is `self / 2 ^ places` rounded down and `-1 >> 1` is `-1`
+    pub fn shr(&self, places: u32) -> Self {
+        // shifting out every digit leaves only the sign, without working out a huge power of 2
+        if places as f64 * std::f64::consts::LOG10_2 > self.digit_count() as f64 + 1.0 {
+            return BigInt::from_i128(if self.negative { -1 } else { 0 });
+        }
+        match self.div_rem_euclid(&BigInt::from_i128(2).pow(places)) {
+            Some((quotient, _)) => quotient,
+            None => BigInt::from_i128(0),
+        }
+    }
+
+    // apply `operation` to each 32 bits of the two's complement of `self` and `rhs`.
+    // a negative integer has infinitely many 1 bits past its top, which the result's sign is worked out from
+    fn bitwise(&self, rhs: &BigInt, operation: impl Fn(u32, u32) -> u32) -> Self {
+        let (lhs_words, rhs_words) = (self.twos_complement(), rhs.twos_complement());
+        let fill = |integer: &BigInt| if integer.negative { u32::MAX } else { 0 };
+        let words: Vec<u32> = (0..lhs_words.len().max(rhs_words.len()))
+            .map(|i| operation(*lhs_words.get(i).unwrap_or(&fill(self)), *rhs_words.get(i).unwrap_or(&fill(rhs))))
+            .collect();
+
+        // a negative result is `-(!words + 1)`
+        if operation(fill(self), fill(rhs)) != 0 {
+            let flipped: Vec<u32> = words.iter().map(|word| !word).collect();
+            BigInt::from_words(&flipped).add(&BigInt::from_i128(1)).neg()
+        } else {
+            BigInt::from_words(&words)
+        }
+    }
+
+    // the two's complement bits of the integer, 32 at a time and least significant first, up to where only the sign bits are left.
+    // a negative integer `-m` is the bits of `m - 1` flipped
+    fn twos_complement(&self) -> Vec<u32> {
+        if !self.negative {
+            return self.words();
+        }
+        self.add(&BigInt::from_i128(1)).words().iter().map(|word| !word).collect()
+    }
+
+    // the magnitude in base 2^32, least significant first, found by repeatedly dividing the limbs by 2^32
+    fn words(&self) -> Vec<u32> {
+        let mut limbs = self.limbs.clone();
+        let mut words = Vec::new();
+        while !limbs.is_empty() {
+            let mut remainder = 0u64;
+            for limb in limbs.iter_mut().rev() {
+                let current = remainder * BASE + *limb as u64;
+                *limb = (current >> 32) as u32;
+                remainder = current & u32::MAX as u64;
+            }
+            words.push(remainder as u32);
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+        words
+    }
+
+    // the integer whose magnitude is `words` in base 2^32, least significant first
+    fn from_words(words: &[u32]) -> Self {
+        let mut limbs: Vec<u32> = Vec::new();
+        for word in words.iter().rev() {
+            // multiply the limbs so far by 2^32 and add the next word
+            let mut carry = *word as u64;
+            for limb in limbs.iter_mut() {
+                let total = ((*limb as u64) << 32) + carry;
+                *limb = (total % BASE) as u32;
+                carry = total / BASE;
+            }
+            while carry > 0 {
+                limbs.push((carry % BASE) as u32);
+                carry /= BASE;
+            }
+        }
+        BigInt::from_parts(false, limbs)
+    }
+
+    // build an integer from its sign and digits, trimming the digits and never making zero negative
+    fn from_parts(negative: bool, mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigInt { negative: negative && !limbs.is_empty(), limbs }
+    }
+}
+impl Ord for BigInt {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        match (self.negative, rhs.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.limbs, &rhs.limbs),
+            (true, true) => compare_magnitudes(&rhs.limbs, &self.limbs),
+        }
+    }
+}
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, rhs: &Self) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+impl Display for BigInt { // allows for `println!()` and `.to_string()`
+
+    /// writes the integer in decimal, like `-12345678901234567890`
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the integer to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((top, rest)) = self.limbs.split_last() else {
+            return write!(f, "0");
+        };
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        // every limb below the top one is zero padded to its full 9 digits
+        write!(f, "{}", top)?;
+        for limb in rest.iter().rev() {
+            write!(f, "{:09}", limb)?;
+        }
+        Ok(())
+    }
+}
+
+// compare two magnitudes, neither of which has zero digits at the most significant end
+fn compare_magnitudes(lhs: &[u32], rhs: &[u32]) -> Ordering {
+    lhs.len().cmp(&rhs.len()).then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+// `lhs + rhs` for magnitudes
+fn add_magnitudes(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut sum = Vec::with_capacity(lhs.len().max(rhs.len()) + 1);
+    let mut carry = 0;
+    for i in 0..lhs.len().max(rhs.len()) {
+        let total = *lhs.get(i).unwrap_or(&0) as u64 + *rhs.get(i).unwrap_or(&0) as u64 + carry;
+        sum.push((total % BASE) as u32);
+        carry = total / BASE;
+    }
+    if carry > 0 {
+        sum.push(carry as u32);
+    }
+    sum
+}
+
+// `lhs - rhs` for magnitudes where `lhs >= rhs`
+fn subtract_magnitudes(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(lhs.len());
+    let mut borrow = 0;
+    for (i, limb) in lhs.iter().enumerate() {
+        let subtrahend = *rhs.get(i).unwrap_or(&0) as i64 + borrow;
+        let mut digit = *limb as i64 - subtrahend;
+        borrow = 0;
+        if digit < 0 {
+            digit += BASE as i64;
+            borrow = 1;
+        }
+        difference.push(digit as u32);
+    }
+    while difference.last() == Some(&0) {
+        difference.pop();
+    }
+    difference
+}
+
+// `lhs * rhs` for magnitudes, by long multiplication
+fn multiply_magnitudes(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    if lhs.is_empty() || rhs.is_empty() {
+        return Vec::new();
+    }
+
+    let mut product = vec![0u64; lhs.len() + rhs.len()];
+    for (i, &left) in lhs.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &right) in rhs.iter().enumerate() {
+            let total = product[i + j] + left as u64 * right as u64 + carry;
+            product[i + j] = total % BASE;
+            carry = total / BASE;
+        }
+        product[i + rhs.len()] += carry;
+    }
+
+    let mut product: Vec<u32> = product.into_iter().map(|limb| limb as u32).collect();
+    while product.last() == Some(&0) {
+        product.pop();
+    }
+    product
+}
+
+// the quotient and remainder of `lhs / rhs` for magnitudes where `rhs` isn't zero, by long division.
+// each digit of the quotient is found by binary search, which is slow for huge numbers but simple
+fn divide_magnitudes(lhs: &[u32], rhs: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0; lhs.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+
+    for i in (0..lhs.len()).rev() {
+        // bring down the next digit
+        remainder.insert(0, lhs[i]);
+        while remainder.last() == Some(&0) {
+            remainder.pop();
+        }
+
+        let (mut low, mut high) = (0, BASE as u32 - 1);
+        while low < high {
+            let middle = low + (high - low).div_ceil(2);
+            if compare_magnitudes(&multiply_magnitudes(rhs, &[middle]), &remainder) == Ordering::Greater {
+                high = middle - 1;
+            } else {
+                low = middle;
+            }
+        }
+
+        quotient[i] = low;
+        remainder = subtract_magnitudes(&remainder, &multiply_magnitudes(rhs, &[low]));
+    }
+
+    while quotient.last() == Some(&0) {
+        quotient.pop();
+    }
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // values on either side of each limb boundary, and the extremes of `i128`
+    const BOUNDARIES: &[i128] = &[
+        1,
+        2,
+        999_999_999,
+        1_000_000_000,
+        1_000_000_001,
+        999_999_999_999_999_999,
+        1_000_000_000_000_000_000,
+        1_000_000_000_000_000_001,
+        1_000_000_000_000_000_000_000_000_000,
+        i128::MAX,
+    ];
+
+    #[test]
+    fn div_rem_matches_i128_at_limb_boundaries() {
+        for &lhs in BOUNDARIES {
+            for &rhs in BOUNDARIES {
+                for (lhs, rhs) in [(lhs, rhs), (-lhs, rhs), (lhs, -rhs), (-lhs, -rhs)] {
+                    let (quotient, remainder) = BigInt::from_i128(lhs).div_rem(&BigInt::from_i128(rhs)).unwrap();
+                    assert_eq!(quotient, BigInt::from_i128(lhs / rhs), "{} / {}", lhs, rhs);
+                    assert_eq!(remainder, BigInt::from_i128(lhs % rhs), "{} % {}", lhs, rhs);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn divide_magnitudes_carries_across_limbs() {
+        // (10^18 - 1) / (10^9 - 1) is 10^9 + 1 exactly
+        assert_eq!(divide_magnitudes(&[999_999_999, 999_999_999], &[999_999_999]), (vec![1, 1], vec![]));
+        // 10^27 / (10^9 + 1) is 10^18 - 10^9, with 10^9 left over
+        let (quotient, remainder) = divide_magnitudes(&[0, 0, 0, 1], &[1, 1]);
+        assert_eq!(BigInt::from_parts(false, quotient).to_string(), "999999999000000000");
+        assert_eq!(BigInt::from_parts(false, remainder).to_string(), "1000000000");
+        // a divisor bigger than the dividend leaves it all as the remainder
+        assert_eq!(divide_magnitudes(&[5], &[0, 1]), (vec![], vec![5]));
+    }
+
+    #[test]
+    fn div_rem_beyond_i128() {
+        let lhs = BigInt::parse("123456789012345678901234567890123456789012345678901234567890").unwrap();
+        let rhs = BigInt::parse("1000000000000000000000000000001").unwrap();
+        let (quotient, remainder) = lhs.div_rem(&rhs).unwrap();
+        assert_eq!(quotient.mul(&rhs).add(&remainder), lhs);
+        assert!(remainder < rhs);
+    }
+
+    #[test]
+    fn div_rem_by_zero_is_none() {
+        assert_eq!(BigInt::from_i128(7).div_rem(&BigInt::from_i128(0)), None);
+        assert_eq!(BigInt::from_i128(7).div_rem_euclid(&BigInt::from_i128(0)), None);
+    }
+
+    #[test]
+    fn div_rem_euclid_matches_i128_for_every_sign() {
+        for (lhs, rhs) in [(7, 2), (-7, 2), (7, -2), (-7, -2), (6, 3), (-6, 3), (6, -3), (-6, -3), (-1, 1_000_000_001), (-1_000_000_001, -1_000_000_000)] {
+            let (quotient, remainder) = BigInt::from_i128(lhs).div_rem_euclid(&BigInt::from_i128(rhs)).unwrap();
+            assert_eq!(quotient, BigInt::from_i128(lhs.div_euclid(rhs)), "{} div_euclid {}", lhs, rhs);
+            assert_eq!(remainder, BigInt::from_i128(lhs.rem_euclid(rhs)), "{} rem_euclid {}", lhs, rhs);
+        }
+    }
+
+    #[test]
+    fn from_parts_trims_zero_limbs_and_never_makes_zero_negative() {
+        assert_eq!(BigInt::from_parts(true, vec![0, 0]), BigInt::from_i128(0));
+        assert!(!BigInt::from_parts(true, Vec::new()).is_negative());
+        assert_eq!(BigInt::from_parts(false, vec![5, 0, 0]).limbs, vec![5]);
+        assert_eq!(BigInt::from_parts(true, vec![5, 0]), BigInt::from_i128(-5));
+    }
+
+    #[test]
+    fn zero_results_are_not_negative() {
+        let five = BigInt::from_i128(5);
+        assert_eq!(BigInt::parse("-0").unwrap().to_string(), "0");
+        assert!(!five.sub(&five).is_negative());
+        assert!(!five.neg().mul(&BigInt::from_i128(0)).is_negative());
+        assert!(!BigInt::from_i128(-3).div_rem(&five).unwrap().0.is_negative());
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    iter::Peekable,
+    path::Path,
+    str::Chars,
+};
+
+use crate::{
+    bigint::BigInt,
+    complex::Complex,
+    date::Date,
+    decimal::Decimal,
+    environment::{Environment, ANSWER},
+    expression::Expression,
+    interval::Interval,
+    matrix::Matrix,
+    rational::Rational,
+    unit::{Dimension, Quantity, ShownUnit},
+    value::Value,
+};
+
+/// How far a batch run got, so an interrupted run can pick up where it left off.<br>
+/// It is saved as text: a `line` with the number of input lines already processed,
+/// an optional `output` with the length the `--output` file had at that point,
+/// then a line for each variable with its name, the kind of value it holds, and every part of that value,
+/// like `x fraction 1 3` or `d quantity 5000 0,1,0,0,0,0,0,0 1000 "km"`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    /// how many lines of input were processed
+    pub line: usize,
+    /// the length of the `--output` file in bytes after those lines, when there is one
+    pub output_length: Option<u64>,
+    /// every variable, including `ans`, after those lines
+    pub variables: Vec<(String, Value)>,
+}
+impl Checkpoint {
+    /// Records the state of a batch run after `line` lines of input
+    /// # Parameters
+    ///  - `line`: how many lines of input were processed
+    ///  - `output_length`: the length of the `--output` file, when there is one
+    ///  - `environment`: the variables after those lines
+    pub fn new(line: usize, output_length: Option<u64>, environment: &Environment) -> Self {
+        let variables = environment.iter().map(|(name, value)| (name.to_owned(), value)).collect();
+        Checkpoint { line, output_length, variables }
+    }
+
+    /// Reads the checkpoint saved at `path`
+    /// # Parameters
+    ///  - `path`: the checkpoint file
+    /// # Returns
+    ///  - `Ok(Some(checkpoint))`: when the file exists and is valid
+    ///  - `Ok(None)`: when there is no file, so the run starts from the beginning
+    ///  - `Err(load_error)`: when the file can't be read or isn't a checkpoint
+    pub fn load(path: &Path) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut lines = text.lines().peekable();
+        let line = lines
+            .next()
+            .and_then(|line| line.strip_prefix("line "))
+            .and_then(|line| line.parse().ok())
+            .ok_or("it doesn't start with `line <number>`")?;
+        let output_length = match lines.next_if(|line| line.starts_with("output ")) {
+            Some(output) => Some(output["output ".len()..].parse()?),
+            None => None,
+        };
+
+        let mut variables = Vec::new();
+        for variable in lines {
+            let invalid = |error: Box<dyn std::error::Error>| format!("invalid variable `{}`: {}", variable, error);
+            let mut parts = split_parts(variable).map_err(invalid)?.into_iter();
+            let name = parts.next().ok_or_else(|| invalid("it is empty".into()))?;
+            let value = read_value(&mut parts).map_err(invalid)?;
+            if parts.next().is_some() {
+                return Err(invalid("it has more parts than its value needs".into()).into());
+            }
+            variables.push((name, value));
+        }
+
+        Ok(Some(Checkpoint { line, output_length, variables }))
+    }
+
+    /// Writes the checkpoint to `path`.
+    /// It is written to a temporary file that then replaces `path`, so an interruption never leaves half a checkpoint
+    /// # Parameters
+    ///  - `path`: the checkpoint file
+    /// # Returns
+    ///  - `Ok(())`: when the checkpoint was written
+    ///  - `Err(save_error)`: when the file couldn't be written, or a variable holds an expression that wouldn't read back the same
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let mut text = format!("line {}\n", self.line);
+        if let Some(output_length) = self.output_length {
+            text.push_str(&format!("output {}\n", output_length));
+        }
+        for (name, value) in &self.variables {
+            text.push_str(name);
+            write_value(value, &mut text).map_err(|error| format!("can't save `{}`: {}", name, error))?;
+            text.push('\n');
+        }
+
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, text)?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    /// Puts the saved variables back into `environment`
+    /// # Parameters
+    ///  - `environment`: where the variables are assigned
+    /// # Returns
+    ///  - `Ok(())`: when every variable was assigned
+    ///  - `Err(assignment_error)`: when a variable names a builtin, which only happens if the file was edited
+    pub fn restore(&self, environment: &mut Environment) -> Result<(), Box<dyn std::error::Error>> {
+        for (name, value) in &self.variables {
+            if name == ANSWER {
+                environment.set_answer(value.clone());
+            } else {
+                environment.assign(name, value.clone())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// write each part of `value` after a space, starting with its kind.
+// `f64`'s `Display` writes the shortest text that parses back to the same number, so no float is changed,
+// and text that can hold spaces, like the name of a unit, is quoted
+fn write_value(value: &Value, text: &mut String) -> Result<(), Box<dyn std::error::Error>> {
+    match value {
+        Value::Real(number) => text.push_str(&format!(" real {}", number)),
+        Value::Integer(integer) => text.push_str(&format!(" integer {}", integer)),
+        Value::Rational(fraction) => text.push_str(&format!(" fraction {} {}", fraction.numerator(), fraction.denominator())),
+        Value::Ratio(ratio) => text.push_str(&format!(" ratio {} {}", ratio.numerator(), ratio.denominator())),
+        Value::Decimal(decimal) => text.push_str(&format!(" decimal {}", decimal)),
+        Value::Interval(interval) => text.push_str(&format!(" interval {} {}", interval.bounds().0, interval.bounds().1)),
+        Value::Complex(z) => text.push_str(&format!(" complex {} {}", z.re, z.im)),
+        Value::Quantity(quantity) => {
+            let powers: Vec<String> = quantity.dimension().powers().iter().map(i8::to_string).collect();
+            text.push_str(&format!(" quantity {} {}", quantity.value(), powers.join(",")));
+            match quantity.unit() {
+                Some(unit) => text.push_str(&format!(" {} {:?}", unit.size, unit.name)),
+                None => text.push_str(" none"),
+            }
+        },
+        Value::Date(date) => text.push_str(&format!(" date {}", date.seconds())),
+        Value::Matrix(matrix) => {
+            text.push_str(&format!(" matrix {} {}", matrix.rows(), matrix.columns()));
+            for element in matrix.elements() {
+                text.push_str(&format!(" {}", element));
+            }
+        },
+        Value::Expression(expression) => {
+            // an expression is saved as the text it is shown as, so it has to parse back to the same expression
+            let shown = expression.to_string();
+            if shown.parse::<Expression>().map(|parsed| parsed.to_string()).ok().as_ref() != Some(&shown) {
+                return Err(format!("the expression `{}` doesn't read back the same", shown).into());
+            }
+            text.push_str(&format!(" expression {:?}", shown));
+        },
+        Value::List(values) => {
+            text.push_str(&format!(" list {}", values.len()));
+            for value in values {
+                write_value(value, text)?;
+            }
+        },
+    }
+    Ok(())
+}
+
+// read a value written by `write_value` from its parts
+fn read_value(parts: &mut impl Iterator<Item = String>) -> Result<Value, Box<dyn std::error::Error>> {
+    let kind = parts.next().ok_or("it has no value")?;
+    let value = match kind.as_str() {
+        "real" => Value::Real(next_part(parts)?.parse()?),
+        "integer" => Value::Integer(BigInt::parse(&next_part(parts)?).ok_or("invalid integer")?),
+        "fraction" => Value::Rational(Rational::new(next_part(parts)?.parse()?, next_part(parts)?.parse()?).ok_or("invalid fraction")?),
+        "ratio" => Value::Ratio(Rational::new(next_part(parts)?.parse()?, next_part(parts)?.parse()?).ok_or("invalid ratio")?),
+        "decimal" => Value::Decimal(Decimal::parse(&next_part(parts)?).ok_or("invalid decimal")?),
+        "interval" => Value::Interval(Interval::new(next_part(parts)?.parse()?, next_part(parts)?.parse()?)),
+        "complex" => Value::Complex(Complex::new(next_part(parts)?.parse()?, next_part(parts)?.parse()?)),
+        "quantity" => {
+            let value = next_part(parts)?.parse()?;
+            let powers: Vec<i8> = next_part(parts)?.split(',').map(str::parse).collect::<Result<_, _>>()?;
+            let powers = powers.try_into().map_err(|_| "a quantity needs the power of each of the 8 base units")?;
+            let unit = match next_part(parts)?.as_str() {
+                "none" => None,
+                size => Some(ShownUnit { size: size.parse()?, name: next_part(parts)? }),
+            };
+            Value::Quantity(Quantity::new(value, Dimension::from_powers(powers), unit))
+        },
+        "date" => Value::Date(Date::from_seconds(next_part(parts)?.parse()?)),
+        "matrix" => {
+            let (rows, columns): (usize, usize) = (next_part(parts)?.parse()?, next_part(parts)?.parse()?);
+            if rows == 0 || columns == 0 {
+                return Err("a matrix needs at least 1 row and column".into());
+            }
+            let elements = (0..rows * columns).map(|_| Ok(next_part(parts)?.parse()?)).collect::<Result<_, Box<dyn std::error::Error>>>()?;
+            Value::Matrix(Matrix::new(columns, elements))
+        },
+        "expression" => Value::Expression(next_part(parts)?.parse()?),
+        "list" => {
+            let length: usize = next_part(parts)?.parse()?;
+            Value::List((0..length).map(|_| read_value(parts)).collect::<Result<_, _>>()?)
+        },
+        _ => return Err(format!("unknown kind of value `{}`", kind).into()),
+    };
+    Ok(value)
+}
+
+// the next part of a value, which every kind of value needs a fixed number of
+fn next_part(parts: &mut impl Iterator<Item = String>) -> Result<String, Box<dyn std::error::Error>> {
+    parts.next().ok_or_else(|| "a value is missing a part".into())
+}
+
+// split a variable line into its parts at spaces, reading a part in `"` quotes, with `\` escapes, as the text inside
+fn split_parts(line: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut characters = line.chars().peekable();
+    let mut parts = Vec::new();
+    while characters.peek().is_some() {
+        if characters.next_if_eq(&' ').is_some() {
+            continue;
+        }
+        if characters.next_if_eq(&'"').is_some() {
+            parts.push(read_quoted(&mut characters)?);
+        } else {
+            parts.push(std::iter::from_fn(|| characters.next_if(|character| *character != ' ')).collect());
+        }
+    }
+    Ok(parts)
+}
+
+// the text of a part written with `{:?}`, after its opening `"`
+fn read_quoted(characters: &mut Peekable<Chars>) -> Result<String, Box<dyn std::error::Error>> {
+    let mut text = String::new();
+    loop {
+        match characters.next().ok_or("unterminated quote")? {
+            '"' => return Ok(text),
+            '\\' => match characters.next().ok_or("unterminated quote")? {
+                'n' => text.push('\n'),
+                't' => text.push('\t'),
+                'r' => text.push('\r'),
+                '0' => text.push('\0'),
+                'u' => {
+                    let code: String = characters.by_ref().skip(1).take_while(|character| *character != '}').collect();
+                    let code = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32).ok_or("invalid escape")?;
+                    text.push(code);
+                },
+                character => text.push(character),
+            },
+            character => text.push(character),
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt::Display;
+
+use crate::value::Value;
+
+/// A complex number `re + im·i`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complex {
+    /// the real part
+    pub re: f64,
+    /// the imaginary part
+    pub im: f64,
+}
+impl Complex {
+    /// Creates the complex number `re + im·i`
+    pub fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    /// The distance from zero, `|z|`
+    pub fn abs(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// The angle from the positive real axis in radians, between `-pi` and `pi`
+    pub fn arg(&self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    /// The complex conjugate `re - im·i`
+    pub fn conj(&self) -> Complex {
+        Complex::new(self.re, -self.im)
+    }
+
+    /// `self + rhs`
+    pub fn add(&self, rhs: Complex) -> Complex {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+
+    /// `self - rhs`
+    pub fn sub(&self, rhs: Complex) -> Complex {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+
+    /// `self * rhs`
+    pub fn mul(&self, rhs: Complex) -> Complex {
+        Complex::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+
+    /// `self / rhs`
+    /// # Returns
+    ///  - `Ok(quotient)`: the quotient
+    ///  - `Err(division_error)`: when `rhs` is zero
+    pub fn div(&self, rhs: Complex) -> Result<Complex, Box<dyn std::error::Error>> {
+        let denominator = rhs.re * rhs.re + rhs.im * rhs.im;
+        if denominator == 0.0 {
+            return Err("Divide by zero error".into());
+        }
+        Ok(Complex::new(
+            (self.re * rhs.re + self.im * rhs.im) / denominator,
+            (self.im * rhs.re - self.re * rhs.im) / denominator,
+        ))
+    }
+
+    /// `self ^ rhs`, using the principal branch of the logarithm
+    pub fn pow(&self, rhs: Complex) -> Complex {
+        // whole powers are repeated multiplication, which keeps results like i ^ 2 = -1 free of rounding noise
+        if rhs.im == 0.0 && rhs.re.fract() == 0.0 && rhs.re.abs() <= 64.0 {
+            let mut result = Complex::new(1.0, 0.0);
+            for _ in 0..rhs.re.abs() as u32 {
+                result = result.mul(*self);
+            }
+            return if rhs.re < 0.0 { Complex::new(1.0, 0.0).div(result).unwrap_or(Complex::new(f64::NAN, f64::NAN)) } else { result };
+        }
+
+        // a half power is a whole power of the square root, so (-1) ^ 0.5 is exactly i
+        if rhs.im == 0.0 && (rhs.re * 2.0).fract() == 0.0 && (rhs.re * 2.0).abs() <= 64.0 {
+            return self.sqrt().pow(Complex::new(rhs.re * 2.0, 0.0));
+        }
+
+        if self.re == 0.0 && self.im == 0.0 {
+            return Complex::new(0.0, 0.0);
+        }
+        self.ln().mul(rhs).exp()
+    }
+
+    /// The principal square root, whose real part is never negative
+    pub fn sqrt(&self) -> Complex {
+        let magnitude = self.abs();
+        let re = ((magnitude + self.re) / 2.0).sqrt();
+        let im = ((magnitude - self.re) / 2.0).sqrt();
+        Complex::new(re, if self.im < 0.0 { -im } else { im })
+    }
+
+    /// `e ^ self`
+    pub fn exp(&self) -> Complex {
+        let magnitude = self.re.exp();
+        Complex::new(magnitude * self.im.cos(), magnitude * self.im.sin())
+    }
+
+    /// The principal natural logarithm, whose imaginary part is between `-pi` and `pi`
+    pub fn ln(&self) -> Complex {
+        Complex::new(self.abs().ln(), self.arg())
+    }
+
+    /// The sine of `self`
+    pub fn sin(&self) -> Complex {
+        Complex::new(self.re.sin() * self.im.cosh(), self.re.cos() * self.im.sinh())
+    }
+
+    /// The cosine of `self`
+    pub fn cos(&self) -> Complex {
+        Complex::new(self.re.cos() * self.im.cosh(), -self.re.sin() * self.im.sinh())
+    }
+}
+impl From<f64> for Complex {
+    fn from(re: f64) -> Self {
+        Complex::new(re, 0.0)
+    }
+}
+impl Display for Complex { // allows for `println!()` and `.to_string()`
+
+    /// writes the number in rectangular form like `3+4i`, `-2i`, or `1-i`, leaving out a zero imaginary part
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the number to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.im.is_sign_negative() { "-" } else { "+" };
+        let im = self.im.abs();
+        let im = if im == 1.0 { String::new() } else { im.to_string() };
+        if self.im == 0.0 {
+            write!(f, "{}", self.re)
+        } else if self.re == 0.0 {
+            write!(f, "{}{}i", if sign == "-" { "-" } else { "" }, im)
+        } else {
+            write!(f, "{}{}{}i", self.re, sign, im)
+        }
+    }
+}
+
+/// Writes `z` in polar form like `5∠53.13010235415598°`, its magnitude then its angle in degrees
+pub fn format_polar(z: Complex) -> String {
+    format!("{}∠{}°", z.abs(), z.arg().to_degrees())
+}
+
+/// Computes the result of a builtin function on complex arguments
+pub type ComplexImplementation = fn(&[Complex]) -> Result<Value, Box<dyn std::error::Error>>;
+
+/// A builtin function that accepts complex arguments.<br>
+/// When a function is called with a complex argument it is looked up here instead of in `FUNCTIONS`
+pub struct ComplexFunction {
+    /// what the user types before the `(`
+    pub name: &'static str,
+    /// computes the result from a single argument
+    pub call: ComplexImplementation,
+    /// whether the result is always exact, like taking the real part, rather than rounded
+    pub exact: bool,
+}
+
+/// Every builtin function that works on complex numbers. They all take exactly 1 argument
+pub const COMPLEX_FUNCTIONS: &[ComplexFunction] = &[
+    ComplexFunction { name: "sqrt", call: |arguments| Ok(Value::from(arguments[0].sqrt())), exact: false },
+    ComplexFunction { name: "exp", call: |arguments| Ok(Value::from(arguments[0].exp())), exact: false },
+    ComplexFunction {
+        name: "ln",
+        call: |arguments| match arguments[0] {
+            z if z.re == 0.0 && z.im == 0.0 => Err("ln is undefined for 0".into()),
+            z => Ok(Value::from(z.ln())),
+        },
+        exact: false,
+    },
+    ComplexFunction { name: "sin", call: |arguments| Ok(Value::from(arguments[0].sin())), exact: false },
+    ComplexFunction { name: "cos", call: |arguments| Ok(Value::from(arguments[0].cos())), exact: false },
+    ComplexFunction { name: "tan", call: |arguments| Ok(Value::from(arguments[0].sin().div(arguments[0].cos())?)), exact: false },
+    ComplexFunction { name: "abs", call: |arguments| Ok(Value::Real(arguments[0].abs())), exact: false },
+    ComplexFunction { name: "arg", call: |arguments| Ok(Value::Real(arguments[0].arg())), exact: false },
+    ComplexFunction { name: "re", call: |arguments| Ok(Value::Real(arguments[0].re)), exact: true },
+    ComplexFunction { name: "im", call: |arguments| Ok(Value::Real(arguments[0].im)), exact: true },
+    ComplexFunction { name: "conj", call: |arguments| Ok(Value::from(arguments[0].conj())), exact: true },
+];
+
+/// Looks up a builtin function that works on complex numbers by name
+/// # Parameters
+///  - `name`: the name the user typed
+/// # Returns
+///  - `Some(function)`: when there is a complex builtin called `name`
+///  - `None`: when there is no such builtin
+pub fn find_complex_function(name: &str) -> Option<&'static ComplexFunction> {
+    COMPLEX_FUNCTIONS.iter().find(|function| function.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        environment::Environment,
+        expression::Expression,
+    };
+
+    use super::*;
+
+    #[test]
+    fn whole_and_half_powers_are_free_of_rounding_noise() {
+        assert_eq!(Complex::new(0.0, 1.0).pow(Complex::from(2.0)), Complex::from(-1.0));
+        assert_eq!(Complex::from(-1.0).pow(Complex::from(0.5)), Complex::new(0.0, 1.0));
+        assert_eq!(Complex::from(-4.0).pow(Complex::from(-0.5)), Complex::new(0.0, -0.5));
+        assert_eq!(Complex::from(-1.0).pow(Complex::from(1.5)), Complex::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn other_powers_use_the_principal_branch() {
+        let root = Complex::from(-8.0).pow(Complex::from(1.0 / 3.0));
+        assert!((root.re - 1.0).abs() < 1e-12 && (root.im - 3f64.sqrt()).abs() < 1e-12, "{}", root);
+    }
+
+    #[test]
+    fn negative_numbers_to_fractional_powers_are_complex() {
+        let evaluate = |input: &str| input.parse::<Expression>().unwrap().evaluate(&Environment::new()).unwrap().to_string();
+        assert_eq!(evaluate("(-1) ^ 0.5"), "i");
+        assert_eq!(evaluate("(-4) ^ 0.5"), evaluate("sqrt(-4)"));
+        assert_eq!(evaluate("(-2) ^ 2"), "4");
+        assert_eq!(evaluate("(-2) ^ -1"), "-0.5");
+    }
+}
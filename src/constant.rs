@@ -0,0 +1,24 @@
+/// A named mathematical constant like `pi`
+pub struct Constant {
+    /// what the user types
+    pub name: &'static str,
+    /// the closest `f64` to the constant
+    pub value: f64,
+}
+
+/// Every builtin constant
+pub const CONSTANTS: &[Constant] = &[
+    Constant { name: "pi", value: std::f64::consts::PI },
+    Constant { name: "e", value: std::f64::consts::E },
+    Constant { name: "tau", value: std::f64::consts::TAU },
+];
+
+/// Looks up a builtin constant by name
+/// # Parameters
+///  - `name`: the name the user typed
+/// # Returns
+///  - `Some(constant)`: when there is a builtin constant called `name`
+///  - `None`: when there is no such constant
+pub fn find_constant(name: &str) -> Option<&'static Constant> {
+    CONSTANTS.iter().find(|constant| constant.name == name)
+}
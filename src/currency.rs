@@ -0,0 +1,117 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+};
+
+use crate::{
+    unit::{Dimension, Quantity, ShownUnit},
+    variables_file::{self, Entry},
+};
+
+/// The currency money is counted in, which every exchange rate is relative to
+pub const BASE_CURRENCY: &str = "USD";
+
+/// Whether `name` looks like a currency code, three capital letters like `EUR`.<br>
+/// The parser uses this to read `100 EUR / 2 h` as money per time before any rates are known
+pub fn is_currency_code(name: &str) -> bool {
+    name.len() == 3 && name.bytes().all(|letter| letter.is_ascii_uppercase())
+}
+
+/// One of the currency `code`, which is worth `1 / rate` US dollars
+/// # Parameters
+///  - `code`: the currency as the user typed it, like `EUR`
+///  - `rate`: how much of the currency one US dollar buys
+pub fn currency(code: &str, rate: f64) -> Quantity {
+    let size = 1.0 / rate;
+    Quantity::new(size, Dimension::MONEY, Some(ShownUnit { name: code.to_owned(), size }))
+}
+
+/// Reads exchange rates from a TOML or JSON file saying how much of each currency one of a base currency buys,
+/// like `EUR = 0.92`, or `{"base": "USD", "rates": {"EUR": 0.92}}` as many rate services send them.<br>
+/// Only the last part of a nested key is used, and keys that aren't currency codes are skipped.
+/// A top level `base` names the currency the rates are based on, which is `USD` when it is left out.
+/// Every rate is rescaled against the rate for `USD`, so a file based on another currency has to have one
+/// # Parameters
+///  - `path`: the file to read. A `.json` file, or one whose text starts with `{`, is read as JSON, anything else as TOML
+/// # Returns
+///  - `Ok(rates)`: every currency in the file and how much of it one US dollar buys
+///  - `Err(load_error)`: when the file can't be read, isn't valid, has no rates, has a rate that isn't positive,
+///    or is based on another currency without a rate for `USD`
+pub fn load_rates(path: &Path) -> Result<BTreeMap<String, f64>, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path)?;
+    let is_json = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    parse_rates(&text, is_json)
+}
+
+/// Downloads exchange rates in the JSON format `load_rates` reads.
+/// Only plain `http://` addresses work, because the calculator has no TLS library
+/// # Parameters
+///  - `url`: where to download the rates from, like `http://localhost:8000/rates.json`
+/// # Returns
+///  - `Ok(rates)`: every currency in the response and how much of it one US dollar buys
+///  - `Err(download_error)`: when the address can't be reached, doesn't answer `200`, or doesn't send valid rates
+#[cfg(feature = "online-rates")]
+pub fn fetch_rates(url: &str) -> Result<BTreeMap<String, f64>, Box<dyn std::error::Error>> {
+    use std::io::{Read, Write};
+
+    let address = url.strip_prefix("http://").ok_or("only http:// addresses are supported")?;
+    let (host, path) = match address.split_once('/') {
+        Some((host, path)) => (host, format!("/{}", path)),
+        None => (address, "/".to_owned()),
+    };
+    let authority = if host.contains(':') { host.to_owned() } else { format!("{}:80", host) };
+
+    let mut stream = std::net::TcpStream::connect(authority)?;
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(10)))?;
+    // HTTP/1.0 makes the server close the connection after a plain body, so there is no chunked encoding to undo
+    write!(stream, "GET {} HTTP/1.0\r\nHost: {}\r\nAccept: application/json\r\n\r\n", path, host)?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response.split_once("\r\n\r\n").ok_or("the response has no body")?;
+    let status = head.lines().next().unwrap_or_default();
+    if status.split(' ').nth(1) != Some("200") {
+        return Err(format!("the server answered `{}`", status).into());
+    }
+    parse_rates(body, true)
+}
+
+// the currency rates in a TOML or JSON document, rescaled so one US dollar is `1`
+fn parse_rates(text: &str, is_json: bool) -> Result<BTreeMap<String, f64>, Box<dyn std::error::Error>> {
+    let mut base = BASE_CURRENCY.to_owned();
+    let mut rates = BTreeMap::new();
+    for (key, entry) in variables_file::parse_entries(text, is_json)? {
+        let rate = match entry {
+            Entry::Text(code) if key == "base" => {
+                if !is_currency_code(&code) {
+                    return Err(format!("the base has to be a currency code like `EUR`, but is `{}`", code).into());
+                }
+                base = code;
+                continue;
+            },
+            Entry::Text(_) => continue,
+            Entry::Number(rate) => rate,
+        };
+        let code = key.rsplit('_').next().unwrap_or_default();
+        if !is_currency_code(code) {
+            continue;
+        }
+        if !(rate.is_finite() && rate > 0.0) {
+            return Err(format!("the rate of {} has to be a positive number, but is {}", code, rate).into());
+        }
+        rates.insert(code.to_owned(), rate);
+    }
+
+    if rates.is_empty() {
+        return Err("it doesn't have any rates, like `EUR = 0.92`".into());
+    }
+    // one of the base currency buys one of itself, whether or not the file says so
+    rates.entry(base.clone()).or_insert(1.0);
+    let Some(dollar) = rates.get(BASE_CURRENCY).copied() else {
+        return Err(format!("the rates are based on {}, so they need a rate for {} to be converted", base, BASE_CURRENCY).into());
+    };
+    for rate in rates.values_mut() {
+        *rate /= dollar;
+    }
+    Ok(rates)
+}
@@ -0,0 +1,184 @@
+use std::{
+    fmt::Display,
+    time::SystemTime,
+};
+
+use crate::unit::{Quantity, ShownUnit, TIME};
+
+/// The name of the current moment, as in `now - 1990-07-14`
+pub const NOW: &str = "now";
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// A moment in time, counted in seconds since the start of `1970-01-01` in UTC, like the date `2024-03-01` or `now`
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Date {
+    seconds: f64,
+}
+impl Date {
+    /// Creates the moment `seconds` after the start of `1970-01-01` in UTC
+    pub fn from_seconds(seconds: f64) -> Self {
+        Date { seconds }
+    }
+
+    /// The current moment, from the system clock
+    pub fn now() -> Self {
+        // a clock set before 1970 is behind the epoch by however long `duration` says
+        let seconds = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs_f64(),
+            Err(error) => -error.duration().as_secs_f64(),
+        };
+        Date::from_seconds(seconds)
+    }
+
+    /// Parses a date literal written `YYYY-MM-DD`, meaning the start of that day in UTC
+    /// # Parameters
+    ///  - `literal`: the date, with a 4 digit year and 2 digit month and day
+    /// # Returns
+    ///  - `Ok(date)`: the start of the day
+    ///  - `Err(date_error)`: when `literal` isn't written `YYYY-MM-DD`, or there is no such day, like `2023-02-29`
+    pub fn parse(literal: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let parts: Vec<&str> = literal.split('-').collect();
+        let [year, month, day] = parts.as_slice() else {
+            return Err("a date is written YYYY-MM-DD".into());
+        };
+        let (year, month, day): (i64, u32, u32) = (year.parse()?, month.parse()?, day.parse()?);
+
+        if !(1..=12).contains(&month) {
+            return Err(format!("there is no month {}", month).into());
+        }
+        if day == 0 || day > days_in_month(year, month) {
+            return Err(format!("month {} of {} has {} days", month, year, days_in_month(year, month)).into());
+        }
+        Ok(Date::from_seconds(days_from_civil(year, month, day) as f64 * SECONDS_PER_DAY))
+    }
+
+    /// How many seconds after the start of `1970-01-01` in UTC the moment is
+    pub fn seconds(&self) -> f64 {
+        self.seconds
+    }
+}
+impl Display for Date { // allows for `println!()` and `.to_string()`
+
+    /// writes the date like `2024-04-15`, followed by the time in UTC like `2024-04-15 13:45:07` when it isn't the start of a day
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the date to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.seconds.is_finite() {
+            return write!(f, "{} seconds from 1970-01-01", self.seconds);
+        }
+
+        let days = (self.seconds / SECONDS_PER_DAY).floor();
+        let (year, month, day) = civil_from_days(days as i64);
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)?;
+
+        // the time of day is shown to the whole second
+        let time = (self.seconds - days * SECONDS_PER_DAY).floor() as u32;
+        if time != 0 {
+            write!(f, " {:02}:{:02}:{:02}", time / 3600, time / 60 % 60, time % 60)?;
+        }
+        Ok(())
+    }
+}
+
+/// A length of time in seconds, shown in days like the difference of two dates
+pub fn duration(seconds: f64) -> Quantity {
+    Quantity::new(seconds, TIME, Some(ShownUnit { name: "days".to_owned(), size: SECONDS_PER_DAY }))
+}
+
+/// A length of time in seconds, shown in the largest of seconds, minutes, hours, or days that it is at least one of,
+/// and rounded to 3 significant digits of that unit, so 90 seconds is `1.5 min` and 536.87 seconds is `8.95 min`.
+/// Whole numbers of days with more digits than that are only rounded to the day
+pub fn readable_duration(seconds: f64) -> Quantity {
+    const SIGNIFICANT_DIGITS: i32 = 3;
+
+    let (name, size) = match seconds.abs() {
+        seconds if seconds >= SECONDS_PER_DAY => ("days", SECONDS_PER_DAY),
+        seconds if seconds >= 3600.0 => ("h", 3600.0),
+        seconds if seconds >= 60.0 => ("min", 60.0),
+        _ => ("s", 1.0),
+    };
+    let amount = seconds / size;
+    let rounded = match amount {
+        0.0 => amount,
+        amount if amount.is_finite() => {
+            let decimals = (SIGNIFICANT_DIGITS - 1 - amount.abs().log10().floor() as i32).max(0);
+            let scale = 10f64.powi(decimals);
+            (amount * scale).round() / scale
+        },
+        amount => amount,
+    };
+    Quantity::new(rounded * size, TIME, Some(ShownUnit { name: name.to_owned(), size }))
+}
+
+// whether `year` has a February 29th in the Gregorian calendar
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+// how many days `month` of `year` has
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// how many days `year-month-day` is after `1970-01-01`, from Howard Hinnant's `days_from_civil`.
+// the year is counted from March, so the leap day is the last day of its year
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_from_march = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// the `(year, month, day)` that is `days` after `1970-01-01`, the inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 } as u32;
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        environment::Environment,
+        expression::Expression,
+    };
+
+    use super::*;
+
+    #[test]
+    fn durations_are_rounded_in_the_largest_unit() {
+        assert_eq!(readable_duration(90.0).to_string(), "1.5 min");
+        assert_eq!(readable_duration(536.870912).to_string(), "8.95 min");
+        assert_eq!(readable_duration(0.00266666).to_string(), "0.00267 s");
+        assert_eq!(readable_duration(2.0 * 3600.0 + 1.0).to_string(), "2 h");
+        assert_eq!(readable_duration(12345.4 * SECONDS_PER_DAY).to_string(), "12345 days");
+        assert_eq!(readable_duration(0.0).to_string(), "0 s");
+    }
+
+    #[test]
+    fn transfer_times_are_readable() {
+        let evaluate = |input: &str| input.parse::<Expression>().unwrap().evaluate(&Environment::new()).unwrap().to_string();
+        assert_eq!(evaluate("transfer_time(2.5 GiB, 40 Mbit/s)"), "8.95 min");
+        assert_eq!(evaluate("transfer_time(2.5 GiB, 40 Mbit/s) in s"), "537 s");
+        assert_eq!(evaluate("1.08 min"), "1.08 min");
+    }
+}
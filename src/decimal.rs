@@ -0,0 +1,262 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+};
+
+use crate::bigint::{BigInt, MAX_EXACT_DIGITS};
+
+/// How many significant digits a quotient that doesn't end, like `1/3`, is worked out to
+pub const DIVISION_DIGITS: usize = 50;
+
+/// An exact base 10 number `coefficient / 10^scale` of any size, so `0.1 + 0.2` is exactly `0.3`.<br>
+/// It is kept without trailing zeros, so equal numbers always have equal parts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decimal {
+    coefficient: BigInt,
+    scale: u32,
+}
+impl Decimal {
+    /// Creates the decimal with the same digits as `number`, so `0.1` is exactly one tenth rather than the
+    /// binary fraction an `f64` rounds it to. This relies on `f64`'s `Display` writing the shortest decimal that parses back to `number`
+    /// # Returns
+    ///  - `Some(decimal)`: the decimal value of `number`
+    ///  - `None`: when `number` isn't finite
+    pub fn from_f64(number: f64) -> Option<Self> {
+        if !number.is_finite() {
+            return None;
+        }
+        Decimal::parse(&number.to_string())
+    }
+
+    /// Creates the decimal written in plain notation as `text`, like `-1234.5`, which is how a decimal is shown
+    /// # Returns
+    ///  - `Some(decimal)`: the decimal `text` stands for
+    ///  - `None`: when `text` isn't digits with an optional `-` and `.`
+    pub fn parse(text: &str) -> Option<Self> {
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        if !fraction.bytes().all(|digit| digit.is_ascii_digit()) {
+            return None;
+        }
+        let coefficient = BigInt::parse(&format!("{}{}", whole, fraction))?;
+        Some(Decimal::new(coefficient, fraction.len() as u32))
+    }
+
+    /// Creates the decimal equal to the whole number `integer`
+    pub fn integer(integer: BigInt) -> Self {
+        Decimal::new(integer, 0)
+    }
+
+    /// Whether the decimal is zero
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.is_zero()
+    }
+
+    /// Whether the decimal is a whole number
+    pub fn is_integer(&self) -> bool {
+        self.scale == 0
+    }
+
+    /// The nearest `f64` to the decimal
+    pub fn to_f64(&self) -> f64 {
+        // parsing the decimal text rounds correctly
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// `-self`
+    pub fn neg(&self) -> Self {
+        Decimal { coefficient: self.coefficient.neg(), scale: self.scale }
+    }
+
+    /// `self + rhs`
+    pub fn add(&self, rhs: &Decimal) -> Self {
+        let (lhs, rhs, scale) = align(self, rhs);
+        Decimal::new(lhs.add(&rhs), scale)
+    }
+
+    /// `self - rhs`
+    pub fn sub(&self, rhs: &Decimal) -> Self {
+        self.add(&rhs.neg())
+    }
+
+    /// `self * rhs`
+    pub fn mul(&self, rhs: &Decimal) -> Self {
+        Decimal::new(self.coefficient.mul(&rhs.coefficient), self.scale + rhs.scale)
+    }
+
+    /// `self / rhs`, rounded to `DIVISION_DIGITS` significant digits when the quotient doesn't end
+    /// # Returns
+    ///  - `Some((quotient, exact))`: the quotient, and whether it needed no rounding
+    ///  - `None`: when `rhs` is zero
+    pub fn div(&self, rhs: &Decimal) -> Option<(Self, bool)> {
+        if rhs.is_zero() {
+            return None;
+        }
+
+        // `(a / 10^s) / (b / 10^t)` is `(a * 10^t) / (b * 10^s)`, then `extra` more digits are divided out for the fraction.
+        // the magnitudes are divided so rounding doesn't depend on the signs
+        let negative = self.coefficient.is_negative() != rhs.coefficient.is_negative();
+        let dividend = self.coefficient.abs().mul_pow10(rhs.scale);
+        let divisor = rhs.coefficient.abs().mul_pow10(self.scale);
+        let extra = (DIVISION_DIGITS + divisor.digit_count()).saturating_sub(dividend.digit_count()) as u32;
+
+        let (mut quotient, remainder) = dividend.mul_pow10(extra).div_rem(&divisor)?;
+        let exact = remainder.is_zero();
+        // round half away from zero, by comparing twice the remainder to the divisor
+        if remainder.add(&remainder) >= divisor {
+            quotient = quotient.add(&BigInt::from_i128(1));
+        }
+
+        let quotient = if negative { quotient.neg() } else { quotient };
+        Some((Decimal::new(quotient, extra), exact))
+    }
+
+    /// `self ^ exponent` for a whole `exponent`
+    /// # Returns
+    ///  - `Some((power, exact))`: the power, and whether it needed no rounding, which only a negative exponent can need
+    ///  - `None`: when `exponent` isn't whole, the power would be too big, or `self` is zero and `exponent` is negative
+    pub fn pow(&self, exponent: &Decimal) -> Option<(Self, bool)> {
+        if !exponent.is_integer() {
+            return None;
+        }
+        let power = u32::try_from(exponent.coefficient.to_i128()?.unsigned_abs()).ok()?;
+        if self.coefficient.digit_count().saturating_mul(power as usize) > MAX_EXACT_DIGITS {
+            return None;
+        }
+
+        let result = Decimal::new(self.coefficient.pow(power), self.scale.checked_mul(power)?);
+        if exponent.coefficient.is_negative() {
+            Decimal::integer(BigInt::from_i128(1)).div(&result)
+        } else {
+            Some((result, true))
+        }
+    }
+
+    /// The Euclidean quotient and remainder of `self / rhs`, matching `f64::div_euclid` and `f64::rem_euclid`
+    /// # Returns
+    ///  - `Some((quotient, remainder))`: where the remainder is always between `0` and `|rhs|`
+    ///  - `None`: when `rhs` is zero
+    pub fn div_rem_euclid(&self, rhs: &Decimal) -> Option<(Self, Self)> {
+        let (lhs, rhs, scale) = align(self, rhs);
+        let (quotient, remainder) = lhs.div_rem_euclid(&rhs)?;
+        Some((Decimal::new(quotient, 0), Decimal::new(remainder, scale)))
+    }
+
+    // build a decimal from its parts, removing trailing zeros
+    fn new(mut coefficient: BigInt, mut scale: u32) -> Self {
+        let ten = BigInt::from_i128(10);
+        while scale > 0 {
+            match coefficient.div_rem(&ten) {
+                Some((quotient, remainder)) if remainder.is_zero() => {
+                    coefficient = quotient;
+                    scale -= 1;
+                },
+                _ => break,
+            }
+        }
+        Decimal { coefficient, scale }
+    }
+}
+impl Ord for Decimal {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        let (lhs, rhs, _) = align(self, rhs);
+        lhs.cmp(&rhs)
+    }
+}
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, rhs: &Self) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+impl Display for Decimal { // allows for `println!()` and `.to_string()`
+
+    /// writes the decimal in plain notation, like `0.3` or `-1234.5`
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the decimal to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self.coefficient.to_string();
+        let (sign, digits) = match digits.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", digits.as_str()),
+        };
+
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        // a number below 1 needs zeros between the point and its first digit
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, whole, fraction)
+    }
+}
+
+// the coefficients of `lhs` and `rhs` scaled to a shared number of decimal places, and that number
+fn align(lhs: &Decimal, rhs: &Decimal) -> (BigInt, BigInt, u32) {
+    let scale = lhs.scale.max(rhs.scale);
+    (
+        lhs.coefficient.mul_pow10(scale - lhs.scale),
+        rhs.coefficient.mul_pow10(scale - rhs.scale),
+        scale,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(text: &str) -> Decimal {
+        Decimal::parse(text).unwrap()
+    }
+
+    #[test]
+    fn parse_drops_trailing_zeros() {
+        assert_eq!(decimal("1.500"), decimal("1.5"));
+        assert_eq!(decimal("2.000"), Decimal::integer(BigInt::from_i128(2)));
+        assert!(decimal("2.000").is_integer());
+        assert_eq!(decimal("-0.0").to_string(), "0");
+        assert_eq!(Decimal::parse("1.2.3"), None);
+        assert_eq!(Decimal::parse("1e5"), None);
+        assert_eq!(Decimal::parse(""), None);
+    }
+
+    #[test]
+    fn from_f64_uses_the_shortest_digits() {
+        assert_eq!(Decimal::from_f64(0.1), Some(decimal("0.1")));
+        assert_eq!(Decimal::from_f64(f64::NAN), None);
+        assert_eq!(decimal("0.1").add(&decimal("0.2")), decimal("0.3"));
+    }
+
+    #[test]
+    fn div_rounds_half_away_from_zero() {
+        let (third, exact) = decimal("1").div(&decimal("3")).unwrap();
+        assert!(!exact);
+        assert_eq!(third.to_string(), format!("0.{}", "3".repeat(DIVISION_DIGITS)));
+        let (two_thirds, _) = decimal("-2").div(&decimal("3")).unwrap();
+        assert_eq!(two_thirds.to_string(), format!("-0.{}7", "6".repeat(DIVISION_DIGITS - 1)));
+        assert_eq!(decimal("1").div(&decimal("8")), Some((decimal("0.125"), true)));
+        assert_eq!(decimal("1").div(&decimal("0")), None);
+    }
+
+    #[test]
+    fn pow_needs_a_whole_exponent() {
+        assert_eq!(decimal("1.5").pow(&decimal("2")), Some((decimal("2.25"), true)));
+        assert_eq!(decimal("2").pow(&decimal("-2")), Some((decimal("0.25"), true)));
+        assert_eq!(decimal("2").pow(&decimal("0.5")), None);
+        assert_eq!(decimal("0").pow(&decimal("-1")), None);
+    }
+
+    #[test]
+    fn div_rem_euclid_matches_f64_for_every_sign() {
+        for (lhs, rhs) in [("7.5", "2"), ("-7.5", "2"), ("7.5", "-2"), ("-7.5", "-2"), ("-0.25", "0.1")] {
+            let (quotient, remainder) = decimal(lhs).div_rem_euclid(&decimal(rhs)).unwrap();
+            let (lhs_float, rhs_float): (f64, f64) = (lhs.parse().unwrap(), rhs.parse().unwrap());
+            assert_eq!(quotient.to_f64(), lhs_float.div_euclid(rhs_float), "{} div_euclid {}", lhs, rhs);
+            assert!(remainder >= decimal("0") && remainder < decimal(rhs.trim_start_matches('-')), "{} rem_euclid {}", lhs, rhs);
+            assert_eq!(quotient.mul(&decimal(rhs)).add(&remainder), decimal(lhs));
+        }
+        assert_eq!(decimal("1").div_rem_euclid(&decimal("0")), None);
+    }
+}
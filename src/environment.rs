@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    complex::find_complex_function,
+    constant::find_constant,
+    currency::BASE_CURRENCY,
+    function::find_function,
+    lexer::IMAGINARY_UNIT,
+    matrix::find_matrix_function,
+    mode::{AngleMode, Mode},
+    symbolic::SYMBOLIC_FUNCTIONS,
+    value::Value,
+};
+
+/// The name of the variable that always holds the most recent result
+pub const ANSWER: &str = "ans";
+
+/// The variables a user has assigned, the `Mode` numbers are evaluated in, the `AngleMode` of trig functions,
+/// and the exchange rates currencies are converted with, kept from one input to the next
+#[derive(Debug, Default, Clone)]
+pub struct Environment {
+    variables: BTreeMap<String, Value>,
+    mode: Mode,
+    angle_mode: AngleMode,
+    /// how much of each currency one US dollar buys
+    exchange_rates: BTreeMap<String, f64>,
+}
+impl Environment {
+    /// Creates an environment without any variables, in the floating point mode with angles in radians
+    pub fn new() -> Self {
+        Environment::default()
+    }
+
+    /// How numbers are represented while evaluating
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Changes how numbers are represented from the next evaluation on. Variables keep their values
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    /// The unit of a plain number given to a trig function
+    pub fn angle_mode(&self) -> AngleMode {
+        self.angle_mode
+    }
+
+    /// Changes the unit of a plain number given to a trig function from the next evaluation on
+    pub fn set_angle_mode(&mut self, angle_mode: AngleMode) {
+        self.angle_mode = angle_mode;
+    }
+
+    /// Adds exchange rates, replacing any earlier rate for the same currency
+    /// # Parameters
+    ///  - `rates`: how much of each currency one US dollar buys
+    pub fn add_exchange_rates(&mut self, rates: BTreeMap<String, f64>) {
+        self.exchange_rates.extend(rates);
+    }
+
+    /// Looks up how much of a currency one US dollar buys
+    /// # Parameters
+    ///  - `code`: the currency, like `EUR`
+    /// # Returns
+    ///  - `Some(rate)`: when `code` is `USD`, or rates have been added for it
+    ///  - `None`: when there is no rate for `code`
+    pub fn exchange_rate(&self, code: &str) -> Option<f64> {
+        match code {
+            BASE_CURRENCY => Some(1.0),
+            _ => self.exchange_rates.get(code).copied(),
+        }
+    }
+
+    /// Looks up the value of a variable
+    /// # Parameters
+    ///  - `name`: the name of the variable
+    /// # Returns
+    ///  - `Some(value)`: when `name` has been assigned
+    ///  - `None`: when `name` has never been assigned
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.variables.get(name).cloned()
+    }
+
+    /// Assigns `value` to the variable `name`, replacing any previous value.
+    /// # Parameters
+    ///  - `name`: the name of the variable
+    ///  - `value`: the new value of the variable
+    /// # Returns
+    ///  - `Ok(())`: when the variable was assigned
+    ///  - `Err(assignment_error)`: when `name` belongs to a builtin constant or function, or is `ans` or `i`, which can't be replaced
+    pub fn assign(&mut self, name: &str, value: Value) -> Result<(), Box<dyn std::error::Error>> {
+        if name == ANSWER {
+            return Err(format!("Cannot assign to `{}`, it always holds the last result", ANSWER).into());
+        }
+        if name == IMAGINARY_UNIT {
+            return Err(format!("Cannot assign to `{}`, it is the imaginary unit", IMAGINARY_UNIT).into());
+        }
+        if find_constant(name).is_some() {
+            return Err(format!("Cannot assign to the builtin constant `{}`", name).into());
+        }
+        if find_function(name).is_some() || find_complex_function(name).is_some() || find_matrix_function(name).is_some() || SYMBOLIC_FUNCTIONS.contains(&name) {
+            return Err(format!("Cannot assign to the builtin function `{}`", name).into());
+        }
+
+        self.variables.insert(name.to_owned(), value);
+        Ok(())
+    }
+
+    /// Every assigned variable and its value, including `ans`, in alphabetical order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Value)> {
+        self.variables.iter().map(|(name, value)| (name.as_str(), value.clone()))
+    }
+
+    /// Stores `value` as the most recent result, so the next input can refer to it as `ans`
+    pub fn set_answer(&mut self, value: Value) {
+        self.variables.insert(ANSWER.to_owned(), value);
+    }
+}
@@ -0,0 +1,905 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+};
+
+use crate::{
+    bigint::{BigInt, MAX_EXACT_DIGITS},
+    complex::{find_complex_function, Complex},
+    constant::find_constant,
+    currency::{currency, is_currency_code},
+    date::{readable_duration, Date, NOW},
+    decimal::Decimal,
+    environment::{Environment, ANSWER},
+    function::{call_in_degrees, find_function, ANGLE_FUNCTIONS},
+    interval::Interval,
+    lexer::tokenize,
+    matrix::{find_matrix_function, Matrix},
+    mode::{AngleMode, Mode},
+    operation::{boolean, integer_operand, Operation, MAX_EXACT_INTEGER},
+    parser::{parse_simple, Parser},
+    rational::Rational,
+    symbolic::{self, SYMBOLIC_FUNCTIONS},
+    unit::{find_unit, Dimension, Quantity, DATA_RATE},
+    value::Value,
+    warning::Warning,
+};
+
+/// A tree of numbers, names, and function calls combined by operations, e.g. `2 - 3 * 4 + 5` is `Chain(2, [(-, Chain(3, [(*, 4)])), (+, 5)])`.<br>
+/// Every node is kept in one `Vec` and refers to its children by index, so parsing an expression makes one allocation
+/// for the whole tree rather than a `Box` for every node. A child is always pushed before its parent, and the root is pushed last
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    nodes: Vec<Node>,
+    root: NodeId,
+}
+
+/// The position of a `Node` in the `Expression` that holds it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeId(usize);
+impl NodeId {
+    /// The id of the node at `index` in the `nodes` given to `Expression::new`
+    pub fn new(index: usize) -> Self {
+        NodeId(index)
+    }
+
+    /// The index of the node in the nodes of its `Expression`
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// One operand or operation of an `Expression`, whose children are other nodes of the same `Expression`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    /// A number literal
+    Number(f64),
+    /// A whole number literal, kept exactly however many digits it has
+    Integer(BigInt),
+    /// An imaginary number literal like `4i`, or `i` itself
+    Imaginary(f64),
+    /// A date literal like `2024-03-01`
+    Date(Date),
+    /// A matrix literal like `[1, 2; 3, 4]`, with its elements row by row
+    Matrix {
+        columns: usize,
+        elements: Vec<NodeId>,
+    },
+    /// A named value like `pi` or a variable
+    Identifier(String),
+    /// The negative of a sub-expression, e.g. `-5` or `-(2 + 3)`
+    Negate(NodeId),
+    /// The bitwise not of a whole number sub-expression, e.g. `~5` is `-6`
+    BitwiseNot(NodeId),
+    /// The logical not of a sub-expression, e.g. `not 1 > 2` is true
+    Not(NodeId),
+    /// A percentage of a sub-expression, e.g. `15%`.
+    /// On its own it is a hundredth of its operand, but added to or subtracted from something it is that share of it,
+    /// so `100 + 10%` is `110`
+    Percent(NodeId),
+    /// The factorial of a whole number sub-expression, e.g. `5!` is `120`
+    Factorial(NodeId),
+    /// A call to a builtin function, e.g. `log(100, 10)`
+    Call {
+        name: String,
+        arguments: Vec<NodeId>,
+    },
+    /// A choice between two sub-expressions, e.g. `x < 0 ? -x : x`.
+    /// Only the branch picked by the condition is evaluated
+    Conditional {
+        condition: NodeId,
+        if_true: NodeId,
+        if_false: NodeId,
+    },
+    /// Two sub-expressions said to be equal, e.g. `2x + 3 = 11`, which is only written as an argument of a function like `solve`
+    Equation {
+        lhs: NodeId,
+        rhs: NodeId,
+    },
+    /// A sub-expression shown in a unit, e.g. `5 km in m` is `5000 m`
+    Convert {
+        quantity: NodeId,
+        unit: NodeId,
+    },
+    /// A run of sub-expressions joined by operations that all have the same precedence.<br>
+    /// Left associative runs are evaluated from `first` towards the end of `rest`,
+    /// right associative runs from the end of `rest` back towards `first`.
+    /// A run has at least one operation, and is kept flat however long it gets.
+    Chain {
+        first: NodeId,
+        rest: Vec<(Operation, NodeId)>,
+    },
+}
+
+/// The result of evaluating an `Expression` along with what was noticed about its precision
+pub struct Evaluation {
+    /// the value of the expression, which is `1` or `0` for a boolean
+    pub value: Value,
+    /// `true` when the expression is a comparison or logic, so its value means true or false
+    pub boolean: bool,
+    /// `true` when no rounding happened in any operation of the expression
+    pub exact: bool,
+    /// precision problems noticed while evaluating the expression
+    pub warnings: Vec<Warning>,
+}
+
+impl Display for Evaluation { // allows for `println!()` and `.to_string()`
+
+    /// writes the value, as `true` or `false` when it is a boolean
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the value to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.boolean {
+            write!(f, "{}", self.value.is_truthy())
+        } else {
+            write!(f, "{}", self.value)
+        }
+    }
+}
+
+impl Expression {
+    /// Creates an expression out of the nodes of its tree
+    /// # Parameters
+    ///  - `nodes`: every node of the tree, each child before its parent
+    ///  - `root`: the node the whole expression is made of
+    pub fn new(nodes: Vec<Node>, root: NodeId) -> Self {
+        Expression { nodes, root }
+    }
+
+    /// The node `id` of this expression
+    pub fn get(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    /// The node `id` of this expression on its own, which can be displayed like a whole expression
+    pub fn node(&self, id: NodeId) -> NodeView<'_> {
+        NodeView { expression: self, id }
+    }
+
+    /// Computes the value of this expression.
+    /// # Parameters
+    ///  - `environment`: the variables that names in the expression can refer to
+    /// # Returns
+    ///  - `Ok(value)`: the value of the expression
+    ///  - `Err(evaluation_error)`: when a name is unknown or some operation is undefined for its operands (e.g. dividing by zero)
+    pub fn evaluate(&self, environment: &Environment) -> Result<Value, Box<dyn std::error::Error>> {
+        Ok(self.evaluate_detailed(environment)?.value)
+    }
+
+    /// Computes the value of this expression and checks whether its displayed digits can be trusted.
+    /// # Parameters
+    ///  - `environment`: the variables that names in the expression can refer to
+    /// # Returns
+    ///  - `Ok(evaluation)`: the value, whether it is exact, and any precision warnings
+    ///  - `Err(evaluation_error)`: when a name is unknown or some operation is undefined for its operands (e.g. dividing by zero)
+    pub fn evaluate_detailed(&self, environment: &Environment) -> Result<Evaluation, Box<dyn std::error::Error>> {
+        let mut exact = true;
+        let mut warnings = Vec::new();
+        let value = self.evaluate_node(self.root, environment, &mut exact, &mut warnings)?;
+
+        if let Value::Real(number) = value {
+            if number.is_finite() && number.fract() == 0.0 && number.abs() > MAX_EXACT_INTEGER {
+                warnings.push(Warning::InexactInteger);
+            }
+        }
+
+        Ok(Evaluation { value, boolean: self.is_boolean(), exact, warnings })
+    }
+
+    /// Whether this expression results in a boolean rather than a number, which is when its outermost operation is a comparison or logic
+    pub fn is_boolean(&self) -> bool {
+        self.is_boolean_node(self.root)
+    }
+
+    // whether the node `id` results in a boolean
+    fn is_boolean_node(&self, id: NodeId) -> bool {
+        match &self.nodes[id.0] {
+            Node::Not(_) => true,
+            Node::Conditional { if_true, if_false, .. } => self.is_boolean_node(*if_true) && self.is_boolean_node(*if_false),
+            Node::Chain { rest, .. } => rest.first().is_some_and(|(operation, _)| operation.is_boolean()),
+            _ => false,
+        }
+    }
+
+    // recursively evaluate the node `id`, clearing `exact` and pushing to `warnings` as precision is lost
+    fn evaluate_node(&self, id: NodeId, environment: &Environment, exact: &mut bool, warnings: &mut Vec<Warning>) -> Result<Value, Box<dyn std::error::Error>> {
+        match &self.nodes[id.0] {
+            // in the fraction mode `0.1` is exactly `1/10`, unless it has too many digits to be a fraction
+            Node::Number(number) => match environment.mode() {
+                Mode::Fraction => Ok(Rational::from_decimal(*number).map_or(Value::Real(*number), Value::Rational)),
+                Mode::Decimal => Ok(Decimal::from_f64(*number).map_or(Value::Real(*number), Value::Decimal)),
+                Mode::Interval => Ok(Value::Interval(Interval::from_decimal(*number))),
+                Mode::Float => Ok(Value::Real(*number)),
+            },
+            // each mode keeps whole numbers exact in its own way, and the float mode keeps them as integers of any size
+            Node::Integer(integer) => match environment.mode() {
+                Mode::Fraction => Ok(integer.to_i128().map_or(Value::Integer(integer.clone()), |integer| Value::Rational(Rational::integer(integer)))),
+                Mode::Decimal => Ok(Value::Decimal(Decimal::integer(integer.clone()))),
+                Mode::Interval => Ok(Value::Interval(Interval::from_integer(integer))),
+                Mode::Float => Ok(Value::Integer(integer.clone())),
+            },
+            Node::Imaginary(number) => Ok(Value::from(Complex::new(0.0, *number))),
+            Node::Date(date) => Ok(Value::Date(*date)),
+            Node::Matrix { columns, elements } => {
+                let elements = elements
+                    .iter()
+                    .map(|element| {
+                        let element = self.evaluate_node(*element, environment, exact, warnings)?;
+                        element
+                            .to_real()
+                            .ok_or_else(|| format!("A matrix can only hold real numbers, but was given {}", element).into())
+                    })
+                    .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+                Ok(Value::Matrix(Matrix::new(*columns, elements)))
+            },
+            Node::Identifier(name) => {
+                // builtin names can't be assigned, so a variable never hides a constant, but it can hide a unit like `m` or a currency
+                if let Some(value) = environment.get(name) {
+                    // a fraction, decimal, or interval assigned in its own mode is used as a float in the other modes
+                    return Ok(match (value, environment.mode()) {
+                        (Value::Rational(fraction), Mode::Float | Mode::Decimal | Mode::Interval) => Value::Real(fraction.to_f64()),
+                        (Value::Decimal(decimal), Mode::Float | Mode::Fraction | Mode::Interval) => Value::Real(decimal.to_f64()),
+                        (Value::Interval(interval), Mode::Float | Mode::Fraction | Mode::Decimal) => Value::Real(interval.midpoint()),
+                        (value, _) => value,
+                    });
+                }
+                if let Some(unit) = find_unit(name) {
+                    return Ok(Value::Quantity(Quantity::of_unit(name, unit)));
+                }
+                if let Some(rate) = environment.exchange_rate(name) {
+                    return Ok(Value::Quantity(currency(name, rate)));
+                }
+                if name == NOW {
+                    return Ok(Value::Date(Date::now()));
+                }
+
+                let is_function = find_function(name).is_some() || find_complex_function(name).is_some() || find_matrix_function(name).is_some() || SYMBOLIC_FUNCTIONS.contains(&name.as_str());
+                let constant = find_constant(name).ok_or_else(|| match is_function {
+                    true => format!("`{}` is a function, call it with parentheses like {}(x)", name, name),
+                    false if name == ANSWER => format!("`{}` holds the last result, but nothing has been calculated yet", ANSWER),
+                    false if is_currency_code(name) => format!("There is no exchange rate for `{}`, load rates with --rates rates.toml", name),
+                    false => format!("Unknown name `{}`", name),
+                })?;
+                // every builtin constant is irrational, so its `f64` value is always rounded
+                *exact = false;
+                match environment.mode() {
+                    Mode::Interval => Ok(Value::Interval(Interval::around(constant.value))),
+                    _ => Ok(Value::Real(constant.value)),
+                }
+            },
+            Node::Negate(operand) => match self.evaluate_node(*operand, environment, exact, warnings)? {
+                Value::Real(number) => Ok(Value::Real(-number)),
+                Value::Integer(integer) => Ok(Value::Integer(integer.neg())),
+                Value::Rational(fraction) | Value::Ratio(fraction) => Ok(fraction.checked_neg().map_or(Value::Real(-fraction.to_f64()), Value::Rational)),
+                Value::Decimal(decimal) => Ok(Value::Decimal(decimal.neg())),
+                Value::Interval(interval) => Ok(Value::Interval(interval.neg())),
+                Value::Complex(z) => Ok(Value::Complex(Complex::new(-z.re, -z.im))),
+                Value::Quantity(quantity) => Ok(Value::Quantity(quantity.with_value(-quantity.value()))),
+                Value::Date(date) => Err(format!("A date can't be negated, but was given {}", date).into()),
+                Value::Matrix(matrix) => Ok(Value::Matrix(matrix.map(|element| -element))),
+                Value::Expression(expression) => Err(format!("`{}` has variables without values, so it can't be negated", expression).into()),
+                Value::List(values) => Err(format!("The list {} can't be negated", Value::List(values)).into()),
+            },
+            // an exact whole number keeps every bit, however big it is
+            Node::BitwiseNot(operand) => {
+                let operand = self.evaluate_node(*operand, environment, exact, warnings)?;
+                match operand.to_integer() {
+                    Some(integer) => Ok(Value::Integer(integer.not())),
+                    None => Ok(Value::Real(!integer_operand(operand.as_real("`~`")?, "~")? as f64)),
+                }
+            },
+            Node::Not(operand) => Ok(Value::Real(boolean(!self.evaluate_node(*operand, environment, exact, warnings)?.is_truthy()))),
+            Node::Conditional { condition, if_true, if_false } => {
+                if self.evaluate_node(*condition, environment, exact, warnings)?.is_truthy() {
+                    self.evaluate_node(*if_true, environment, exact, warnings)
+                } else {
+                    self.evaluate_node(*if_false, environment, exact, warnings)
+                }
+            },
+            Node::Factorial(operand) => factorial(self.evaluate_node(*operand, environment, exact, warnings)?),
+            Node::Percent(operand) => {
+                let percentage = self.evaluate_node(*operand, environment, exact, warnings)?;
+                apply_checked(Operation::Divide, percentage, Value::Real(100.0), exact, warnings)
+            },
+            // a symbolic function works on its arguments as written, so they aren't evaluated
+            Node::Call { name, arguments } if SYMBOLIC_FUNCTIONS.contains(&name.as_str()) => symbolic::call(self, name, arguments, environment, exact),
+            Node::Call { name, arguments } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.evaluate_node(*argument, environment, exact, warnings))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let result = call_function(name, &arguments, environment, exact)?;
+                if arguments.iter().any(|argument| matches!(argument, Value::Interval(_))) && !matches!(result, Value::Interval(_)) {
+                    warnings.push(Warning::DroppedBounds { operation: name.clone() });
+                }
+                Ok(result)
+            },
+            Node::Equation { .. } => Err(format!("The equation `{}` can only be given to a function like solve", self.node(id)).into()),
+            Node::Convert { quantity, unit } => {
+                let quantity = match self.evaluate_node(*quantity, environment, exact, warnings)? {
+                    Value::Date(date) => return Err(format!("A date has no units to convert, but was given {}", date).into()),
+                    Value::Matrix(_) => return Err("A matrix has no units to convert".into()),
+                    Value::Expression(expression) => return Err(format!("`{}` has variables without values, so it has no units to convert", expression).into()),
+                    Value::List(_) => return Err("A list has no units to convert".into()),
+                    value => value.to_quantity().ok_or("Complex numbers can't have units")?,
+                };
+                let Value::Quantity(target) = self.evaluate_node(*unit, environment, exact, warnings)? else {
+                    return Err(format!("`in` needs a unit after it, like `in km`, but was given `{}`", self.node(*unit)).into());
+                };
+                // the unit is shown as it was typed, without the spaces around its operators
+                let name = self.node(*unit).to_string().replace(' ', "");
+                Ok(Value::Quantity(quantity.convert(&target, name)?))
+            },
+            Node::Chain { first, rest } => {
+                let first = self.evaluate_node(*first, environment, exact, warnings)?;
+
+                // every operation in a chain shares an associativity
+                let right_associative = rest.first().is_some_and(|(operation, _)| operation.is_right_associative());
+                let comparison = rest.first().is_some_and(|(operation, _)| operation.is_comparison());
+
+                if comparison {
+                    // `a < b <= c` checks `a < b` and `b <= c`, rather than comparing the result of `a < b` to `c`
+                    let mut lhs = first;
+                    let mut all_true = true;
+                    for (operation, term) in rest {
+                        let rhs = self.evaluate_node(*term, environment, exact, warnings)?;
+                        all_true &= apply_checked(*operation, lhs, rhs.clone(), exact, warnings)?.is_truthy();
+                        lhs = rhs;
+                    }
+                    Ok(Value::Real(boolean(all_true)))
+                } else if right_associative {
+                    // `a ^ b ^ c` is `a ^ (b ^ c)`, so evaluate every operand then fold from the end
+                    let mut operands = vec![first];
+                    for (_, term) in rest {
+                        operands.push(self.evaluate_node(*term, environment, exact, warnings)?);
+                    }
+
+                    let mut result = operands.pop().unwrap_or(Value::Real(0.0));
+                    for ((operation, _), lhs) in rest.iter().zip(operands).rev() {
+                        result = apply_checked(*operation, lhs, result, exact, warnings)?;
+                    }
+                    Ok(result)
+                } else {
+                    // `a - b + c` is `(a - b) + c`, so fold from the start
+                    let mut result = first;
+                    for (operation, term) in rest {
+                        // once `and` meets a false operand, or `or` a true one, the rest can't change the result,
+                        // so they aren't evaluated and `x != 0 and 1 / x > 2` is safe
+                        let is_decided = match operation {
+                            Operation::And => !result.is_truthy(),
+                            Operation::Or => result.is_truthy(),
+                            _ => false,
+                        };
+                        if is_decided {
+                            result = Value::Real(boolean(result.is_truthy()));
+                            break;
+                        }
+
+                        let rhs = match &self.nodes[term.0] {
+                            // `a + p%` and `a - p%` add or subtract `p` percent of `a`
+                            Node::Percent(percentage) if matches!(operation, Operation::Add | Operation::Subtract) => {
+                                let percentage = self.evaluate_node(*percentage, environment, exact, warnings)?;
+                                let scaled = apply_checked(Operation::Multiply, result.clone(), percentage, exact, warnings)?;
+                                apply_checked(Operation::Divide, scaled, Value::Real(100.0), exact, warnings)?
+                            },
+                            _ => self.evaluate_node(*term, environment, exact, warnings)?,
+                        };
+                        result = apply_checked(*operation, result, rhs, exact, warnings)?;
+                    }
+                    Ok(result)
+                }
+            },
+        }
+    }
+
+    // how tightly the top of the node `id` binds; literals never need parentheses
+    fn precedence(&self, id: NodeId) -> u8 {
+        match &self.nodes[id.0] {
+            Node::Number(_)
+            | Node::Integer(_)
+            | Node::Imaginary(_)
+            | Node::Date(_)
+            | Node::Matrix { .. }
+            | Node::Identifier(_)
+            | Node::Call { .. }
+            | Node::Percent(_)
+            | Node::Factorial(_) => u8::MAX,
+            // a sign binds looser than `^` but tighter than everything else
+            Node::Negate(_) | Node::BitwiseNot(_) => Operation::HIGHEST_PRECEDENCE,
+            Node::Not(_) => Operation::NOT_PRECEDENCE,
+            // a conditional or conversion binds looser than every operator
+            Node::Conditional { .. } | Node::Convert { .. } | Node::Equation { .. } => 0,
+            Node::Chain { rest, .. } => rest.first().map_or(u8::MAX, |(operation, _)| operation.precedence()),
+        }
+    }
+}
+
+// call the builtin function `name`, clearing `exact` if its result was rounded.
+// an interval goes to the interval version of the function when there is one, and
+// real arguments go to the real version of the function, and complex ones to the complex version,
+// which is also used when the real version is undefined for the arguments, so `sqrt(-4)` is `2i`
+fn call_function(name: &str, arguments: &[Value], environment: &Environment, exact: &mut bool) -> Result<Value, Box<dyn std::error::Error>> {
+    if let Some(Value::Expression(expression)) = arguments.iter().find(|argument| matches!(argument, Value::Expression(_))) {
+        return Err(format!("`{}` has variables without values, so `{}` can't be calculated from it", expression, name).into());
+    }
+
+    // a name that isn't a builtin function says so before anything about its arguments, so `det(A)` is an unknown function
+    if find_function(name).is_none() && find_complex_function(name).is_none() && find_matrix_function(name).is_none() {
+        return Err(match find_constant(name) {
+            Some(_) => format!("`{}` is not a function", name),
+            None if environment.get(name).is_some() => format!("`{}` is a variable, not a function; multiply with {} * (...)", name, name),
+            None => format!("Unknown function `{}`", name),
+        }
+        .into());
+    }
+
+    // a function of a list like `sum` takes the elements of a matrix or list as its arguments
+    let spread: Vec<Value>;
+    let arguments = match find_function(name) {
+        Some(function) if function.takes_list() && arguments.iter().any(|argument| matches!(argument, Value::Matrix(_) | Value::List(_))) => {
+            spread = arguments
+                .iter()
+                .flat_map(|argument| match argument {
+                    Value::Matrix(matrix) => matrix.elements().iter().map(|element| Value::Real(*element)).collect(),
+                    Value::List(values) => values.clone(),
+                    argument => vec![argument.clone()],
+                })
+                .collect();
+            &spread[..]
+        },
+        _ => arguments,
+    };
+    if let Some(list) = arguments.iter().find(|argument| matches!(argument, Value::List(_))) {
+        return Err(format!("`{}` doesn't accept lists, but was given {}", name, list).into());
+    }
+
+    // the matrix functions only take a matrix, and no other function takes one
+    let matrix_function = find_matrix_function(name);
+    let matrix = arguments.iter().find(|argument| matches!(argument, Value::Matrix(_)));
+    match (matrix_function, arguments) {
+        (Some(function), [Value::Matrix(matrix)]) => return Ok((function.call)(matrix)),
+        (Some(_), [argument]) => return Err(format!("`{}` only accepts a matrix, but was given {}", name, argument).into()),
+        (Some(_), arguments) => return Err(format!("{} takes 1 argument but was given {}", name, arguments.len()).into()),
+        (None, _) if matrix.is_some() => return Err(format!("`{}` doesn't accept matrices", name).into()),
+        (None, _) => {},
+    }
+    if let Some(Value::Date(date)) = arguments.iter().find(|argument| matches!(argument, Value::Date(_))) {
+        return Err(format!("`{}` doesn't accept dates, but was given {}", name, date).into());
+    }
+    if let Some(quantity) = arguments.iter().find_map(|argument| match argument {
+        Value::Quantity(quantity) => Some(quantity),
+        _ => None,
+    }) {
+        return call_with_units(name, quantity, arguments, exact);
+    }
+
+    // in degree mode a plain real angle goes to the degree version of the trig function,
+    // and a complex one is converted to radians for the complex version
+    let is_angle = ANGLE_FUNCTIONS.contains(&name) && environment.angle_mode() == AngleMode::Degrees;
+    let converted;
+    let arguments = match arguments {
+        [argument] if is_angle => match argument.to_real() {
+            Some(degrees) => {
+                let (result, is_exact) = call_in_degrees(name, degrees)?;
+                if !is_exact {
+                    *exact = false;
+                }
+                return Ok(Value::Real(result));
+            },
+            None => {
+                let z = argument.as_complex();
+                converted = [Value::from(Complex::new(z.re.to_radians(), z.im.to_radians()))];
+                &converted[..]
+            },
+        },
+        arguments => arguments,
+    };
+    if let [Value::Interval(interval)] = arguments {
+        if let Some(result) = interval.apply_function(name) {
+            if !result.is_point() {
+                *exact = false;
+            }
+            return Ok(Value::Interval(result));
+        }
+    }
+
+    let real_arguments: Option<Vec<f64>> = arguments.iter().map(|argument| argument.to_real()).collect();
+    let function = find_function(name);
+    let complex_function = find_complex_function(name);
+
+    if let (Some(function), Some(real_arguments)) = (function, &real_arguments) {
+        match function.call(real_arguments) {
+            Ok(result) => {
+                // an aspect ratio is shown in lowest terms like `16:9`, as long as both sides have few enough digits to be a fraction
+                if name == "aspect" {
+                    if let Some(ratio) = aspect_ratio(real_arguments[0], real_arguments[1]) {
+                        return Ok(Value::Ratio(ratio));
+                    }
+                }
+                if !(function.is_exact)(real_arguments, result) {
+                    *exact = false;
+                }
+                return Ok(Value::Real(result));
+            },
+            Err(error) if complex_function.is_none() || real_arguments.len() != 1 => return Err(error),
+            Err(_) => {},
+        }
+    }
+
+    let Some(complex_function) = complex_function else {
+        return Err(format!("`{}` doesn't accept complex numbers", name).into());
+    };
+    if arguments.len() != 1 {
+        return Err(format!("{} takes 1 argument but was given {}", name, arguments.len()).into());
+    }
+
+    if !complex_function.exact {
+        *exact = false;
+    }
+    let arguments: Vec<Complex> = arguments.iter().map(Value::as_complex).collect();
+    (complex_function.call)(&arguments)
+}
+
+// the ratio `width:height` in lowest terms, from the decimal digits of each side so `aspect(2.39, 1)` is `239:100`
+fn aspect_ratio(width: f64, height: f64) -> Option<Rational> {
+    Rational::from_decimal(width)?.checked_div(Rational::from_decimal(height)?)
+}
+
+// call the builtin function `name` on `arguments`, where `quantity` is the first with units, which only `abs` and `sqrt` accept,
+// along with the trig functions when the quantity is an angle like `30 deg`, and `transfer_time` for an amount of data and a rate
+fn call_with_units(name: &str, quantity: &Quantity, arguments: &[Value], exact: &mut bool) -> Result<Value, Box<dyn std::error::Error>> {
+    let is_angle = ANGLE_FUNCTIONS.contains(&name);
+    let argument_count = arguments.len();
+    match name {
+        "abs" | "sqrt" if argument_count != 1 => Err(format!("{} takes 1 argument but was given {}", name, argument_count).into()),
+        _ if is_angle && argument_count != 1 => Err(format!("{} takes 1 argument but was given {}", name, argument_count).into()),
+        // an angle with units is the same in either angle mode, and is counted in degrees
+        _ if is_angle && quantity.dimension() == Dimension::ANGLE => {
+            let (result, is_exact) = call_in_degrees(name, quantity.value())?;
+            if !is_exact {
+                *exact = false;
+            }
+            Ok(Value::Real(result))
+        },
+        "abs" => Ok(Value::Quantity(quantity.with_value(quantity.value().abs()))),
+        "sqrt" if quantity.value() < 0.0 => Err("sqrt is undefined for negative numbers".into()),
+        "sqrt" => {
+            let dimension = quantity.dimension().sqrt().ok_or_else(|| format!("The square root of {} doesn't have whole units", quantity))?;
+            Ok(Quantity::new(quantity.value().sqrt(), dimension, None).into_value())
+        },
+        "transfer_time" => {
+            let [size, rate] = arguments else {
+                return Err(format!("transfer_time takes 2 arguments but was given {}", argument_count).into());
+            };
+            let (size, rate) = match (size.to_quantity(), rate.to_quantity()) {
+                (Some(size), Some(rate)) if size.dimension() == Dimension::INFORMATION && rate.dimension() == DATA_RATE => (size.value(), rate.value()),
+                _ => return Err(format!("transfer_time needs an amount of data like 2.5 GiB and a rate like 40 Mbit/s, but was given {} and {}", size, rate).into()),
+            };
+            if size < 0.0 {
+                return Err("transfer_time needs a size of at least 0 bytes".into());
+            }
+            if rate <= 0.0 {
+                return Err("transfer_time needs a positive rate".into());
+            }
+            let seconds = size / rate;
+            let duration = readable_duration(seconds);
+            if !Operation::Divide.is_exact(size, rate, seconds) || duration.value() != seconds {
+                *exact = false;
+            }
+            Ok(Value::Quantity(duration))
+        },
+        _ => Err(format!("`{}` doesn't accept quantities with units", name).into()),
+    }
+}
+
+// the product of every whole number from 1 to `value`, which is an error when it has too many digits to work out exactly
+fn factorial(value: Value) -> Result<Value, Box<dyn std::error::Error>> {
+    // 3248! is the largest factorial with fewer than 10000 digits
+    const MAX_EXACT_FACTORIAL: f64 = 3248.0;
+
+    let number = value.as_real("`!`")?;
+    if number < 0.0 || number.fract() != 0.0 {
+        return Err(format!("`!` only works on whole numbers that aren't negative, but was given {}", value).into());
+    }
+    if number > MAX_EXACT_FACTORIAL {
+        return Err(format!("`!` of {} would give a result with more than {} digits", value, MAX_EXACT_DIGITS).into());
+    }
+
+    let mut result = BigInt::from_i128(1);
+    for factor in 2..=number as i128 {
+        result = result.mul(&BigInt::from_i128(factor));
+    }
+    Ok(Value::Integer(result))
+}
+
+// apply `operation`, clearing `exact` and pushing to `warnings` if the result lost precision
+fn apply_checked(operation: Operation, lhs: Value, rhs: Value, exact: &mut bool, warnings: &mut Vec<Warning>) -> Result<Value, Box<dyn std::error::Error>> {
+    if let Value::Expression(expression) = if matches!(lhs, Value::Expression(_)) { &lhs } else { &rhs } {
+        return Err(format!("`{}` has variables without values, so `{}` can't work on it", expression, operation).into());
+    }
+
+    if let Value::List(_) = if matches!(lhs, Value::List(_)) { &lhs } else { &rhs } {
+        return Err(format!("`{}` doesn't work on lists, but was given {} and {}", operation, lhs, rhs).into());
+    }
+
+    // matrices are worked on in floating point, as products or element by element
+    if matches!(lhs, Value::Matrix(_)) || matches!(rhs, Value::Matrix(_)) {
+        let (result, is_exact) = operation.apply_matrix(&lhs, &rhs)?;
+        if !is_exact {
+            *exact = false;
+        }
+        return Ok(result);
+    }
+
+    // apart from matrices, an element-wise operation is the same as the one it applies to each element
+    let operation = operation.on_elements();
+
+    // dates are seconds since 1970, moved by durations
+    if matches!(lhs, Value::Date(_)) || matches!(rhs, Value::Date(_)) {
+        return operation.apply_date(&lhs, &rhs);
+    }
+
+    // units are tracked in floating point, on the amounts in SI base units
+    if matches!(lhs, Value::Quantity(_)) || matches!(rhs, Value::Quantity(_)) {
+        let (Some(lhs), Some(rhs)) = (lhs.to_quantity(), rhs.to_quantity()) else {
+            return Err("Complex numbers can't have units".into());
+        };
+        let result = operation.apply_quantity(&lhs, &rhs)?;
+        let amount = result.to_quantity().map_or(f64::NAN, |result| result.value());
+        if !operation.is_exact(lhs.value(), rhs.value(), amount) {
+            *exact = false;
+        }
+        return Ok(result);
+    }
+
+    // exact numbers stay exact as long as the result can be, with an integer joining a fraction or decimal as one of them.
+    // an interval turns any real operand into an interval, so the bounds keep holding the true result
+    match (&lhs, &rhs) {
+        (Value::Interval(_), _) | (_, Value::Interval(_)) => {
+            if let (Some(lhs), Some(rhs)) = (lhs.to_interval(), rhs.to_interval()) {
+                if let Some(result) = operation.apply_interval(lhs, rhs)? {
+                    if !matches!(result, Value::Interval(interval) if interval.is_point()) {
+                        *exact = false;
+                    }
+                    return Ok(result);
+                }
+            }
+            // a comparison only gives true or false, so it has no bounds to lose,
+            // and the integer and bitwise operations are exact on intervals that are only a single number
+            let is_point = |value: &Value| value.to_interval().is_some_and(|interval| interval.is_point());
+            let is_exact = operation != Operation::Exponential && is_point(&lhs) && is_point(&rhs);
+            if !operation.is_comparison() && !is_exact {
+                warnings.push(Warning::DroppedBounds { operation: operation.to_string() });
+            }
+        },
+        (Value::Integer(lhs), Value::Integer(rhs)) => {
+            if let Some(result) = operation.apply_integer(lhs, rhs)? {
+                return Ok(result);
+            }
+        },
+        // whole numbers too big for a fraction carry on as integers, so `2 ^ 200` and `100! + 1` stay exact in the fraction mode
+        (Value::Rational(_) | Value::Integer(_), Value::Rational(_) | Value::Integer(_)) => {
+            if let (Some(lhs), Some(rhs)) = (lhs.to_rational(), rhs.to_rational()) {
+                if let Some(result) = operation.apply_rational(lhs, rhs)? {
+                    return Ok(result);
+                }
+            }
+            if let (Some(lhs), Some(rhs)) = (lhs.to_integer(), rhs.to_integer()) {
+                if let Some(result) = operation.apply_integer(&lhs, &rhs)? {
+                    return Ok(result);
+                }
+            }
+        },
+        (Value::Decimal(_) | Value::Integer(_), Value::Decimal(_) | Value::Integer(_)) => {
+            if let (Some(lhs), Some(rhs)) = (lhs.to_decimal(), rhs.to_decimal()) {
+                if let Some((result, is_exact)) = operation.apply_decimal(&lhs, &rhs)? {
+                    if !is_exact {
+                        *exact = false;
+                    }
+                    return Ok(result);
+                }
+            }
+        },
+        _ => {},
+    }
+
+    let (lhs, rhs) = match (lhs.to_real(), rhs.to_real()) {
+        (Some(lhs), Some(rhs)) => (lhs, rhs),
+        // precision warnings are only worked out for real numbers
+        _ => {
+            let (lhs, rhs) = (lhs.as_complex(), rhs.as_complex());
+            let result = operation.apply_complex(lhs, rhs)?;
+            if !operation.is_exact_complex(lhs, rhs, result) {
+                *exact = false;
+            }
+            return Ok(Value::from(result));
+        },
+    };
+
+    // a negative number to a fractional power is complex, so `(-1) ^ 0.5` is `i` like `sqrt(-1)` rather than NaN
+    if operation == Operation::Exponential && lhs < 0.0 && rhs.is_finite() && rhs.fract() != 0.0 {
+        let (lhs, rhs) = (Complex::from(lhs), Complex::from(rhs));
+        let result = operation.apply_complex(lhs, rhs)?;
+        if !operation.is_exact_complex(lhs, rhs, result) {
+            *exact = false;
+        }
+        return Ok(Value::from(result));
+    }
+
+    let result = operation.apply(lhs, rhs)?;
+
+    if !operation.is_exact(lhs, rhs, result) {
+        *exact = false;
+    }
+    if let Some(warning) = operation.precision_warning(lhs, rhs, result) {
+        warnings.push(warning);
+    }
+
+    Ok(Value::Real(result))
+}
+
+impl FromStr for Expression { // Trait that allows .parse to work
+
+    type Err = Box<dyn std::error::Error>; // parse error type
+
+    /// Parse an `Expression` from `s`.<br>
+    /// `s` can contain numbers, names, the operators `+` `-` `*` `/` `//` `%` `^` `.*` `./` `.^` `&` `|` `xor` `<<` `>>` `==` `!=` `<` `<=` `>` `>=` `and` `or`,
+    /// signs, `~`, `not`, percentages, imaginary numbers like `4i`, function calls, conditionals like `c ? a : b`, and parentheses.
+    /// `^` and `.^` bind tightest and group from the right, then signs and `~`, then `*` `/` `//` `%` `.*` `./`, then `+` `-`, then `<<` `>>`, then `&`, then `xor`, then `|`,
+    /// which all group from the left, then the comparisons, which chain, then `not`, then `and`, then `or`, then `? :`.
+    /// # Parameters
+    ///  - `s`: The string slice to be parsed
+    /// # Returns
+    ///  - `Ok(expression)`: When `s` is a well formed expression
+    ///  - `Err(from_str_error)`: When `s` contains unknown characters or isn't a well formed expression
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(expression) = parse_simple(s) {
+            return Ok(expression);
+        }
+        let tokens = tokenize(s)?;
+        Parser::new(tokens).parse()
+    }
+}
+impl Display for Expression { // allows for `println!()` and `.to_string()`
+
+    /// writes the the expression to the formatter `f`, with only the parentheses needed to keep its meaning
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the expression to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_node(f, self.root)
+    }
+}
+impl Expression {
+    // write the node `id` to `f`, with only the parentheses needed to keep its meaning
+    fn write_node(&self, f: &mut std::fmt::Formatter<'_>, id: NodeId) -> std::fmt::Result {
+        match &self.nodes[id.0] {
+            Node::Number(number) => write!(f, "{}", number),
+            Node::Integer(integer) => write!(f, "{}", integer),
+            Node::Imaginary(number) if *number == 1.0 => write!(f, "i"),
+            Node::Imaginary(number) => write!(f, "{}i", number),
+            Node::Date(date) => write!(f, "{}", date),
+            Node::Matrix { columns, elements } => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    match i {
+                        0 => {},
+                        i if i % columns == 0 => write!(f, "; ")?,
+                        _ => write!(f, ", ")?,
+                    }
+                    self.write_node(f, *element)?;
+                }
+                write!(f, "]")
+            },
+            Node::Identifier(name) => write!(f, "{}", name),
+            Node::Negate(operand) => {
+                write!(f, "-")?;
+                self.write_operand(f, *operand, self.precedence(*operand) < Operation::HIGHEST_PRECEDENCE)
+            },
+            Node::BitwiseNot(operand) => {
+                write!(f, "~")?;
+                self.write_operand(f, *operand, self.precedence(*operand) < Operation::HIGHEST_PRECEDENCE)
+            },
+            Node::Not(operand) => {
+                write!(f, "not ")?;
+                self.write_operand(f, *operand, self.precedence(*operand) < Operation::NOT_PRECEDENCE)
+            },
+            // conditionals group from the right, so only a conditional in the condition needs parentheses
+            Node::Conditional { condition, if_true, if_false } => {
+                self.write_operand(f, *condition, self.precedence(*condition) == 0)?;
+                write!(f, " ? ")?;
+                self.write_node(f, *if_true)?;
+                write!(f, " : ")?;
+                self.write_node(f, *if_false)
+            },
+            Node::Percent(operand) => {
+                self.write_operand(f, *operand, self.precedence(*operand) < u8::MAX)?;
+                write!(f, "%")
+            },
+            Node::Factorial(operand) => {
+                self.write_operand(f, *operand, self.precedence(*operand) < u8::MAX)?;
+                write!(f, "!")
+            },
+            Node::Equation { lhs, rhs } => {
+                self.write_node(f, *lhs)?;
+                write!(f, " = ")?;
+                self.write_node(f, *rhs)
+            },
+            // a conversion in the quantity groups by itself, but a conditional has to be kept out of the last branch
+            Node::Convert { quantity, unit } => {
+                self.write_operand(f, *quantity, matches!(self.nodes[quantity.0], Node::Conditional { .. }))?;
+                write!(f, " in ")?;
+                self.write_operand(f, *unit, self.precedence(*unit) == 0)
+            },
+            Node::Call { name, arguments } => {
+                write!(f, "{}(", name)?;
+                for (i, argument) in arguments.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    self.write_node(f, *argument)?;
+                }
+                write!(f, ")")
+            },
+            Node::Chain { first, rest } => {
+                let precedence = self.precedence(id);
+                let right_associative = rest.first().is_some_and(|(operation, _)| operation.is_right_associative());
+
+                // an operand needs parentheses if it binds looser than the chain,
+                // or equally loose on the side that the chain doesn't group towards
+                let first_needs_parentheses = self.precedence(*first) < precedence
+                    || (self.precedence(*first) == precedence && right_associative);
+                self.write_operand(f, *first, first_needs_parentheses)?;
+
+                for (i, (operation, term)) in rest.iter().enumerate() {
+                    // a sign captures every `^` after it, so a negated term in the middle of a `^` chain needs parentheses,
+                    // and a `%` followed by a sign would read as a percentage
+                    let is_last = i + 1 == rest.len();
+                    let is_negated = matches!(self.nodes[term.0], Node::Negate(_) | Node::BitwiseNot(_));
+                    let term_needs_parentheses = self.precedence(*term) < precedence
+                        || (self.precedence(*term) == precedence && !right_associative)
+                        || (is_negated && !is_last)
+                        || (is_negated && *operation == Operation::Modulo);
+                    write!(f, " {} ", operation)?;
+                    self.write_operand(f, *term, term_needs_parentheses)?;
+                }
+
+                Ok(())
+            },
+        }
+    }
+
+    // write the node `id` to `f`, wrapped in parentheses when `parenthesize` is set
+    fn write_operand(&self, f: &mut std::fmt::Formatter<'_>, id: NodeId, parenthesize: bool) -> std::fmt::Result {
+        if parenthesize {
+            write!(f, "(")?;
+            self.write_node(f, id)?;
+            write!(f, ")")
+        } else {
+            self.write_node(f, id)
+        }
+    }
+}
+
+// one node of an `Expression`, which displays like an expression made of just that node and its children
+pub struct NodeView<'a> {
+    expression: &'a Expression,
+    id: NodeId,
+}
+impl Display for NodeView<'_> { // allows for `println!()` and `.to_string()`
+
+    /// writes the node and its children, with only the parentheses needed to keep their meaning
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the node to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.expression.write_node(f, self.id)
+    }
+}
@@ -0,0 +1,43 @@
+/// Writes `number` as a C99 style hexadecimal floating point literal, like `printf("%a")` does.<br>
+/// Every bit of the `f64` is shown, so this is handy for seeing exactly which value a decimal rounded to.
+/// # Parameters
+///  - `number`: the value to format
+/// # Returns
+///  - normal numbers: `0x1.<hex digits>p<exponent>`, e.g. `0x1.8p+3` for `12`
+///  - subnormal numbers: `0x0.<hex digits>p-1022`
+///  - zero, infinity, and NaN: `0x0p+0`, `inf`, and `NaN` with the appropriate sign
+pub fn format_hex_float(number: f64) -> String {
+    const FRACTION_BITS: u32 = 52;
+    const EXPONENT_BIAS: i64 = 1023;
+
+    if number.is_nan() {
+        return "NaN".to_owned();
+    }
+
+    let sign = if number.is_sign_negative() { "-" } else { "" };
+    if number.is_infinite() {
+        return format!("{}inf", sign);
+    }
+
+    let bits = number.to_bits();
+    let biased_exponent = ((bits >> FRACTION_BITS) & 0x7FF) as i64;
+    let fraction = bits & ((1 << FRACTION_BITS) - 1);
+
+    if biased_exponent == 0 && fraction == 0 {
+        return format!("{}0x0p+0", sign);
+    }
+
+    // subnormal numbers have an implicit leading 0 instead of 1, and the smallest normal exponent
+    let (leading_digit, exponent) = if biased_exponent == 0 {
+        (0, 1 - EXPONENT_BIAS)
+    } else {
+        (1, biased_exponent - EXPONENT_BIAS)
+    };
+
+    // 52 fraction bits are exactly 13 hex digits; trailing zeros don't add information
+    let fraction_digits = format!("{:013x}", fraction);
+    let fraction_digits = fraction_digits.trim_end_matches('0');
+    let point = if fraction_digits.is_empty() { "" } else { "." };
+
+    format!("{}0x{}{}{}p{:+}", sign, leading_digit, point, fraction_digits, exponent)
+}
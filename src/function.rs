@@ -0,0 +1,413 @@
+use crate::operation::MAX_EXACT_INTEGER;
+
+/// Computes the result of a builtin function from its arguments
+pub type Implementation = fn(&[f64]) -> Result<f64, Box<dyn std::error::Error>>;
+
+/// A builtin function that can be called like `name(arguments)`
+pub struct Function {
+    /// what the user types before the `(`
+    pub name: &'static str,
+    /// the fewest arguments the function accepts
+    pub min_arguments: usize,
+    /// the most arguments the function accepts, which is `usize::MAX` for a function of a list of numbers like `mean`
+    pub max_arguments: usize,
+    /// computes the result from arguments whose count is already checked
+    pub call: Implementation,
+    /// whether a result of `call` is exactly the mathematical result for the arguments
+    pub is_exact: fn(&[f64], f64) -> bool,
+}
+impl Function {
+    /// Checks the number of arguments then calls the function.
+    /// # Parameters
+    ///  - `arguments`: the evaluated arguments, in order
+    /// # Returns
+    ///  - `Ok(result)`: the value of the function for `arguments`
+    ///  - `Err(call_error)`: when there are too few or too many arguments, or they are outside the function's domain
+    pub fn call(&self, arguments: &[f64]) -> Result<f64, Box<dyn std::error::Error>> {
+        if arguments.len() < self.min_arguments || arguments.len() > self.max_arguments {
+            let expected = match (self.min_arguments, self.max_arguments) {
+                (1, 1) => "1 argument".to_owned(),
+                (1, usize::MAX) => "at least 1 argument".to_owned(),
+                (min, usize::MAX) => format!("at least {} arguments", min),
+                (min, max) if min == max => format!("{} arguments", min),
+                (min, max) => format!("{} to {} arguments", min, max),
+            };
+            return Err(format!("{} takes {} but was given {}", self.name, expected, arguments.len()).into());
+        }
+
+        (self.call)(arguments)
+    }
+
+    /// Whether the function takes a list of any length, like `mean(1, 2, 3, 4)`, which can also be given as a matrix like `mean([1, 2, 3, 4])`
+    pub fn takes_list(&self) -> bool {
+        self.max_arguments == usize::MAX
+    }
+}
+
+/// Every builtin function
+pub const FUNCTIONS: &[Function] = &[
+    Function {
+        name: "sqrt",
+        min_arguments: 1,
+        max_arguments: 1,
+        call: |arguments| match arguments[0] {
+            x if x < 0.0 => Err("sqrt is undefined for negative numbers".into()),
+            x => Ok(x.sqrt()),
+        },
+        // the root is exact when squaring it gives back exactly the argument
+        is_exact: |arguments, result| result.is_finite() && result.mul_add(result, -arguments[0]) == 0.0,
+    },
+    Function {
+        name: "sin",
+        min_arguments: 1,
+        max_arguments: 1,
+        call: |arguments| Ok(arguments[0].sin()),
+        is_exact: |arguments, _| arguments[0] == 0.0,
+    },
+    Function {
+        name: "cos",
+        min_arguments: 1,
+        max_arguments: 1,
+        call: |arguments| Ok(arguments[0].cos()),
+        is_exact: |arguments, _| arguments[0] == 0.0,
+    },
+    Function {
+        name: "tan",
+        min_arguments: 1,
+        max_arguments: 1,
+        call: |arguments| Ok(arguments[0].tan()),
+        is_exact: |arguments, _| arguments[0] == 0.0,
+    },
+    Function {
+        name: "log",
+        min_arguments: 1,
+        max_arguments: 2,
+        call: |arguments| {
+            let x = arguments[0];
+            let base = arguments.get(1).copied().unwrap_or(10.0);
+            if x <= 0.0 {
+                return Err("log is only defined for positive numbers".into());
+            }
+            if base <= 0.0 || base == 1.0 {
+                return Err("the base of log must be positive and not 1".into());
+            }
+            Ok(if base == 10.0 { x.log10() } else { x.log(base) })
+        },
+        // a whole number result is exact when raising the base to it gives back exactly the argument
+        is_exact: |arguments, result| {
+            let base = arguments.get(1).copied().unwrap_or(10.0);
+            result.fract() == 0.0 && base.powf(result) == arguments[0]
+        },
+    },
+    Function {
+        name: "ln",
+        min_arguments: 1,
+        max_arguments: 1,
+        call: |arguments| match arguments[0] {
+            x if x <= 0.0 => Err("ln is only defined for positive numbers".into()),
+            x => Ok(x.ln()),
+        },
+        is_exact: |arguments, _| arguments[0] == 1.0,
+    },
+    Function {
+        name: "exp",
+        min_arguments: 1,
+        max_arguments: 1,
+        call: |arguments| Ok(arguments[0].exp()),
+        is_exact: |arguments, _| arguments[0] == 0.0,
+    },
+    Function {
+        name: "abs",
+        min_arguments: 1,
+        max_arguments: 1,
+        call: |arguments| Ok(arguments[0].abs()),
+        is_exact: |_, result| !result.is_nan(),
+    },
+    Function {
+        name: "midi_to_freq",
+        min_arguments: 1,
+        max_arguments: 1,
+        // A4 is MIDI note 69 at 440 Hz, and every 12 notes doubles the frequency
+        call: |arguments| Ok(440.0 * 2f64.powf((arguments[0] - 69.0) / 12.0)),
+        is_exact: |arguments, _| (arguments[0] - 69.0) % 12.0 == 0.0,
+    },
+    Function {
+        name: "freq_to_midi",
+        min_arguments: 1,
+        max_arguments: 1,
+        call: |arguments| match arguments[0] {
+            frequency if frequency <= 0.0 => Err("freq_to_midi is only defined for positive frequencies".into()),
+            frequency => Ok(69.0 + 12.0 * (frequency / 440.0).log2()),
+        },
+        is_exact: |arguments, result| result.fract() == 0.0 && 440.0 * 2f64.powf((result - 69.0) / 12.0) == arguments[0],
+    },
+    Function {
+        name: "db",
+        min_arguments: 1,
+        max_arguments: 1,
+        // decibels of an amplitude ratio; a power ratio would use 10 instead of 20
+        call: |arguments| match arguments[0] {
+            ratio if ratio <= 0.0 => Err("db is only defined for positive ratios".into()),
+            ratio => Ok(20.0 * ratio.log10()),
+        },
+        is_exact: |arguments, _| arguments[0] == 1.0,
+    },
+    Function {
+        name: "undb",
+        min_arguments: 1,
+        max_arguments: 1,
+        call: |arguments| Ok(10f64.powf(arguments[0] / 20.0)),
+        is_exact: |arguments, _| arguments[0] % 20.0 == 0.0 && arguments[0] >= 0.0,
+    },
+    Function {
+        name: "ev",
+        min_arguments: 2,
+        max_arguments: 3,
+        // exposure value normalised to ISO 100: log2(N^2 / t) - log2(iso / 100)
+        call: |arguments| {
+            let (aperture, shutter) = (arguments[0], arguments[1]);
+            let iso = arguments.get(2).copied().unwrap_or(100.0);
+            if aperture <= 0.0 || shutter <= 0.0 || iso <= 0.0 {
+                return Err("ev needs a positive aperture, shutter time, and ISO".into());
+            }
+            Ok((aperture * aperture / shutter).log2() - (iso / 100.0).log2())
+        },
+        is_exact: |_, _| false,
+    },
+    Function {
+        name: "fstop",
+        min_arguments: 2,
+        max_arguments: 2,
+        // each stop multiplies the f-number by sqrt(2), halving the light
+        call: |arguments| match (arguments[0], arguments[1]) {
+            (aperture, _) if aperture <= 0.0 => Err("fstop needs a positive f-number".into()),
+            (aperture, stops) => Ok(aperture * 2f64.powf(stops / 2.0)),
+        },
+        is_exact: |arguments, _| arguments[1] % 2.0 == 0.0,
+    },
+    Function {
+        name: "ppi",
+        min_arguments: 3,
+        max_arguments: 3,
+        // pixels along the diagonal divided by the diagonal's length in inches
+        call: |arguments| match (arguments[0], arguments[1], arguments[2]) {
+            (width, height, diagonal) if width <= 0.0 || height <= 0.0 || diagonal <= 0.0 => {
+                Err("ppi needs a positive width, height, and diagonal".into())
+            },
+            (width, height, diagonal) => Ok(width.hypot(height) / diagonal),
+        },
+        is_exact: |arguments, result| {
+            let diagonal_pixels = arguments[0].hypot(arguments[1]);
+            diagonal_pixels.fract() == 0.0 && result.mul_add(arguments[2], -diagonal_pixels) == 0.0
+        },
+    },
+    Function {
+        name: "aspect",
+        min_arguments: 2,
+        max_arguments: 2,
+        // this is the quotient, which `call_function` shows in lowest terms like `16:9` when it can
+        call: |arguments| match (arguments[0], arguments[1]) {
+            (width, height) if width <= 0.0 || height <= 0.0 => Err("aspect needs a positive width and height".into()),
+            (width, height) => Ok(width / height),
+        },
+        is_exact: |arguments, result| result.mul_add(arguments[1], -arguments[0]) == 0.0,
+    },
+    Function {
+        name: "fit_scale",
+        min_arguments: 4,
+        max_arguments: 4,
+        call: |arguments| fit_scale(arguments),
+        is_exact: |arguments, result| fit_scale_is_exact(arguments, result),
+    },
+    Function {
+        name: "letterbox",
+        min_arguments: 4,
+        max_arguments: 4,
+        // the bars above and below fill the height the scaled source leaves empty
+        call: |arguments| {
+            let scale = fit_scale(arguments)?;
+            Ok(((arguments[3] - arguments[1] * scale) / 2.0).max(0.0))
+        },
+        is_exact: |arguments, result| bars_are_exact(arguments[1], arguments[3], arguments, result),
+    },
+    Function {
+        name: "pillarbox",
+        min_arguments: 4,
+        max_arguments: 4,
+        // the bars left and right fill the width the scaled source leaves empty
+        call: |arguments| {
+            let scale = fit_scale(arguments)?;
+            Ok(((arguments[2] - arguments[0] * scale) / 2.0).max(0.0))
+        },
+        is_exact: |arguments, result| bars_are_exact(arguments[0], arguments[2], arguments, result),
+    },
+    Function {
+        name: "transfer_time",
+        min_arguments: 2,
+        max_arguments: 2,
+        // sizes are counted in bytes and rates in bits, so there are 8 bits to move for every byte
+        call: |arguments| match (arguments[0], arguments[1]) {
+            (bytes, _) if bytes < 0.0 => Err("transfer_time needs a size of at least 0 bytes".into()),
+            (_, bits_per_second) if bits_per_second <= 0.0 => Err("transfer_time needs a positive rate in bits per second".into()),
+            (bytes, bits_per_second) => Ok(bytes * 8.0 / bits_per_second),
+        },
+        is_exact: |arguments, result| result.mul_add(arguments[1], -(arguments[0] * 8.0)) == 0.0,
+    },
+    Function {
+        name: "sum",
+        min_arguments: 1,
+        max_arguments: usize::MAX,
+        call: |arguments| Ok(arguments.iter().sum()),
+        is_exact: |arguments, _| sum_is_exact(arguments),
+    },
+    Function {
+        name: "mean",
+        min_arguments: 1,
+        max_arguments: usize::MAX,
+        call: |arguments| Ok(arguments.iter().sum::<f64>() / arguments.len() as f64),
+        // the mean is exact when the sum is, and dividing it by the count leaves no remainder
+        is_exact: |arguments, result| {
+            sum_is_exact(arguments) && result.mul_add(arguments.len() as f64, -arguments.iter().sum::<f64>()) == 0.0
+        },
+    },
+    Function {
+        name: "median",
+        min_arguments: 1,
+        max_arguments: usize::MAX,
+        call: |arguments| {
+            let (lower, upper) = middle(arguments);
+            Ok(lower / 2.0 + upper / 2.0)
+        },
+        // halving is exact, so the median is exact when it is one of the numbers or the sum of the middle two is exact
+        is_exact: |arguments, _| {
+            let (lower, upper) = middle(arguments);
+            lower == upper || sum_is_exact(&[lower, upper])
+        },
+    },
+    Function {
+        name: "stddev",
+        min_arguments: 2,
+        max_arguments: usize::MAX,
+        // the sample standard deviation, which divides by one less than the count like a spreadsheet's STDEV
+        call: |arguments| {
+            let mean = arguments.iter().sum::<f64>() / arguments.len() as f64;
+            let squares: f64 = arguments.iter().map(|x| (x - mean) * (x - mean)).sum();
+            Ok((squares / (arguments.len() - 1) as f64).sqrt())
+        },
+        is_exact: |_, result| result == 0.0,
+    },
+    Function {
+        name: "min",
+        min_arguments: 1,
+        max_arguments: usize::MAX,
+        call: |arguments| Ok(arguments.iter().copied().fold(f64::INFINITY, f64::min)),
+        is_exact: |_, _| true,
+    },
+    Function {
+        name: "max",
+        min_arguments: 1,
+        max_arguments: usize::MAX,
+        call: |arguments| Ok(arguments.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+        is_exact: |_, _| true,
+    },
+];
+
+// a sum of whole numbers is exact as long as no partial sum can pass the largest exact integer
+fn sum_is_exact(arguments: &[f64]) -> bool {
+    arguments.iter().all(|x| x.fract() == 0.0) && arguments.iter().map(|x| x.abs()).sum::<f64>() <= MAX_EXACT_INTEGER
+}
+
+// the two middle numbers of `arguments` once sorted, which are the same number when there is an odd count
+fn middle(arguments: &[f64]) -> (f64, f64) {
+    let mut sorted = arguments.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    (sorted[(sorted.len() - 1) / 2], sorted[sorted.len() / 2])
+}
+
+// the largest factor a `source_width` x `source_height` picture can be scaled by and still fit
+// inside `target_width` x `target_height`, which is the smaller of the two axis ratios
+fn fit_scale(arguments: &[f64]) -> Result<f64, Box<dyn std::error::Error>> {
+    if arguments.iter().any(|&size| size <= 0.0) {
+        return Err("fitting a picture needs positive source and target sizes".into());
+    }
+    let (source_width, source_height, target_width, target_height) = (arguments[0], arguments[1], arguments[2], arguments[3]);
+    Ok((target_width / source_width).min(target_height / source_height))
+}
+
+// the scale is exact when it maps one side of the source exactly onto the target
+fn fit_scale_is_exact(arguments: &[f64], scale: f64) -> bool {
+    scale.mul_add(arguments[0], -arguments[2]) == 0.0 || scale.mul_add(arguments[1], -arguments[3]) == 0.0
+}
+
+// the bars are exact when the scale is, and the scaled `source` plus both bars gives back exactly `target`
+fn bars_are_exact(source: f64, target: f64, arguments: &[f64], bar: f64) -> bool {
+    let Ok(scale) = fit_scale(arguments) else {
+        return false;
+    };
+    fit_scale_is_exact(arguments, scale) && source.mul_add(scale, 2.0 * bar - target) == 0.0
+}
+
+/// Looks up a builtin function by name
+/// # Parameters
+///  - `name`: the name the user typed
+/// # Returns
+///  - `Some(function)`: when there is a builtin called `name`
+///  - `None`: when there is no such builtin
+pub fn find_function(name: &str) -> Option<&'static Function> {
+    FUNCTIONS.iter().find(|function| function.name == name)
+}
+
+/// The builtin functions of an angle, which takes a plain number in radians or degrees depending on the `AngleMode`
+pub const ANGLE_FUNCTIONS: &[&str] = &["sin", "cos", "tan"];
+
+/// Computes `sin`, `cos`, or `tan` of an angle in degrees.<br>
+/// Like on a handheld calculator, angles where the result is `0`, a half, or `1`, like `sin(30)`, give exactly that,
+/// rather than the nearly equal result of first rounding the angle to radians
+/// # Parameters
+///  - `name`: one of the `ANGLE_FUNCTIONS`
+///  - `degrees`: the angle
+/// # Returns
+///  - `Ok((result, exact))`: the result, and whether it is exactly the mathematical result
+///  - `Err(call_error)`: for `tan` of 90 degrees plus a multiple of 180, where it is undefined, or a `name` that isn't an angle function
+pub fn call_in_degrees(name: &str, degrees: f64) -> Result<(f64, bool), Box<dyn std::error::Error>> {
+    match name {
+        "sin" => Ok(sin_degrees(degrees)),
+        "cos" => Ok(cos_degrees(degrees)),
+        "tan" => {
+            let ((sine, sine_is_exact), (cosine, cosine_is_exact)) = (sin_degrees(degrees), cos_degrees(degrees));
+            if cosine == 0.0 {
+                return Err("tan is undefined at 90 degrees plus any multiple of 180".into());
+            }
+            Ok((sine / cosine, sine_is_exact && cosine_is_exact))
+        },
+        _ => Err(format!("`{}` isn't a function of an angle", name).into()),
+    }
+}
+
+// the sine of an angle in degrees, and whether it is exact.
+// the angle is folded into 0 to 90 degrees with `sin(x + 180) = -sin(x)` and `sin(180 - x) = sin(x)`,
+// which are exact in floating point, so the multiples of 30 degrees can be looked up
+fn sin_degrees(degrees: f64) -> (f64, bool) {
+    let degrees = degrees.rem_euclid(360.0);
+    let (degrees, sign) = if degrees >= 180.0 { (degrees - 180.0, -1.0) } else { (degrees, 1.0) };
+    let degrees = if degrees > 90.0 { 180.0 - degrees } else { degrees };
+
+    // adding `0.0` turns the `-0` of `sin(180)` into `0`
+    let (sine, exact) = if degrees == 0.0 {
+        (0.0, true)
+    } else if degrees == 30.0 {
+        (0.5, true)
+    } else if degrees == 90.0 {
+        (1.0, true)
+    } else {
+        (degrees.to_radians().sin(), false)
+    };
+    (sign * sine + 0.0, exact)
+}
+
+// the cosine of an angle in degrees, and whether it is exact, from `cos(x) = cos(-x) = sin(90 - x)`
+fn cos_degrees(degrees: f64) -> (f64, bool) {
+    let degrees = degrees.rem_euclid(360.0);
+    let degrees = if degrees > 180.0 { 360.0 - degrees } else { degrees };
+    sin_degrees(90.0 - degrees)
+}
@@ -0,0 +1,655 @@
+/// Documentation for one builtin, shown by `:help`
+pub struct HelpEntry {
+    /// the symbol or command the user types
+    pub name: &'static str,
+    /// how to use it
+    pub signature: &'static str,
+    /// what it does
+    pub description: &'static str,
+    /// which inputs are valid and what outputs to expect
+    pub domain: &'static str,
+    /// sample inputs paired with their results
+    pub examples: &'static [(&'static str, &'static str)],
+}
+
+/// Every documented builtin, in the order `:help` lists them
+pub const HELP_ENTRIES: &[HelpEntry] = &[
+    HelpEntry {
+        name: "+",
+        signature: "a + b",
+        description: "Adds two numbers",
+        domain: "any real a and b",
+        examples: &[("2 + 3", "5"), ("1.5 + 0.25", "1.75")],
+    },
+    HelpEntry {
+        name: "-",
+        signature: "a - b | -a",
+        description: "Subtracts b from a, or negates a. Negation binds looser than ^, so -2 ^ 2 is -(2 ^ 2)",
+        domain: "any real a and b",
+        examples: &[("5 - 3", "2"), ("3 - 5", "-2"), ("2 * -4", "-8"), ("-2 ^ 2", "-4")],
+    },
+    HelpEntry {
+        name: "*",
+        signature: "a * b",
+        description: "Multiplies two numbers. The * can be left out between a number or ) and a following ( or name, so 2pi is 2 * pi",
+        domain: "any real a and b",
+        examples: &[("4 * 2.5", "10"), ("2(3 + 4)", "14"), ("(1 + 2)(3 + 4)", "21")],
+    },
+    HelpEntry {
+        name: "/",
+        signature: "a / b",
+        description: "Divides a by b",
+        domain: "any real a, any nonzero b",
+        examples: &[("7 / 2", "3.5"), ("1 / 0", "Divide by zero error")],
+    },
+    HelpEntry {
+        name: "//",
+        signature: "a // b",
+        description: "The integer quotient of a divided by b, matching % so that a = b * (a // b) + a % b. For a positive b this rounds a / b down",
+        domain: "any real a, any nonzero b",
+        examples: &[("7 // 2", "3"), ("-7 // 2", "-4"), ("7.5 // 2", "3")],
+    },
+    HelpEntry {
+        name: "%",
+        signature: "a % b | a%",
+        description: "The Euclidean remainder of a divided by b, which is always between 0 and |b| whatever the signs of a and b. A % with no number, name, or ( after it is a percentage instead: a hundredth of a, or that share of the left side of + and -. Write a % (-b) for the remainder of a negative b",
+        domain: "any real a, any nonzero b",
+        examples: &[("17 % 5", "2"), ("-7 % 3", "2"), ("7 % (-3)", "1"), ("200 * 15%", "30"), ("100 + 10%", "110"), ("100 - 10%", "90")],
+    },
+    HelpEntry {
+        name: "^",
+        signature: "a ^ b",
+        description: "Raises a to the power of b",
+        domain: "any real or complex a and b; a negative a with a fractional b gives the principal complex power",
+        examples: &[("2 ^ 10", "1024"), ("9 ^ 0.5", "3"), ("2 ^ 100", "1267650600228229401496703205376"), ("(-4) ^ 0.5", "2i")],
+    },
+    HelpEntry {
+        name: ".*",
+        signature: "a .* b",
+        description: "Multiplies matrices element by element, where * would be the matrix product. On numbers it is the same as *",
+        domain: "matrices of the same shape, or a single row or column where they differ",
+        examples: &[("[1, 2, 3] .* [4, 5, 6]", "[4  10  18]"), ("[1, 2] .* 3", "[3  6]")],
+    },
+    HelpEntry {
+        name: "./",
+        signature: "a ./ b",
+        description: "Divides matrices element by element, since / can't divide by a matrix. On numbers it is the same as /",
+        domain: "matrices of the same shape, or a single row or column where they differ; no element of b can be zero",
+        examples: &[("[1, 2, 3] ./ [4, 5, 6]", "[0.25  0.4  0.5]"), ("1 ./ [2, 4]", "[0.5  0.25]")],
+    },
+    HelpEntry {
+        name: ".^",
+        signature: "a .^ b",
+        description: "Raises matrices to a power element by element, where ^ would multiply a square matrix by itself. On numbers it is the same as ^",
+        domain: "matrices of the same shape, or a single row or column where they differ",
+        examples: &[("[1, 2, 3] .^ 2", "[1  4  9]"), ("2 .^ [1, 2, 3]", "[2  4  8]")],
+    },
+    HelpEntry {
+        name: "!",
+        signature: "n!",
+        description: "The factorial of n, the product of every whole number from 1 to n. Whole numbers typed without a point stay exact at any size",
+        domain: "whole n from 0 to 3248, since a result past 10000 digits is an error",
+        examples: &[("5!", "120"), ("0!", "1")],
+    },
+    HelpEntry {
+        name: "&",
+        signature: "a & b",
+        description: "The bitwise and of two whole numbers, using their two's complement bits",
+        domain: "whole a and b of any size, or between -2^53 and 2^53 when they are floating point",
+        examples: &[("6 & 3", "2"), ("0xFF & 0b1010", "10"), ("(2 ^ 64 + 1) & 1", "1")],
+    },
+    HelpEntry {
+        name: "|",
+        signature: "a | b",
+        description: "The bitwise or of two whole numbers, using their two's complement bits",
+        domain: "whole a and b of any size, or between -2^53 and 2^53 when they are floating point",
+        examples: &[("6 | 3", "7")],
+    },
+    HelpEntry {
+        name: "xor",
+        signature: "a xor b",
+        description: "The bitwise exclusive or of two whole numbers. It is spelled out because ^ raises to a power",
+        domain: "whole a and b of any size, or between -2^53 and 2^53 when they are floating point",
+        examples: &[("6 xor 3", "5")],
+    },
+    HelpEntry {
+        name: "<<",
+        signature: "a << b",
+        description: "Shifts the bits of a whole number left by b places, multiplying it by 2 ^ b",
+        domain: "whole a of any size and whole b >= 0 up to a result of 10000 digits, or a between -2^53 and 2^53 and b from 0 to 63 when a is floating point",
+        examples: &[("1 << 4", "16"), ("1 << 2 + 1", "8"), ("1 << 100", "1267650600228229401496703205376")],
+    },
+    HelpEntry {
+        name: ">>",
+        signature: "a >> b",
+        description: "Shifts the bits of a whole number right by b places, keeping its sign, which rounds a / 2 ^ b down",
+        domain: "whole a of any size and whole b >= 0, or a between -2^53 and 2^53 and b from 0 to 63 when a is floating point",
+        examples: &[("16 >> 2", "4"), ("-8 >> 1", "-4")],
+    },
+    HelpEntry {
+        name: "~",
+        signature: "~a",
+        description: "The bitwise not of a whole number, which flips every bit so ~a is -a - 1. Like a sign it binds looser than ^",
+        domain: "whole a of any size, or between -2^53 and 2^53 when it is floating point",
+        examples: &[("~5", "-6"), ("~0", "-1")],
+    },
+    HelpEntry {
+        name: "==",
+        signature: "a == b",
+        description: "True when a and b are exactly equal. Like every comparison, chains check each neighbouring pair, so a == b == c is a == b and b == c",
+        domain: "any real a and b; the result is true or false",
+        examples: &[("2 + 2 == 4", "true"), ("0.1 + 0.2 == 0.3", "false")],
+    },
+    HelpEntry {
+        name: "!=",
+        signature: "a != b",
+        description: "True when a and b are not exactly equal",
+        domain: "any real a and b; the result is true or false",
+        examples: &[("1 != 2", "true")],
+    },
+    HelpEntry {
+        name: "<",
+        signature: "a < b",
+        description: "True when a is less than b. 1 < x < 3 checks both 1 < x and x < 3",
+        domain: "any real a and b; the result is true or false",
+        examples: &[("2 ^ 10 < 1000", "false"), ("1 < 2 < 3", "true")],
+    },
+    HelpEntry {
+        name: "<=",
+        signature: "a <= b",
+        description: "True when a is less than or equal to b",
+        domain: "any real a and b; the result is true or false",
+        examples: &[("3 <= 3", "true")],
+    },
+    HelpEntry {
+        name: ">",
+        signature: "a > b",
+        description: "True when a is greater than b",
+        domain: "any real a and b; the result is true or false",
+        examples: &[("2 ^ 10 > 1000", "true")],
+    },
+    HelpEntry {
+        name: ">=",
+        signature: "a >= b",
+        description: "True when a is greater than or equal to b",
+        domain: "any real a and b; the result is true or false",
+        examples: &[("2 >= 3", "false")],
+    },
+    HelpEntry {
+        name: "and",
+        signature: "a and b",
+        description: "True when both a and b are true, where any nonzero number counts as true. b isn't evaluated when a is false",
+        domain: "any real a and b; the result is true or false",
+        examples: &[("1 < 2 and 2 < 3", "true"), ("0 and 1 / 0", "false")],
+    },
+    HelpEntry {
+        name: "or",
+        signature: "a or b",
+        description: "True when a or b is true, where any nonzero number counts as true. b isn't evaluated when a is true",
+        domain: "any real a and b; the result is true or false",
+        examples: &[("1 > 2 or 2 < 3", "true")],
+    },
+    HelpEntry {
+        name: "not",
+        signature: "not a",
+        description: "True when a is false or 0. It binds looser than comparisons, so not a < b is not (a < b)",
+        domain: "any real a; the result is true or false",
+        examples: &[("not 1 > 2", "true"), ("not 0", "true")],
+    },
+    HelpEntry {
+        name: "?",
+        signature: "condition ? a : b",
+        description: "a when the condition is true or nonzero, otherwise b. Only the chosen branch is evaluated, and it binds looser than every operator",
+        domain: "any real condition, a, and b",
+        examples: &[("x = -3", "-3"), ("x < 0 ? -x : x", "3"), ("0 ? 1 / 0 : 2", "2")],
+    },
+    HelpEntry {
+        name: "in",
+        signature: "a in unit",
+        description: "Shows a quantity in another unit. It binds looser than every operator, so the whole left side is converted",
+        domain: "a quantity and a unit that measure the same thing, like a length and km",
+        examples: &[("60 mph * 2 h in mi", "120 mi"), ("5 km + 3 mi in m", "9828.032 m"), ("(3 m)^2 in ft^2", "96.8751937503875 ft^2")],
+    },
+    HelpEntry {
+        name: "units",
+        signature: "number unit",
+        description: "A number followed by a unit is a quantity, and binds tighter than * and / so 100 km / 2 h is a speed. \
+            Units can be multiplied, divided, and raised to whole powers, like m/s^2. \
+            Lengths: m km cm mm inch ft yd mi. Areas: ha acre. Volumes: L mL gal. Masses: kg g mg tonne lb oz. \
+            Times: s ms min h day week. Frequencies: Hz kHz MHz GHz. Speeds: mph kph knot. \
+            Forces and pressures: N Pa kPa bar atm psi. Energies and powers: J kJ cal kcal Wh kWh W kW. \
+            Electricity and temperature: A V K. Angles: deg rad. Data: bit kbit Mbit Gbit B kB MB GB TB KiB MiB GiB TiB. \
+            Data rates: bps kbps Mbps Gbps",
+        domain: "quantities can only be added, subtracted, and compared when they measure the same thing",
+        examples: &[("100 km / 2 h in mph", "31.0685596118667 mph"), ("2 kg * 9.81 m/s^2", "19.62 kg*m/s^2"), ("1 km / 1 m", "1000")],
+    },
+    HelpEntry {
+        name: "currencies",
+        signature: "number CODE",
+        description: "Money in a currency, named by its three letter code like USD or EUR, which works like a unit. \
+            USD is always known, and other currencies need rates from --rates file, a TOML or JSON file like EUR = 0.92 \
+            saying how much of each currency one of a base currency buys",
+        domain: "currencies that have a rate, converted and added at those rates",
+        examples: &[("100 USD in EUR", "92 EUR"), ("25 USD/h * 40 h", "1000 USD")],
+    },
+    HelpEntry {
+        name: "dates",
+        signature: "YYYY-MM-DD",
+        description: "The start of a day in UTC. A length of time can be added to or subtracted from a date, \
+            and subtracting two dates gives the time between them in days",
+        domain: "days of the Gregorian calendar with a 4 digit year, which can be compared with each other",
+        examples: &[("2024-03-01 + 45 days", "2024-04-15"), ("2024-03-01 - 1990-07-14", "12284 days"), ("2024-01-31 + 36 h", "2024-02-01 12:00:00")],
+    },
+    HelpEntry {
+        name: "now",
+        signature: "now",
+        description: "The current date and time in UTC, from the system clock",
+        domain: "a date, which changes every time it is evaluated",
+        examples: &[("now - 1990-07-14 in weeks", "1891.74 weeks"), ("now > 2024-01-01", "true")],
+    },
+    HelpEntry {
+        name: "[]",
+        signature: "[a, b; c, d]",
+        description: "A matrix of real numbers, with , between the elements of a row and ; between rows. A single row like [1, 2, 3] works as a list. \
+            * of two matrices is the matrix product, and ^ multiplies a square matrix by itself. \
+            Every other operation, including .* ./ and .^, works element by element, \
+            repeating a number, or a single row or column, across the other operand",
+        domain: "every row needs as many elements as the first; * of two matrices needs the columns of the left to match the rows of the right; \
+            ^ needs a square matrix and a whole power >= 0; / can't divide by a matrix; == and != compare whole matrices, and the other comparisons don't work on them",
+        examples: &[("[1, 2] * [3; 4]", "[11]"), ("sum([1, 1; 0, 1] ^ 3)", "5"), ("[1, 2, 3] + 1", "[2  3  4]"), ("2 * [1, 2]", "[2  4]")],
+    },
+    HelpEntry {
+        name: "transpose",
+        signature: "transpose(matrix)",
+        description: "Swaps the rows and columns of a matrix. rows(matrix) and columns(matrix) give its size",
+        domain: "any matrix",
+        examples: &[("transpose([1; 2])", "[1  2]"), ("rows([1, 2, 3])", "1")],
+    },
+    HelpEntry {
+        name: "()",
+        signature: "(expression)",
+        description: "Groups an expression so it is evaluated before the operators around it. Without parentheses ^ is evaluated first, then * / // %, then + -, then << >>, then &, then xor, then |, then comparisons, then not, then and, then or, then ? :",
+        domain: "any expression",
+        examples: &[("2 + 3 * 4", "14"), ("(2 + 3) * 4", "20")],
+    },
+    HelpEntry {
+        name: "=",
+        signature: "name = expression",
+        description: "Stores the value of an expression in a variable, which can then be used by name in later inputs",
+        domain: "any name that isn't a builtin constant or function",
+        examples: &[("x = 5", "5"), ("x * 3", "15")],
+    },
+    HelpEntry {
+        name: "ans",
+        signature: "ans",
+        description: "The result of the most recent successful calculation",
+        domain: "any result; it can't be assigned",
+        examples: &[("2 + 3", "5"), ("ans * 2", "10")],
+    },
+    HelpEntry {
+        name: "pi",
+        signature: "pi",
+        description: "The ratio of a circle's circumference to its diameter",
+        domain: "a constant, 3.141592653589793",
+        examples: &[("2 * pi", "6.283185307179586")],
+    },
+    HelpEntry {
+        name: "e",
+        signature: "e",
+        description: "Euler's number, the base of the natural logarithm",
+        domain: "a constant, 2.718281828459045",
+        examples: &[("ln(e)", "1")],
+    },
+    HelpEntry {
+        name: "tau",
+        signature: "tau",
+        description: "The ratio of a circle's circumference to its radius, 2 * pi",
+        domain: "a constant, 6.283185307179586",
+        examples: &[("tau / 4", "1.5707963267948966")],
+    },
+    HelpEntry {
+        name: "i",
+        signature: "i | bi",
+        description: "The imaginary unit, whose square is -1. A number directly followed by i, like 4i, is imaginary. + - * / ^ == != and the functions sqrt exp ln sin cos tan abs work on complex numbers",
+        domain: "a reserved name; it can't be assigned",
+        examples: &[("i ^ 2", "-1"), ("(3 + 4i) * (1 - 2i)", "11-2i"), ("abs(3 + 4i)", "5")],
+    },
+    HelpEntry {
+        name: "sqrt",
+        signature: "sqrt(x)",
+        description: "The square root of x",
+        domain: "any x; a negative or complex x gives a complex result",
+        examples: &[("sqrt(16)", "4"), ("sqrt(2)", "1.4142135623730951"), ("sqrt(-4)", "2i")],
+    },
+    HelpEntry {
+        name: "sin",
+        signature: "sin(x)",
+        description: "The sine of the angle x, in radians, or in degrees after :mode deg. An angle with units like 30 deg works in either mode",
+        domain: "any real x; the result is between -1 and 1",
+        examples: &[("sin(0)", "0"), ("sin(1.5707963267948966)", "1"), ("sin(30 deg)", "0.5")],
+    },
+    HelpEntry {
+        name: "cos",
+        signature: "cos(x)",
+        description: "The cosine of the angle x, in radians, or in degrees after :mode deg. An angle with units like 60 deg works in either mode",
+        domain: "any real x; the result is between -1 and 1",
+        examples: &[("cos(0)", "1"), ("cos(60) (with :mode deg)", "0.5")],
+    },
+    HelpEntry {
+        name: "tan",
+        signature: "tan(x)",
+        description: "The tangent of the angle x, in radians, or in degrees after :mode deg. An angle with units like 45 deg works in either mode",
+        domain: "any real x, except 90 degrees plus a multiple of 180 in degrees; the result is any real number",
+        examples: &[("tan(0)", "0"), ("tan(0.7853981633974483)", "0.9999999999999999"), ("tan(45 deg)", "1")],
+    },
+    HelpEntry {
+        name: "log",
+        signature: "log(x) | log(x, base)",
+        description: "The logarithm of x in the given base, or base 10 when no base is given",
+        domain: "x > 0, base > 0 and not 1; the result is any real number",
+        examples: &[("log(1000)", "3"), ("log(8, 2)", "3")],
+    },
+    HelpEntry {
+        name: "ln",
+        signature: "ln(x)",
+        description: "The natural logarithm of x",
+        domain: "x != 0; a negative or complex x gives a complex result",
+        examples: &[("ln(1)", "0"), ("ln(10)", "2.302585092994046"), ("ln(-1)", "3.141592653589793i")],
+    },
+    HelpEntry {
+        name: "exp",
+        signature: "exp(x)",
+        description: "e raised to the power of x",
+        domain: "any real x; the result is > 0",
+        examples: &[("exp(0)", "1"), ("exp(1)", "2.718281828459045")],
+    },
+    HelpEntry {
+        name: "abs",
+        signature: "abs(x)",
+        description: "The absolute value of x",
+        domain: "any x; for a complex x it is the magnitude. The result is >= 0",
+        examples: &[("abs(-3.5)", "3.5"), ("abs(3 + 4i)", "5")],
+    },
+    HelpEntry {
+        name: "re",
+        signature: "re(z)",
+        description: "The real part of z",
+        domain: "any z",
+        examples: &[("re(3 + 4i)", "3")],
+    },
+    HelpEntry {
+        name: "im",
+        signature: "im(z)",
+        description: "The imaginary part of z",
+        domain: "any z",
+        examples: &[("im(3 + 4i)", "4")],
+    },
+    HelpEntry {
+        name: "arg",
+        signature: "arg(z)",
+        description: "The angle of z from the positive real axis in radians",
+        domain: "any z; the result is between -pi and pi",
+        examples: &[("arg(i)", "1.5707963267948966"), ("arg(-1)", "3.141592653589793")],
+    },
+    HelpEntry {
+        name: "conj",
+        signature: "conj(z)",
+        description: "The complex conjugate of z, with the sign of its imaginary part flipped",
+        domain: "any z",
+        examples: &[("conj(3 + 4i)", "3-4i")],
+    },
+    HelpEntry {
+        name: "midi_to_freq",
+        signature: "midi_to_freq(note)",
+        description: "The frequency in Hz of a MIDI note number, with A4 = note 69 = 440 Hz in equal temperament",
+        domain: "any real note, fractional notes are detuned; the result is > 0",
+        examples: &[("midi_to_freq(69)", "440"), ("midi_to_freq(60)", "261.6255653005986")],
+    },
+    HelpEntry {
+        name: "freq_to_midi",
+        signature: "freq_to_midi(hz)",
+        description: "The MIDI note number of a frequency in Hz, the inverse of midi_to_freq. A fractional part is how far off the nearest note the frequency is",
+        domain: "hz > 0; the result is any real number",
+        examples: &[("freq_to_midi(440)", "69"), ("freq_to_midi(880)", "81")],
+    },
+    HelpEntry {
+        name: "db",
+        signature: "db(ratio)",
+        description: "An amplitude (voltage, pressure) ratio in decibels: 20 * log(ratio). For a power ratio use 10 * log(ratio)",
+        domain: "ratio > 0; the result is any real number",
+        examples: &[("db(2)", "6.020599913279624"), ("db(0.5)", "-6.020599913279624")],
+    },
+    HelpEntry {
+        name: "undb",
+        signature: "undb(decibels)",
+        description: "The amplitude ratio of a level in decibels, the inverse of db: 10 ^ (decibels / 20)",
+        domain: "any real decibels; the result is > 0",
+        examples: &[("undb(20)", "10"), ("undb(-6)", "0.5011872336272722")],
+    },
+    HelpEntry {
+        name: "ev",
+        signature: "ev(aperture, shutter) | ev(aperture, shutter, iso)",
+        description: "The exposure value of an f-number and shutter time in seconds, normalised to ISO 100. Each step of 1 is one stop",
+        domain: "positive aperture, shutter, and iso (default 100); the result is any real number",
+        examples: &[("ev(16, 1 / 125)", "14.965784284662087"), ("ev(2.8, 1 / 60, 400)", "6.877744249949002")],
+    },
+    HelpEntry {
+        name: "fstop",
+        signature: "fstop(aperture, stops)",
+        description: "The f-number that lets in `stops` stops less light than `aperture`; negative stops open up. f/2.8 + 2 stops is fstop(2.8, 2)",
+        domain: "aperture > 0, any real stops; the result is > 0",
+        examples: &[("fstop(2.8, 2)", "5.6"), ("fstop(8, -1)", "5.656854249492381")],
+    },
+    HelpEntry {
+        name: "ppi",
+        signature: "ppi(width_px, height_px, diagonal_in)",
+        description: "The pixel density of a screen from its resolution and its diagonal size in inches",
+        domain: "width_px > 0, height_px > 0, diagonal_in > 0",
+        examples: &[("ppi(1920, 1080, 24)", "91.7877987534291"), ("ppi(3000, 4000, 10)", "500")],
+    },
+    HelpEntry {
+        name: "aspect",
+        signature: "aspect(width, height)",
+        description: "The aspect ratio width:height in lowest terms, which works like the fraction width / height in arithmetic",
+        domain: "width > 0, height > 0",
+        examples: &[("aspect(1920, 1080)", "16:9"), ("aspect(1024, 768)", "4:3"), ("aspect(2.39, 1)", "239:100"), ("aspect(1920, 1080) * 1080", "1920")],
+    },
+    HelpEntry {
+        name: "fit_scale",
+        signature: "fit_scale(source_w, source_h, target_w, target_h)",
+        description: "The largest factor a source picture can be scaled by while still fitting inside the target, keeping its aspect ratio",
+        domain: "every size > 0",
+        examples: &[("fit_scale(1280, 720, 1920, 1080)", "1.5"), ("fit_scale(1920, 1080, 1080, 1920)", "0.5625")],
+    },
+    HelpEntry {
+        name: "letterbox",
+        signature: "letterbox(source_w, source_h, target_w, target_h)",
+        description: "The height of each bar above and below a source picture scaled to fit the target, 0 when the bars are on the sides instead",
+        domain: "every size > 0",
+        examples: &[("letterbox(1920, 800, 1920, 1080)", "140"), ("letterbox(1440, 1080, 1920, 1080)", "0")],
+    },
+    HelpEntry {
+        name: "pillarbox",
+        signature: "pillarbox(source_w, source_h, target_w, target_h)",
+        description: "The width of each bar left and right of a source picture scaled to fit the target, 0 when the bars are above and below instead",
+        domain: "every size > 0",
+        examples: &[("pillarbox(1440, 1080, 1920, 1080)", "240"), ("pillarbox(1920, 800, 1920, 1080)", "0")],
+    },
+    HelpEntry {
+        name: "transfer_time",
+        signature: "transfer_time(size, rate)",
+        description: "How long it takes to send an amount of data at a rate, like transfer_time(2.5 GiB, 40 Mbit/s), \
+            shown in seconds, minutes, hours, or days, whichever it is at least one of, to 3 significant digits. Plain numbers are a size in bytes and a rate in bits per second, \
+            and give a number of seconds",
+        domain: "size >= 0, rate > 0; both have units of data, or neither does",
+        examples: &[("transfer_time(2.5 GiB, 40 Mbit/s)", "8.95 min"), ("transfer_time(1 kB, 8 kbit/s)", "1 s"), ("transfer_time(1000, 8000)", "1")],
+    },
+    HelpEntry {
+        name: "sum",
+        signature: "sum(x, y, ...)",
+        description: "The sum of a list of numbers. Like the other list functions, a matrix argument adds its elements to the list",
+        domain: "one or more real numbers",
+        examples: &[("sum(1, 2, 3)", "6"), ("sum([1, 2; 3, 4])", "10")],
+    },
+    HelpEntry {
+        name: "mean",
+        signature: "mean(x, y, ...)",
+        description: "The average of a list of numbers, their sum divided by how many there are",
+        domain: "one or more real numbers",
+        examples: &[("mean(1, 2, 3, 4)", "2.5")],
+    },
+    HelpEntry {
+        name: "median",
+        signature: "median(x, y, ...)",
+        description: "The middle of a list of numbers once sorted, or the average of the middle two when there is an even count",
+        domain: "one or more real numbers",
+        examples: &[("median(3, 1, 2)", "2"), ("median(1, 2, 3, 4)", "2.5")],
+    },
+    HelpEntry {
+        name: "stddev",
+        signature: "stddev(x, y, ...)",
+        description: "The sample standard deviation of a list of numbers, which divides by one less than the count",
+        domain: "two or more real numbers; the result is at least 0",
+        examples: &[("stddev(2, 4, 4, 4, 5, 5, 7, 9)", "2.138089935299395")],
+    },
+    HelpEntry {
+        name: "min",
+        signature: "min(x, y, ...)",
+        description: "The smallest of a list of numbers. Without parentheses min is minutes",
+        domain: "one or more real numbers",
+        examples: &[("min(3, 1, 2)", "1")],
+    },
+    HelpEntry {
+        name: "max",
+        signature: "max(x, y, ...)",
+        description: "The largest of a list of numbers",
+        domain: "one or more real numbers",
+        examples: &[("max(3, 1, 2)", "3")],
+    },
+    HelpEntry {
+        name: "diff",
+        signature: "diff(expression, variable)",
+        description: "The derivative of an expression by one of its variables, worked out symbolically. \
+            The variables don't need values, and any other name is held constant. Trig functions are differentiated in radians",
+        domain: "expressions made of + - * / ^ and sqrt sin cos tan exp ln log abs",
+        examples: &[("diff(x ^ 2 + 3x, x)", "2 * x + 3"), ("diff(sin(2x), x)", "2 * cos(2 * x)")],
+    },
+    HelpEntry {
+        name: "simplify",
+        signature: "simplify(expression)",
+        description: "Rewrites an expression in a simpler form without needing values for its variables: \
+            x * 1 and x + 0 become x, parts without names like 1/3 + 1/6 or sin(0) are worked out when the result is exact, \
+            powers of the same base are multiplied by adding their exponents, so x^2 * x becomes x ^ 3, \
+            and like terms are collected, even with their factors in a different order, so 2x + 3x becomes 5 * x \
+            and x * y + y * x becomes 2 * x * y. 0 * x becomes 0 only when x is sure to be finite. The results of diff are simplified the same way",
+        domain: "expressions made of numbers, names, function calls, and operators",
+        examples: &[("simplify(x * 1 + 0)", "x"), ("simplify(x + 2 - x + 3y + 1)", "3 * y + 3"), ("simplify(x ^ 2 * x)", "x ^ 3")],
+    },
+    HelpEntry {
+        name: "solve",
+        signature: "solve(lhs = rhs, variable) | solve(expression, variable)",
+        description: "The values of a variable that make both sides of an equation equal, or an expression 0. \
+            The solutions are a list, even when there is only one. Other names in the equation use their values",
+        domain: "linear and quadratic equations in the variable with real solutions",
+        examples: &[("solve(2x + 3 = 11, x)", "[4]"), ("solve(x ^ 2 = 4, x)", "[-2, 2]")],
+    },
+    HelpEntry {
+        name: "roots",
+        signature: "roots(polynomial) | roots(polynomial, variable) | roots([coefficients])",
+        description: "Every root of a polynomial, real or complex, found numerically. The variable is x unless another is given, \
+            and coefficients start with the highest power. The roots are a list, which functions like sum take",
+        domain: "polynomials of degree 1 to 64 with finite coefficients",
+        examples: &[("roots(x ^ 3 - x)", "[-1, 0, 1]"), ("roots([1, 0, -4])", "[-2, 2]"), ("roots(x ^ 2 + 1)", "[-i, i]")],
+    },
+    HelpEntry {
+        name: ":warnings",
+        signature: ":warnings on|off",
+        description: "Shows or hides precision loss warnings",
+        domain: "on or off",
+        examples: &[(":warnings off", "")],
+    },
+    HelpEntry {
+        name: ":verbose-results",
+        signature: ":verbose-results [on|off]",
+        description: "Shows the type and exactness of each result, e.g. `42  <int, exact>`, followed by the units of a quantity, e.g. `5 km  <quantity, exact, km>`, and the base of a result that isn't shown in base 10, e.g. `0x1.8p+3  <int, exact, base 16>`",
+        domain: "on, off, or nothing to toggle",
+        examples: &[(":verbose-results", "")],
+    },
+    HelpEntry {
+        name: ":hex-float",
+        signature: ":hex-float [on|off]",
+        description: "Shows results as C99 hexadecimal floating point literals, revealing every bit of the value. Hex float literals like 0x1.8p3 are always accepted as input",
+        domain: "on, off, or nothing to toggle",
+        examples: &[("0x1.8p3", "12"), ("0.1 (with :hex-float on)", "0x1.999999999999ap-4")],
+    },
+    HelpEntry {
+        name: ":mode",
+        signature: ":mode [float|fraction|decimal|interval|rad|deg]",
+        description: "Changes how numbers are represented. float uses 64 bit floating point. fraction keeps exact fractions. decimal keeps exact base 10 numbers of any size, rounding quotients like 1/3 to 50 significant digits. Both fall back to floating point for results like sqrt(2) that they can't hold. interval keeps bounds that the true result is always between, shown as the midpoint ± the distance to the bounds; + - * /, powers of positive numbers, sqrt, exp, ln, log, abs, sin, cos and tan keep the bounds, and anything else uses the midpoint with a warning that the bounds were dropped. rad and deg choose the unit of a plain number given to sin, cos, and tan, separately from how numbers are represented. With nothing after it, shows the current modes",
+        domain: "float, fraction, decimal, interval, rad, deg, or nothing to show the modes. Number literals keep up to 17 significant digits in every mode",
+        examples: &[("1/3 + 1/6 (with :mode fraction)", "1/2"), ("0.1 + 0.2 (with :mode decimal)", "0.3"), ("2 ^ 100 (with :mode decimal)", "1267650600228229401496703205376"), ("0.1 + 0.2 (with :mode interval)", "0.30000000000000004 ± 2e-16"), ("sin(30) (with :mode deg)", "0.5")],
+    },
+    HelpEntry {
+        name: ":polar",
+        signature: ":polar [on|off]",
+        description: "Shows complex results in polar form, their magnitude then their angle in degrees, instead of like 3+4i",
+        domain: "on, off, or nothing to toggle",
+        examples: &[("3 + 4i (with :polar on)", "5∠53.13010235415598°")],
+    },
+    HelpEntry {
+        name: ":write",
+        signature: ":write expression > file",
+        description: "Evaluates an expression like any other input, then adds the result line to the end of a file",
+        domain: "any expression, and a file that can be created or appended to",
+        examples: &[(":write 2 + 3 > results.txt", "2 + 3 = 5")],
+    },
+    HelpEntry {
+        name: ":help",
+        signature: ":help [name]",
+        description: "Lists everything that has documentation, or shows the documentation for one name",
+        domain: "any documented name",
+        examples: &[(":help ^", "")],
+    },
+    HelpEntry {
+        name: "exit",
+        signature: "exit | quit | q | :q | :exit | exit(code)",
+        description: "Quits the calculator, optionally with a process exit code",
+        domain: "code is a 32 bit integer",
+        examples: &[("exit(3)", "")],
+    },
+];
+
+/// Looks up the documentation for `name` in `HELP_ENTRIES`
+/// # Parameters
+///  - `name`: the symbol or command to look up
+/// # Returns
+///  - `Some(entry)`: when `name` is documented
+///  - `None`: when `name` is not documented
+pub fn find_help(name: &str) -> Option<&'static HelpEntry> {
+    HELP_ENTRIES.iter().find(|entry| entry.name == name)
+}
+
+/// Prints the documentation for `topic`, or a list of every documented name when `topic` is empty
+pub fn print_help(topic: &str) {
+    if topic.is_empty() {
+        println!("Documented names (type :help <name> for details):");
+        for entry in HELP_ENTRIES {
+            println!("  {:<16} {}", entry.name, entry.description);
+        }
+        return;
+    }
+
+    match find_help(topic) {
+        Some(entry) => {
+            println!("{}\n  {}\n  domain: {}", entry.signature, entry.description, entry.domain);
+            for (input, output) in entry.examples {
+                if output.is_empty() {
+                    println!("  example: {}", input);
+                } else {
+                    println!("  example: {} = {}", input, output);
+                }
+            }
+        },
+        None => eprintln!("No help for `{}`. Type :help to list documented names", topic),
+    }
+}
@@ -0,0 +1,379 @@
+use std::fmt::Display;
+
+use crate::{
+    bigint::BigInt,
+    rational::Rational,
+};
+
+/// A real number that is only known to lie somewhere between `lo` and `hi`, from the interval mode.<br>
+/// Every operation rounds its bounds outward, so the true result is always inside and the width shows how much rounding has built up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+impl Interval {
+    /// Creates the interval holding every number from `lo` to `hi`
+    pub fn new(lo: f64, hi: f64) -> Self {
+        Interval { lo, hi }
+    }
+
+    /// The lowest and highest number the interval holds
+    pub fn bounds(&self) -> (f64, f64) {
+        (self.lo, self.hi)
+    }
+
+    /// Creates the interval holding exactly `number`
+    pub fn point(number: f64) -> Self {
+        Interval { lo: number, hi: number }
+    }
+
+    /// Creates the interval holding every real number that rounds to `number`, like the true value of `pi`
+    pub fn around(number: f64) -> Self {
+        Interval { lo: number.next_down(), hi: number.next_up() }
+    }
+
+    /// Creates the interval holding the number with the same decimal digits as `number`, so `0.1` holds exactly one tenth.
+    /// Like `Rational::from_decimal` this relies on `f64`'s `Display` writing the shortest decimal that parses back to `number`
+    pub fn from_decimal(number: f64) -> Self {
+        // the digits are exactly `number` when they make a fraction with a power of 2 below, like `0.5`, that an `f64` can hold
+        let is_exact = Rational::from_decimal(number).is_some_and(|fraction| {
+            (fraction.denominator() as u128).is_power_of_two() && fraction.numerator() as f64 as i128 == fraction.numerator()
+        });
+        if is_exact {
+            Interval::point(number)
+        } else {
+            Interval::around(number)
+        }
+    }
+
+    /// Creates the interval holding exactly `integer`, which is only a single point when an `f64` can hold every digit
+    pub fn from_integer(integer: &BigInt) -> Self {
+        match integer.to_i128() {
+            Some(integer) if integer as f64 as i128 == integer => Interval::point(integer as f64),
+            _ => Interval::around(integer.to_f64()),
+        }
+    }
+
+    /// The number halfway between the bounds, which is what the interval is shown and used as outside the interval mode
+    pub fn midpoint(&self) -> f64 {
+        if self.is_point() {
+            self.lo
+        } else {
+            // halving first can't overflow
+            self.lo / 2.0 + self.hi / 2.0
+        }
+    }
+
+    /// Whether the interval holds only one number, so no rounding has happened
+    pub fn is_point(&self) -> bool {
+        self.lo == self.hi
+    }
+
+    /// `-self`
+    pub fn neg(&self) -> Self {
+        Interval { lo: -self.hi, hi: -self.lo }
+    }
+
+    /// `self + rhs`
+    pub fn add(&self, rhs: Interval) -> Self {
+        Interval { lo: sum_bounds(self.lo, rhs.lo).0, hi: sum_bounds(self.hi, rhs.hi).1 }
+    }
+
+    /// `self - rhs`
+    pub fn sub(&self, rhs: Interval) -> Self {
+        self.add(rhs.neg())
+    }
+
+    /// `self * rhs`
+    pub fn mul(&self, rhs: Interval) -> Self {
+        // the product of two intervals is bounded by the products of their bounds
+        let products = [
+            product_bounds(self.lo, rhs.lo),
+            product_bounds(self.lo, rhs.hi),
+            product_bounds(self.hi, rhs.lo),
+            product_bounds(self.hi, rhs.hi),
+        ];
+        enclose(&products)
+    }
+
+    /// `self / rhs`
+    /// # Returns
+    ///  - `Some(quotient)`: the quotient, which is every real number when `rhs` holds `0` but isn't only `0`
+    ///  - `None`: when `rhs` is exactly `0`
+    pub fn div(&self, rhs: Interval) -> Option<Self> {
+        if rhs.lo == 0.0 && rhs.hi == 0.0 {
+            return None;
+        }
+        if rhs.lo <= 0.0 && rhs.hi >= 0.0 {
+            return Some(Interval { lo: f64::NEG_INFINITY, hi: f64::INFINITY });
+        }
+
+        let quotients = [
+            quotient_bounds(self.lo, rhs.lo),
+            quotient_bounds(self.lo, rhs.hi),
+            quotient_bounds(self.hi, rhs.lo),
+            quotient_bounds(self.hi, rhs.hi),
+        ];
+        Some(enclose(&quotients))
+    }
+
+    /// `self ^ exponent` for a whole `exponent`, by repeated multiplication
+    /// # Returns
+    ///  - `Some(power)`: the power
+    ///  - `None`: when `exponent` is negative and `self` is exactly `0`
+    pub fn powi(&self, exponent: i32) -> Option<Self> {
+        let mut result = Interval::point(1.0);
+        let mut base = *self;
+        let mut power = exponent.unsigned_abs();
+        while power > 0 {
+            if power & 1 == 1 {
+                result = result.mul(base);
+            }
+            power >>= 1;
+            if power > 0 {
+                base = base.mul(base);
+            }
+        }
+        // an even power is never negative, even though multiplying the bounds of an interval around 0 says it could be
+        if exponent % 2 == 0 {
+            result.lo = result.lo.max(0.0);
+        }
+
+        if exponent < 0 {
+            Interval::point(1.0).div(result)
+        } else {
+            Some(result)
+        }
+    }
+
+    /// Applies the builtin function `name` to every number in the interval
+    /// # Returns
+    ///  - `Some(result)`: the interval holding every result
+    ///  - `None`: when `name` has no interval version, or the interval isn't entirely inside its domain,
+    ///    so the function has to be worked out on the midpoint in floating point
+    pub fn apply_function(&self, name: &str) -> Option<Self> {
+        match name {
+            "sqrt" if self.lo >= 0.0 => Some(Interval { lo: root_bounds(self.lo).0, hi: root_bounds(self.hi).1 }),
+            "exp" => Some(self.increasing(f64::exp)),
+            "ln" if self.lo > 0.0 => Some(self.increasing(f64::ln)),
+            "log" if self.lo > 0.0 => Some(self.increasing(f64::log10)),
+            "abs" if self.lo >= 0.0 => Some(*self),
+            "abs" if self.hi <= 0.0 => Some(self.neg()),
+            "abs" => Some(Interval { lo: 0.0, hi: self.hi.max(-self.lo) }),
+            "sin" => Some(self.periodic(f64::sin, std::f64::consts::FRAC_PI_2)),
+            // `cos` has its peaks a quarter turn before those of `sin`
+            "cos" => Some(self.periodic(f64::cos, 0.0)),
+            // `tan` increases between its poles, and every real number is reached when the interval crosses one
+            "tan" if self.holds_multiple(std::f64::consts::FRAC_PI_2, std::f64::consts::PI) => Some(Interval { lo: f64::NEG_INFINITY, hi: f64::INFINITY }),
+            "tan" => Some(self.increasing(f64::tan)),
+            _ => None,
+        }
+    }
+
+    /// `self ^ exponent` for any `exponent`, when `self` is positive
+    /// # Returns
+    ///  - `Some(power)`: the power
+    ///  - `None`: when `self` holds `0` or a negative number, where the power isn't real or doesn't change smoothly
+    pub fn powf(&self, exponent: Interval) -> Option<Self> {
+        if self.lo <= 0.0 {
+            return None;
+        }
+
+        // a positive base to a power only ever rises or falls in each operand, so the extremes are at the corners
+        let powers = [
+            (self.lo, exponent.lo),
+            (self.lo, exponent.hi),
+            (self.hi, exponent.lo),
+            (self.hi, exponent.hi),
+        ]
+        .map(|(base, exponent)| {
+            let power = base.powf(exponent);
+            (power.next_down(), power.next_up())
+        });
+        let mut result = enclose(&powers);
+        result.lo = result.lo.max(0.0);
+        Some(result)
+    }
+
+    // apply an increasing function to the bounds, widening each by one unit in the last place,
+    // which covers the error of the standard library's functions
+    fn increasing(&self, function: fn(f64) -> f64) -> Self {
+        Interval { lo: function(self.lo).next_down(), hi: function(self.hi).next_up() }
+    }
+
+    // apply a function like `sin` that goes between -1 and 1, with its peaks at `peak` plus whole turns,
+    // and its troughs half a turn later. the bounds are at the ends unless a peak or trough is inside
+    fn periodic(&self, function: fn(f64) -> f64, peak: f64) -> Self {
+        use std::f64::consts::{PI, TAU};
+
+        let (start, end) = (function(self.lo), function(self.hi));
+        let lo = match self.holds_multiple(peak + PI, TAU) {
+            true => -1.0,
+            false => start.min(end).next_down().max(-1.0),
+        };
+        let hi = match self.holds_multiple(peak, TAU) {
+            true => 1.0,
+            false => start.max(end).next_up().min(1.0),
+        };
+        Interval { lo, hi }
+    }
+
+    // whether the interval holds `offset` plus a whole number of `period`s.
+    // `offset` and `period` are rounded, so points just outside the interval count too, which only makes the result wider
+    fn holds_multiple(&self, offset: f64, period: f64) -> bool {
+        const SLACK: f64 = 1e-9;
+
+        if !self.lo.is_finite() || !self.hi.is_finite() || self.hi - self.lo >= period {
+            return true;
+        }
+        let first = ((self.lo - offset) / period - SLACK).ceil();
+        let last = ((self.hi - offset) / period + SLACK).floor();
+        first <= last
+    }
+}
+impl Display for Interval { // allows for `println!()` and `.to_string()`
+
+    /// writes the interval as its midpoint and how far the bounds are from it, like `0.30000000000000004 ± 2e-16`,
+    /// or just the number when the interval holds only one
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the interval to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_point() {
+            return write!(f, "{}", self.lo);
+        }
+        if !self.lo.is_finite() || !self.hi.is_finite() {
+            return write!(f, "[{}, {}]", self.lo, self.hi);
+        }
+
+        let midpoint = self.midpoint();
+        let radius = sum_bounds(self.hi, -midpoint).1.max(sum_bounds(midpoint, -self.lo).1);
+        write!(f, "{} ± {}", midpoint, format_radius(radius))
+    }
+}
+
+// the radius to 1 significant digit, rounded up so it still covers both bounds
+fn format_radius(radius: f64) -> String {
+    let mut exponent = radius.log10().floor() as i32;
+    let mut digit = (radius / 10f64.powi(exponent)).ceil().max(1.0) as u64;
+    if digit >= 10 {
+        digit = 1;
+        exponent += 1;
+    }
+
+    match u32::try_from(exponent) {
+        Ok(exponent) if exponent < 16 => (digit * 10u64.pow(exponent)).to_string(),
+        _ => format!("{}e{}", digit, exponent),
+    }
+}
+
+// the smallest interval holding every `(lower, upper)` pair
+fn enclose(bounds: &[(f64, f64)]) -> Interval {
+    Interval {
+        lo: bounds.iter().map(|(lower, _)| *lower).fold(f64::INFINITY, f64::min),
+        hi: bounds.iter().map(|(_, upper)| *upper).fold(f64::NEG_INFINITY, f64::max),
+    }
+}
+
+// the `f64`s on either side of the exact result that `result` was rounded from, given the sign of the rounding `error`.
+// an error of `0` means `result` is exact, and an error that isn't a number means it is unknown, so both sides are widened
+fn rounded_bounds(result: f64, error: f64) -> (f64, f64) {
+    if error > 0.0 {
+        (result, result.next_up())
+    } else if error < 0.0 {
+        (result.next_down(), result)
+    } else if error == 0.0 {
+        (result, result)
+    } else {
+        (result.next_down(), result.next_up())
+    }
+}
+
+// the bounds of `a + b`, using the TwoSum algorithm to find the exact error of the rounded sum
+fn sum_bounds(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_part = sum - a;
+    let a_part = sum - b_part;
+    rounded_bounds(sum, (a - a_part) + (b - b_part))
+}
+
+// the bounds of `a * b`, where a fused multiply add finds the exact error of the rounded product
+fn product_bounds(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+    rounded_bounds(product, a.mul_add(b, -product))
+}
+
+// the bounds of `a / b`, where the exact remainder `a - quotient * b` has the sign of the error times the sign of `b`
+fn quotient_bounds(a: f64, b: f64) -> (f64, f64) {
+    let quotient = a / b;
+    rounded_bounds(quotient, (-quotient).mul_add(b, a) * b.signum())
+}
+
+// the bounds of `sqrt(x)`, where the exact remainder `x - root * root` has the sign of the error
+fn root_bounds(x: f64) -> (f64, f64) {
+    let root = x.sqrt();
+    rounded_bounds(root, (-root).mul_add(root, x))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        environment::Environment,
+        expression::Expression,
+        mode::Mode,
+        warning::Warning,
+    };
+
+    use super::*;
+
+    // whether the interval holds `number`
+    fn holds(interval: Interval, number: f64) -> bool {
+        interval.lo <= number && number <= interval.hi
+    }
+
+    #[test]
+    fn trig_functions_keep_their_bounds() {
+        let one = Interval::point(1.0);
+        for (name, expected) in [("sin", 1f64.sin()), ("cos", 1f64.cos()), ("tan", 1f64.tan())] {
+            let result = one.apply_function(name).unwrap();
+            assert!(holds(result, expected) && !result.is_point(), "{} gave {}", name, result);
+        }
+    }
+
+    #[test]
+    fn trig_functions_reach_their_peaks_inside_the_interval() {
+        let around_half_pi = Interval::new(1.5, 1.6);
+        assert_eq!(around_half_pi.apply_function("sin").unwrap().bounds().1, 1.0);
+        assert!(around_half_pi.apply_function("cos").unwrap().bounds().0 < 0.0);
+
+        let around_pi = Interval::new(3.0, 3.2);
+        assert_eq!(around_pi.apply_function("cos").unwrap().bounds().0, -1.0);
+        assert_eq!(Interval::new(0.0, 7.0).apply_function("sin").unwrap().bounds(), (-1.0, 1.0));
+        assert_eq!(around_half_pi.apply_function("tan").unwrap().bounds(), (f64::NEG_INFINITY, f64::INFINITY));
+    }
+
+    #[test]
+    fn fractional_powers_keep_their_bounds() {
+        let root = Interval::point(2.0).powf(Interval::point(0.5)).unwrap();
+        assert!(holds(root, std::f64::consts::SQRT_2) && !root.is_point(), "{}", root);
+
+        let power = Interval::new(2.0, 3.0).powf(Interval::new(-0.5, 0.5)).unwrap();
+        assert!(holds(power, 3f64.powf(-0.5)) && holds(power, 3f64.powf(0.5)), "{}", power);
+        assert!(Interval::new(-1.0, 2.0).powf(Interval::point(0.5)).is_none());
+    }
+
+    #[test]
+    fn dropped_bounds_are_warned_about() {
+        let mut environment = Environment::new();
+        environment.set_mode(Mode::Interval);
+        let warnings = |input: &str| input.parse::<Expression>().unwrap().evaluate_detailed(&environment).unwrap().warnings;
+
+        assert!(warnings("sin(1) + 2 ^ 0.5").is_empty());
+        assert!(warnings("5 // 2").is_empty());
+        assert_eq!(warnings("db(2)"), vec![Warning::DroppedBounds { operation: "db".to_string() }]);
+        assert_eq!(warnings("(0.1 + 0.2) // 0.1"), vec![Warning::DroppedBounds { operation: "//".to_string() }]);
+    }
+}
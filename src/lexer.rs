@@ -0,0 +1,460 @@
+use std::{
+    fmt::Display,
+    iter::Peekable,
+    str::CharIndices,
+};
+
+use crate::{
+    bigint::BigInt,
+    date::Date,
+    operation::Operation,
+};
+
+/// The name of the imaginary unit, whose square is `-1`. It is reserved, so it can't be a variable
+pub const IMAGINARY_UNIT: &str = "i";
+
+/// The smallest meaningful pieces of an expression.<br>
+/// Names and whole numbers borrow their text from the input, so splitting an expression into tokens allocates nothing but the list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Token<'a> {
+    /// A number literal like `2.5`, `1.5e3`, or `0x1.8p3`
+    Number(f64),
+    /// A whole number literal like `3`, `0xFF`, `0b1010`, or `0o17`, which is kept exactly however long it is.
+    /// The literal is only turned into a `BigInt` by `parse_integer` when the parser builds its node
+    Integer(&'a str),
+    /// An imaginary number literal like `4i`, or the imaginary unit `i` itself
+    Imaginary(f64),
+    /// A date literal like `2024-03-01`
+    Date(Date),
+    /// A name like `sqrt`
+    Identifier(&'a str),
+    /// One of the binary operators
+    Operator(Operation),
+    /// `(`
+    LeftParenthesis,
+    /// `)`
+    RightParenthesis,
+    /// `[` before the elements of a matrix
+    LeftBracket,
+    /// `]` after the elements of a matrix
+    RightBracket,
+    /// `,` between function arguments, or the elements of a matrix row
+    Comma,
+    /// `;` between the rows of a matrix
+    Semicolon,
+    /// `=` between a variable and its new value
+    Equals,
+    /// `~`, the bitwise not of the operand after it
+    Tilde,
+    /// `!`, the factorial of the operand before it
+    Exclamation,
+    /// `not`, the logical not of the operand after it
+    Not,
+    /// `in` between a quantity and the unit to show it in, like `5 km in m`
+    In,
+    /// `?` between the condition and the first branch of a conditional
+    Question,
+    /// `:` between the branches of a conditional
+    Colon,
+}
+impl Display for Token<'_> { // allows for `println!()` and `.to_string()`
+
+    /// writes the token the way the user would have typed it
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the token to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "{}", number),
+            Token::Integer(digits) => write!(f, "{}", digits),
+            Token::Imaginary(number) if *number == 1.0 => write!(f, "i"),
+            Token::Imaginary(number) => write!(f, "{}i", number),
+            Token::Date(date) => write!(f, "{}", date),
+            Token::Identifier(name) => write!(f, "{}", name),
+            Token::Operator(operation) => write!(f, "{}", operation),
+            Token::LeftParenthesis => write!(f, "("),
+            Token::RightParenthesis => write!(f, ")"),
+            Token::LeftBracket => write!(f, "["),
+            Token::RightBracket => write!(f, "]"),
+            Token::Comma => write!(f, ","),
+            Token::Semicolon => write!(f, ";"),
+            Token::Equals => write!(f, "="),
+            Token::Tilde => write!(f, "~"),
+            Token::Exclamation => write!(f, "!"),
+            Token::Not => write!(f, "not"),
+            Token::In => write!(f, "in"),
+            Token::Question => write!(f, "?"),
+            Token::Colon => write!(f, ":"),
+        }
+    }
+}
+
+/// Splits `input` into a list of `Token`s, skipping whitespace.
+/// # Parameters
+///  - `input`: the text typed by the user, which the names and whole numbers in the tokens borrow from
+/// # Returns
+///  - `Ok(tokens)`: the tokens of `input` in order
+///  - `Err(lex_error)`: when `input` contains a malformed number or a character that isn't part of any token
+pub fn tokenize(input: &str) -> Result<Vec<Token<'_>>, Box<dyn std::error::Error>> {
+    let mut tokens = Vec::new();
+    let mut characters = input.char_indices().peekable();
+
+    while let Some((start, character)) = characters.next() {
+        if character.is_whitespace() {
+            continue;
+        }
+
+        // a number starting with `0b` or `0o` is a binary or octal integer like `0b1010` or `0o17`
+        if character == '0' && matches!(characters.peek(), Some((_, 'b' | 'B' | 'o' | 'O'))) {
+            characters.next();
+            let mut end = start + 2;
+            consume_while(&mut characters, &mut end, |next| next.is_ascii_digit());
+
+            let literal = &input[start..end];
+            parse_integer(literal).map_err(|error| format!("Invalid number `{}` at column {}: {}", literal, column(input, start), error))?;
+            tokens.push(Token::Integer(literal));
+            continue;
+        }
+
+        // a number starting with `0x` is a hexadecimal integer like `0xFF`, or floating point literal like `0x1.8p3`
+        if character == '0' && matches!(characters.peek(), Some((_, 'x' | 'X'))) {
+            characters.next();
+            let mut end = start + 2;
+            consume_while(&mut characters, &mut end, |next| next.is_ascii_hexdigit() || next == '.');
+            if matches!(characters.peek(), Some((_, 'p' | 'P'))) {
+                consume_while(&mut characters, &mut end, |next| next == 'p' || next == 'P');
+                consume_while(&mut characters, &mut end, |next| next == '+' || next == '-');
+                consume_while(&mut characters, &mut end, |next| next.is_ascii_digit());
+            }
+
+            let literal = &input[start..end];
+            let invalid = |error: Box<dyn std::error::Error>| format!("Invalid number `{}` at column {}: {}", literal, column(input, start), error);
+            // without a `.` or `p` exponent the literal is a whole number, which is kept exact however many bits it has
+            if literal.contains(['.', 'p', 'P']) {
+                tokens.push(Token::Number(parse_hex_float(literal).map_err(invalid)?));
+            } else {
+                parse_integer(literal).map_err(invalid)?;
+                tokens.push(Token::Integer(literal));
+            }
+            continue;
+        }
+
+        // a 4 digit year followed by `-MM-DD` is a date like `2024-03-01` rather than a subtraction
+        let date = date_length(&input[start..]);
+        if date > 0 {
+            for _ in 1..date {
+                characters.next();
+            }
+            let literal = &input[start..start + date];
+            let date = Date::parse(literal)
+                .map_err(|error| format!("Invalid date `{}` at column {}: {}", literal, column(input, start), error))?;
+            tokens.push(Token::Date(date));
+            continue;
+        }
+
+        // any other number is a run of digits and '.', optionally followed by an exponent like `e3` or `E-4`.
+        // a '.' that starts an element-wise operator like `.*` isn't part of the number, so `2.*A` is `2 .* A`
+        if (character.is_ascii_digit() || character == '.') && !is_elementwise_operator(&input[start..]) {
+            let mut end = start + character.len_utf8();
+            while let Some(&(i, next)) = characters.peek() {
+                if !(next.is_ascii_digit() || next == '.') || is_elementwise_operator(&input[i..]) {
+                    break;
+                }
+                end = i + next.len_utf8();
+                characters.next();
+            }
+            let Some(exponent) = exponent_length(&input[end..]) else {
+                let sign = usize::from(input[end + 1..].starts_with(['+', '-']));
+                let literal = &input[start..end + 1 + sign];
+                return Err(format!("Invalid number `{}` at column {}: missing exponent digits", literal, column(input, start)).into());
+            };
+            for _ in 0..exponent {
+                characters.next();
+            }
+            end += exponent;
+            // `2e3e` has an `e` after its exponent, which isn't the constant `e` either
+            if exponent > 0 && exponent_length(&input[end..]) != Some(0) {
+                let literal = &input[start..end + 1];
+                return Err(format!("Invalid number `{}` at column {}: more than one exponent", literal, column(input, start)).into());
+            }
+
+            let literal = &input[start..end];
+            let number = literal
+                .parse()
+                .map_err(|error| format!("Invalid number `{}` at column {}: {}", literal, column(input, start), error))?;
+
+            // a number directly followed by a lone `i`, like `4i`, is imaginary
+            let is_imaginary = input[end..].starts_with('i')
+                && !input[end + 1..].starts_with(|next: char| next.is_alphanumeric() || next == '_');
+            if is_imaginary {
+                characters.next();
+                tokens.push(Token::Imaginary(number));
+            } else {
+                // digits alone are a whole number, which `f64` would round once it has more than 15 or so of them
+                let is_integer = literal.bytes().all(|digit| digit.is_ascii_digit());
+                tokens.push(if is_integer { Token::Integer(literal) } else { Token::Number(number) });
+            }
+            continue;
+        }
+
+        // a name starts with a letter or '_' and continues with letters, digits, and '_'.
+        // operators spelled as words, like `xor`, `not`, and `in`, aren't names
+        if character.is_alphabetic() || character == '_' {
+            let mut end = start + character.len_utf8();
+            consume_while(&mut characters, &mut end, |next| next.is_alphanumeric() || next == '_');
+            let name = &input[start..end];
+            tokens.push(match name {
+                "not" => Token::Not,
+                "in" => Token::In,
+                IMAGINARY_UNIT => Token::Imaginary(1.0),
+                _ => match name.parse() {
+                    Ok(operation) => Token::Operator(operation),
+                    Err(_) => Token::Identifier(name),
+                },
+            });
+            continue;
+        }
+
+        let token = match character {
+            '(' => Token::LeftParenthesis,
+            ')' => Token::RightParenthesis,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            ',' => Token::Comma,
+            ';' => Token::Semicolon,
+            // `==` is a comparison, which the operator arm below finds
+            '=' if !matches!(characters.peek(), Some((_, '='))) => Token::Equals,
+            '~' => Token::Tilde,
+            // `!=` is a comparison, which the operator arm below finds
+            '!' if !matches!(characters.peek(), Some((_, '='))) => Token::Exclamation,
+            '?' => Token::Question,
+            ':' => Token::Colon,
+            _ => {
+                // operators can be two characters long like `//` or `<=`, so try the longest one first
+                let pair = characters.peek().map(|&(i, next)| &input[start..i + next.len_utf8()]);
+                match pair.map(str::parse) {
+                    Some(Ok(operation)) => {
+                        characters.next();
+                        Token::Operator(operation)
+                    },
+                    _ => match input[start..start + character.len_utf8()].parse() {
+                        Ok(operation) => Token::Operator(operation),
+                        Err(error) => return Err(format!("Unexpected `{}` at column {}: {}", character, column(input, start), error).into()),
+                    },
+                }
+            },
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+// whether `rest` starts with one of the element-wise operators `.*` `./` or `.^`
+fn is_elementwise_operator(rest: &str) -> bool {
+    [".*", "./", ".^"].iter().any(|operator| rest.starts_with(operator))
+}
+
+// consume characters while they match `predicate`, moving `end` past each one
+fn consume_while(characters: &mut Peekable<CharIndices>, end: &mut usize, predicate: impl Fn(char) -> bool) {
+    while let Some(&(i, next)) = characters.peek() {
+        if !predicate(next) {
+            break;
+        }
+        *end = i + next.len_utf8();
+        characters.next();
+    }
+}
+
+// the length of the exponent at the start of `rest`, like `e3` or `E-4`, or 0 when there isn't one.
+// an `e` that starts a name, like the `exp` of `2exp(1)`, isn't an exponent.
+// returns `None` when an `e` has no digits after it, like `1e` or `1e+`, which would otherwise be read as the constant `e`
+fn exponent_length(rest: &str) -> Option<usize> {
+    let bytes = rest.as_bytes();
+    if !matches!(bytes.first(), Some(b'e' | b'E')) {
+        return Some(0);
+    }
+
+    let sign = usize::from(matches!(bytes.get(1), Some(b'+' | b'-')));
+    let digits = bytes[1 + sign..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+    let starts_name = sign == 0 && bytes.get(1).is_some_and(|next| next.is_ascii_alphabetic() || *next == b'_' || !next.is_ascii());
+    if digits > 0 {
+        Some(1 + sign + digits)
+    } else if starts_name {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+// the length of the date at the start of `rest`, like `2024-03-01`, or 0 when there isn't one.
+// a date can't run straight into more digits, a name, or a `.`
+fn date_length(rest: &str) -> usize {
+    const PATTERN: &[u8] = b"0000-00-00";
+    let bytes = rest.as_bytes();
+    let matches = bytes.len() >= PATTERN.len()
+        && PATTERN.iter().zip(bytes).all(|(expected, byte)| match expected {
+            b'0' => byte.is_ascii_digit(),
+            _ => byte == expected,
+        });
+    let is_followed = bytes.get(PATTERN.len()).is_some_and(|next| next.is_ascii_alphanumeric() || matches!(next, b'_' | b'.'));
+    if matches && !is_followed {
+        PATTERN.len()
+    } else {
+        0
+    }
+}
+
+/// Parses a whole number literal, which is decimal digits like `255`, or digits after a `0x`, `0b`, or `0o` prefix like `0xFF`, `0b1010`, or `0o17`.
+/// # Parameters
+///  - `literal`: the literal as the user typed it
+/// # Returns
+///  - `Ok(integer)`: the exact value of the literal, however many digits it has
+///  - `Err(parse_error)`: when there are no digits, or a digit is too big for the base
+pub fn parse_integer(literal: &str) -> Result<BigInt, Box<dyn std::error::Error>> {
+    let (digits, radix, name) = match literal.get(..2) {
+        Some("0x" | "0X") => (&literal[2..], 16, "a hexadecimal"),
+        Some("0b" | "0B") => (&literal[2..], 2, "a binary"),
+        Some("0o" | "0O") => (&literal[2..], 8, "an octal"),
+        _ => (literal, 10, "a decimal"),
+    };
+    if digits.is_empty() {
+        return Err("missing digits".into());
+    }
+    if let Some(digit) = digits.chars().find(|digit| !digit.is_digit(radix)) {
+        return Err(format!("`{}` is not {} digit", digit, name).into());
+    }
+    BigInt::parse_radix(digits, radix).ok_or_else(|| format!("`{}` is not a whole number", literal).into())
+}
+
+/// Parses a C99 style hexadecimal floating point literal like `0x1.8p3`, which is `1.5 * 2^3`.
+/// # Parameters
+///  - `literal`: `0x` followed by hex digits with an optional `.`, then a `p` and a decimal power of two, which is required after a `.`
+/// # Returns
+///  - `Ok(number)`: the value of the literal, rounded to the nearest `f64`
+///  - `Err(parse_error)`: when the literal has no digits, more than one `.`, or a `.` without a `p` exponent
+fn parse_hex_float(literal: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    // only this many hex digits fit in a `u128`, the rest can't affect the rounded result
+    const MAX_SIGNIFICANT_DIGITS: usize = 30;
+
+    let digits = &literal[2..];
+    let (mantissa, exponent) = match digits.split_once(['p', 'P']) {
+        Some(parts) => parts,
+        None if digits.contains('.') => return Err("hexadecimal fractions need a `p` exponent, e.g. 0x1.8p3".into()),
+        None => (digits, "0"),
+    };
+    let mut exponent: i64 = exponent
+        .parse()
+        .map_err(|_| "the `p` exponent must be a decimal integer")?;
+
+    let (integer_part, fraction_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if fraction_part.contains('.') {
+        return Err("more than one `.`".into());
+    }
+    if integer_part.is_empty() && fraction_part.is_empty() {
+        return Err("missing hex digits".into());
+    }
+
+    // accumulate every digit into one integer, then scale it by the power of two its position implies
+    let mut significand: u128 = 0;
+    let mut significant_digits = 0;
+    for (i, digit) in integer_part.chars().chain(fraction_part.chars()).enumerate() {
+        let is_fraction_digit = i >= integer_part.len();
+        if significant_digits < MAX_SIGNIFICANT_DIGITS {
+            significand = significand * 16 + digit.to_digit(16).unwrap_or_default() as u128;
+            if significand != 0 {
+                significant_digits += 1;
+            }
+            if is_fraction_digit {
+                exponent -= 4;
+            }
+        } else if !is_fraction_digit {
+            // integer digits that don't fit still make the number 16 times bigger
+            exponent += 4;
+        }
+    }
+
+    Ok(scale_by_power_of_two(significand as f64, exponent))
+}
+
+/// Computes `x * 2^exponent` without overflowing the intermediate power of two,
+/// which is exact unless the result is too big or too small for an `f64`
+pub fn scale_by_power_of_two(mut x: f64, mut exponent: i64) -> f64 {
+    const STEP: i64 = 512;
+    while exponent > STEP && x.is_finite() && x != 0.0 {
+        x *= 2f64.powi(STEP as i32);
+        exponent -= STEP;
+    }
+    while exponent < -STEP && x != 0.0 {
+        x *= 2f64.powi(-STEP as i32);
+        exponent += STEP;
+    }
+    x * 2f64.powi(exponent as i32)
+}
+
+// the 1-based character column of the byte offset `index` in `input`
+fn column(input: &str, index: usize) -> usize {
+    input[..index].chars().count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scientific_notation() {
+        assert_eq!(tokenize("1.5e3").unwrap(), vec![Token::Number(1500.0)]);
+        assert_eq!(tokenize("2.5e-4").unwrap(), vec![Token::Number(2.5e-4)]);
+        assert_eq!(tokenize("1E+2").unwrap(), vec![Token::Number(100.0)]);
+        assert_eq!(tokenize("1.5e3 + 2").unwrap(), vec![Token::Number(1500.0), Token::Operator(Operation::Add), Token::Integer("2")]);
+        assert_eq!(tokenize("2e3i").unwrap(), vec![Token::Imaginary(2000.0)]);
+    }
+
+    #[test]
+    fn exponents_need_digits() {
+        for input in ["1e", "1E", "1e+", "1e-", "1e-x", "1.5e)", "2e(1)"] {
+            let error = tokenize(input).unwrap_err().to_string();
+            assert!(error.contains("missing exponent digits"), "`{}`: {}", input, error);
+        }
+        assert!(tokenize("1e+").unwrap_err().to_string().contains("`1e+`"));
+        for input in ["2e3e", "2e3e4", "1e1E+"] {
+            let error = tokenize(input).unwrap_err().to_string();
+            assert!(error.contains("more than one exponent"), "`{}`: {}", input, error);
+        }
+        // an `e` that starts a name is still implicit multiplication, and a separate `e` is the constant
+        assert_eq!(tokenize("2exp").unwrap(), vec![Token::Integer("2"), Token::Identifier("exp")]);
+        assert_eq!(tokenize("2 e").unwrap(), vec![Token::Integer("2"), Token::Identifier("e")]);
+    }
+
+    #[test]
+    fn prefixed_integers() {
+        assert_eq!(tokenize("0xFF + 0b1").unwrap(), vec![Token::Integer("0xFF"), Token::Operator(Operation::Add), Token::Integer("0b1")]);
+        assert_eq!(parse_integer("0xff").unwrap(), BigInt::from_i128(255));
+        assert_eq!(parse_integer("0b1010").unwrap(), BigInt::from_i128(10));
+        assert_eq!(parse_integer("0o17").unwrap(), BigInt::from_i128(15));
+        // 2^128 is kept exactly, past the range of any builtin integer
+        assert_eq!(parse_integer("0x100000000000000000000000000000000").unwrap().to_string(), "340282366920938463463374607431768211456");
+    }
+
+    #[test]
+    fn malformed_prefixed_integers() {
+        for input in ["0x", "0b", "0o", "0b102", "0o8", "0xG"] {
+            assert!(tokenize(input).is_err(), "`{}` lexed", input);
+        }
+    }
+
+    #[test]
+    fn hex_floats() {
+        assert_eq!(tokenize("0x1.8p3").unwrap(), vec![Token::Number(12.0)]);
+        assert_eq!(tokenize("0x10p-4").unwrap(), vec![Token::Number(1.0)]);
+        assert_eq!(tokenize("0x.8p1").unwrap(), vec![Token::Number(1.0)]);
+        assert_eq!(tokenize("0x1.999999999999ap-4").unwrap(), vec![Token::Number(0.1)]);
+        assert_eq!(tokenize("0x1p-1074").unwrap(), vec![Token::Number(f64::from_bits(1))]);
+        assert_eq!(tokenize("0x1p1024").unwrap(), vec![Token::Number(f64::INFINITY)]);
+        for input in ["0x1.8", "0x1.8.1p3", "0x.p1", "0x1p"] {
+            assert!(tokenize(input).is_err(), "`{}` lexed", input);
+        }
+    }
+}
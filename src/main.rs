@@ -1,217 +1,774 @@
+mod bigint;
+mod checkpoint;
+mod complex;
+mod constant;
+mod currency;
+mod date;
+mod decimal;
+mod environment;
+mod expression;
+mod format;
+mod function;
+mod help;
+mod interval;
+mod lexer;
+mod matrix;
+mod mode;
+mod operation;
+mod parser;
+mod polynomial;
+mod progress;
+mod rational;
+mod statement;
+mod symbolic;
+mod template;
+mod unit;
+mod value;
+mod variables_file;
+mod warning;
+
 use std::{
+    fs::{File, OpenOptions},
     io::{
         self,
+        IsTerminal,
         Write,
         stdin
     },
-    str::FromStr,
-    fmt::Display
+    path::Path,
+    time::{Duration, Instant},
 };
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // greeting 
-    println!("Simple Terminal Calculator\nSupported operations: + - * / ^\ntype exit to quit");
+use checkpoint::Checkpoint;
+use environment::Environment;
+use expression::{Evaluation, Expression};
+use mode::{AngleMode, Mode};
+use progress::Progress;
+use statement::Statement;
+use value::Value;
+use warning::Warning;
+
+// how often `--checkpoint` records progress; often enough to lose little work, rarely enough not to slow the run
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+
+fn main() {
+    // a bad flag or file is reported like any other error, rather than as the `Debug` text `main` would print
+    if let Err(error) = run() {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
 
-    // keep allowing user to input expressions until they type quit
-    loop {
-        // get input
-        let input = get_input("> ")?;
-        
-        // check if user wants to quit
-        if input.to_lowercase() == "exit" {
-            println!("Goodbye!");
-            break;
+// read the command line flags and run the mode they ask for
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    // read command line flags
+    let mut deny_warnings = false; // `--deny-warnings` treats every warning as an error
+    let mut quiet = false; // `--quiet` hides the greeting and goodbye messages
+    let mut tutorial = false; // `calc tutorial` runs the interactive tutorial instead of the calculator
+    let mut template = None; // `calc template path` fills in the `{{ expression }}` placeholders of a file
+    let mut filter = false; // `--filter` copies stdin to stdout, replacing `$((expression))` with its value
+    let mut on_error = ErrorPolicy::Skip; // `--on-error skip|stop|emit-nan` decides what a failing line does
+    let mut one_shot = None; // `calc "expression"` prints the value of a single expression and exits
+    let mut output_path = None; // `--output path` also writes every result to a file
+    let mut checkpoint_path = None; // `--checkpoint path` records progress through piped input so an interrupted run can resume
+    let mut show_progress = false; // `--progress` shows how far through piped input the calculator is
+    // variables from `--var name=value`, `--vars-file path`, or `name = expression` are kept until the calculator quits
+    let mut environment = Environment::new();
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--deny-warnings" => deny_warnings = true,
+            "--quiet" | "-q" => quiet = true,
+            "--var" => {
+                let definition = arguments.next().ok_or("--var needs a definition like --var r=2.5")?;
+                define_variable(&definition, &mut environment)
+                    .map_err(|error| format!("Invalid --var `{}`: {}", definition, error))?;
+            },
+            "--vars-file" => {
+                let path = arguments.next().ok_or("--vars-file needs a TOML or JSON file like --vars-file params.toml")?;
+                variables_file::load(path.as_ref(), &mut environment)
+                    .map_err(|error| format!("Invalid --vars-file `{}`: {}", path, error))?;
+            },
+            "--rates" => {
+                let path = arguments.next().ok_or("--rates needs a TOML or JSON file like --rates rates.toml")?;
+                let rates = currency::load_rates(path.as_ref()).map_err(|error| format!("Invalid --rates `{}`: {}", path, error))?;
+                environment.add_exchange_rates(rates);
+            },
+            #[cfg(feature = "online-rates")]
+            "--rates-url" => {
+                let url = arguments.next().ok_or("--rates-url needs an address like --rates-url http://localhost:8000/rates.json")?;
+                let rates = currency::fetch_rates(&url).map_err(|error| format!("Invalid --rates-url `{}`: {}", url, error))?;
+                environment.add_exchange_rates(rates);
+            },
+            "--filter" => filter = true,
+            "--progress" => show_progress = true,
+            "--on-error" => {
+                on_error = match arguments.next().as_deref() {
+                    Some("skip") => ErrorPolicy::Skip,
+                    Some("stop") => ErrorPolicy::Stop,
+                    Some("emit-nan") => ErrorPolicy::EmitNan,
+                    _ => return Err("--on-error needs one of skip, stop, or emit-nan".into()),
+                };
+            },
+            "--output" => output_path = Some(arguments.next().ok_or("--output needs a file like --output results.txt")?),
+            "--checkpoint" => checkpoint_path = Some(arguments.next().ok_or("--checkpoint needs a file like --checkpoint run.checkpoint")?),
+            "tutorial" => tutorial = true,
+            "template" => template = Some(arguments.next().ok_or("template needs a file like calc template report.txt")?),
+            _ if argument.starts_with("--") => return Err(format!("Unknown argument: {}", argument).into()),
+            _ if one_shot.is_some() => return Err(format!("Only one expression can be given, but found another: {}", argument).into()),
+            _ => one_shot = Some(argument),
         }
+    }
 
-        // if the user didn't want to quit parse the input into an `Expression`
-        let expression: Expression = match input.parse() { 
-            Ok(parsed_expression) => parsed_expression,
-            Err(error) => {
-                eprintln!("Invalid input:\n{}\nTry again", error);
-                continue;
-            },
-        }; 
+    if checkpoint_path.is_some() && (tutorial || template.is_some() || filter || one_shot.is_some()) {
+        return Err("--checkpoint only works when evaluating lines of input".into());
+    }
 
-        // evaluate the input `Expression`
-        match expression.evaluate() { 
-            Ok(result) => println!("{} = {}", expression, result),
-            Err(error) => {
-                eprintln!("Error evaluating expression:\n{}\nTry again", error);
-                continue;
-            }, 
+    // a checkpoint left by an interrupted run says how much of the input was already done
+    let resume = match &checkpoint_path {
+        Some(path) => Checkpoint::load(Path::new(path)).map_err(|error| format!("Invalid --checkpoint `{}`: {}", path, error))?,
+        None => None,
+    };
+    if let Some(checkpoint) = &resume {
+        checkpoint
+            .restore(&mut environment)
+            .map_err(|error| format!("Invalid --checkpoint: {}", error))?;
+    }
+
+    // a resumed run continues its output file from where the checkpoint was saved, instead of starting it over
+    let mut output = match output_path {
+        Some(path) => {
+            let file = open_output(&path, resume.as_ref().and_then(|checkpoint| checkpoint.output_length))
+                .map_err(|error| format!("Invalid --output `{}`: {}", path, error))?;
+            Some((path, file))
+        },
+        None => None,
+    };
+
+    if tutorial {
+        return run_tutorial();
+    }
+
+    if let Some(path) = template {
+        if let Err(error) = run_template(&path, &mut environment, output) {
+            eprintln!("{}", error);
+            std::process::exit(1);
         }
+        return Ok(());
     }
 
-    Ok(())
-}
+    if filter {
+        match run_filter(&mut environment, Progress::for_stdin(show_progress)) {
+            Ok(true) => return Ok(()),
+            Ok(false) => std::process::exit(1),
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            },
+        }
+    }
 
-/// An expression has 
-struct Expression {
-    lhs: f64,
-    rhs: f64,
-    operation: Operation,
-}
-impl Expression {
-    pub fn evaluate(&self) -> Result<f64, Box<dyn std::error::Error>> {
-        match self.operation {
-            Operation::Add         => Ok(self.lhs + self.rhs),
-            Operation::Subtract    => Ok(self.lhs - self.rhs),
-            Operation::Multiply    => Ok(self.lhs * self.rhs),
-            Operation::Exponential => Ok(self.lhs.powf(self.rhs)),
-            Operation::Divide 
-                if self.rhs != 0.0 => Ok(self.lhs / self.rhs),
-            Operation::Divide      => Err("Divide by zero error".into()),
+    if let Some(input) = one_shot {
+        if let Err(error) = run_once(&input, &environment, deny_warnings) {
+            eprintln!("{}", error);
+            std::process::exit(1);
         }
+        return Ok(());
     }
-}
-impl FromStr for Expression { // Trait that allows .parse to work
 
-    type Err = Box<dyn std::error::Error>; // parse error type
+    // greeting 
+    if !quiet {
+        println!("Simple Terminal Calculator\nSupported operations: + - * / // % ^ .* ./ .^ ! & | xor << >> ~ == != < <= > >= and or not in ( )\ntype :help for help or exit to quit");
+    }
 
-    /// Parse an `Expression` from `s`.<br>
-    /// `s` must start with a number
-    /// # Parameters
-    ///  - `s`: The string slice to be parsed
-    /// # Returns
-    ///  - `Ok(expression)`: When `s` is one of the supported operation characters,
-    ///  - `Err(from_str_error)`: When `s` is not one of the supported operation characters,
-    fn from_str(original_str: &str) -> Result<Self, Self::Err> {
-        
-        //  Store each character from `original_str` that is not whitespace
-        let mut string = String::new(); // create a new `String` to store the non-whitespace characters in
+    // precision warnings can be turned off with `:warnings off`
+    let mut show_warnings = true;
+    // result metadata can be turned on with `:verbose-results`
+    let mut verbose_results = false;
+    // results can be shown as hexadecimal floating point with `:hex-float`
+    let mut hex_float = false;
+    // complex results can be shown in polar form like `5∠53.13°` with `:polar`
+    let mut polar = false;
+
+    // the line numbers of inputs that failed, for the summary at the end of a batch
+    let mut line_number = 0;
+    let mut failed_lines = Vec::new();
+    let mut progress = Progress::for_stdin(show_progress);
+
+    // lines up to `resume_line` were evaluated by the interrupted run, so only settings on them are applied
+    let resume_line = resume.map_or(0, |checkpoint| checkpoint.line);
+    let mut last_checkpoint = Instant::now();
+    let mut stopped = false;
+
+    // keep allowing user to input expressions until they type quit
+    let exit_code = loop {
+        // every line before this one is done, which is what a checkpoint records
+        if let Some(path) = &checkpoint_path {
+            if line_number > resume_line && last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+                save_checkpoint(path, line_number, &environment, &output);
+                last_checkpoint = Instant::now();
+            }
+        }
 
-        for character in original_str.chars() { // iterate over every character in `original_str`
+        // get input, stopping at the end of piped input
+        let Some(input) = get_input("> ")? else {
+            break 0;
+        };
+        line_number += 1;
+        progress.advance(input.len() + 1);
         
-            if !character.is_whitespace() { // if the character is not whitespace
-                string.push(character); // then push (append) the non-whitespace character onto `string`
+        // check if user wants to quit
+        if let Some(exit_code) = parse_exit_command(&input) {
+            if !quiet {
+                println!("Goodbye!");
             }
-        } 
+            break exit_code;
+        }
 
+        // check if user wants help
+        if let Some(topic) = input.strip_prefix(":help") {
+            help::print_help(topic.trim());
+            continue;
+        }
+
+        // check if user wants to toggle result metadata
+        if let Some(setting) = input.strip_prefix(":verbose-results") {
+            match setting.trim() {
+                "" => verbose_results = !verbose_results,
+                "on" => verbose_results = true,
+                "off" => verbose_results = false,
+                _ => eprintln!("Usage: :verbose-results [on|off]"),
+            }
+            continue;
+        }
 
-        // Store the first string of digits to `lhs`
-        let mut lhs = String::new(); // Create a new string to hold digit characters in
-        let mut current_index = 0; // we'll use this later to find the `operation` and `rhs`
+        // check if user wants to toggle hexadecimal floating point results
+        if let Some(setting) = input.strip_prefix(":hex-float") {
+            match setting.trim() {
+                "" => hex_float = !hex_float,
+                "on" => hex_float = true,
+                "off" => hex_float = false,
+                _ => eprintln!("Usage: :hex-float [on|off]"),
+            }
+            continue;
+        }
 
-        for (i, character) in string.chars().enumerate() { // iterate over each character with its index
+        // check if user wants to toggle polar complex results
+        if let Some(setting) = input.strip_prefix(":polar") {
+            match setting.trim() {
+                "" => polar = !polar,
+                "on" => polar = true,
+                "off" => polar = false,
+                _ => eprintln!("Usage: :polar [on|off]"),
+            }
+            continue;
+        }
 
-            if character.is_digit(10) || character == '.' { // if the character is a number or '.'
-                lhs.push(character); // then push the digit character onto `lhs`
+        // check if user wants to change how numbers are represented
+        if let Some(mode) = input.strip_prefix(":mode") {
+            // the angle mode is set with the same command, but kept separately, so `:mode deg` leaves fractions on
+            match mode.trim() {
+                "" => println!("mode: {}, angles in {}", environment.mode(), environment.angle_mode()),
+                mode => match (mode.parse::<Mode>(), mode.parse::<AngleMode>()) {
+                    (Ok(mode), _) => environment.set_mode(mode),
+                    (_, Ok(angle_mode)) => environment.set_angle_mode(angle_mode),
+                    (Err(error), Err(_)) => eprintln!("{}\nUsage: :mode [float|fraction|decimal|interval|rad|deg]", error),
+                },
             }
-            else {
-                // if the character was not a digit then `character` is the operator.
-                current_index = i; // save index of first non-digit (aka operator index)
-                break; // stop the loop because we found the end of `lhs`
+            continue;
+        }
+
+        // check if user wants to toggle precision warnings
+        if let Some(setting) = input.strip_prefix(":warnings") {
+            match setting.trim() {
+                "on" => show_warnings = true,
+                "off" => show_warnings = false,
+                _ => eprintln!("Usage: :warnings on|off"),
             }
+            continue;
         }
-        let lhs: f64 = match lhs.parse() { // parse `lhs` into a `f64`
 
-            // if `.parse()` return `Ok` the value shadows `lhs`
-            Ok(parsed_lhs) => parsed_lhs,
+        // `:write expression > file` evaluates like any other input, then appends the result to `file`
+        let (input, write_path) = match input.strip_prefix(":write") {
+            Some(rest) => match rest.rsplit_once('>') {
+                Some((expression, path)) if !path.trim().is_empty() => (expression.trim().to_owned(), Some(path.trim().to_owned())),
+                _ => {
+                    eprintln!("Usage: :write expression > file");
+                    continue;
+                },
+            },
+            None => (input, None),
+        };
+
+        if line_number <= resume_line {
+            continue;
+        }
 
-            // if `.parse()` returns `Err` with with some context
-            Err(error) => return Err(format!("Failed to parse left hand side: {}", error).into()),
+        // evaluate the input, handling a failure the way `--on-error` asks
+        let (result, warnings) = match evaluate_input(&input, &mut environment, deny_warnings) {
+            Ok((label, evaluation)) => (format_result(&label, &evaluation, verbose_results, hex_float, polar), evaluation.warnings),
+            Err(message) => {
+                failed_lines.push(line_number);
+                progress.clear();
+                match on_error {
+                    ErrorPolicy::Skip => {
+                        eprintln!("{}\nTry again", message);
+                        continue;
+                    },
+                    // the failed line is where a resumed run starts again
+                    ErrorPolicy::Stop => {
+                        eprintln!("{}", message);
+                        if let Some(path) = &checkpoint_path {
+                            save_checkpoint(path, line_number - 1, &environment, &output);
+                        }
+                        stopped = true;
+                        break 1;
+                    },
+                    // a placeholder result keeps the output lined up with the input
+                    ErrorPolicy::EmitNan => {
+                        eprintln!("{}", message);
+                        (format!("{} = NaN", input_label(&input)), Vec::new())
+                    },
+                }
+            },
         };
 
+        println!("{}", result);
+        if let Some((path, file)) = &mut output {
+            if let Err(error) = writeln!(file, "{}", result) {
+                eprintln!("Error writing to {}: {}", path, error);
+            }
+        }
+        if let Some(path) = &write_path {
+            if let Err(error) = append_line(path, &result) {
+                eprintln!("Error writing to {}: {}", path, error);
+            }
+        }
+        if show_warnings && !warnings.is_empty() {
+            progress.clear();
+            for warning in &warnings {
+                report_warning(warning);
+            }
+        }
+    };
 
-        // get the operation from `string`
-        let operation = match string.chars().nth(current_index) { // try to get the character at `current_index`
+    progress.clear();
 
-            // if there is some character at `current_index` 
-            Some(character) => match character.to_string().parse() { // try to parse `character`
+    // a run that got through its input has nothing left to resume
+    if let (Some(path), false) = (&checkpoint_path, stopped) {
+        if let Err(error) = std::fs::remove_file(path) {
+            if error.kind() != io::ErrorKind::NotFound {
+                eprintln!("Error removing checkpoint {}: {}", path, error);
+            }
+        }
+    }
 
-                // if `.parse()` succeeds the value is bound to `operation`
-                Ok(parsed_operation) => parsed_operation,
+    // when reading a file there is no one watching each error go by, so sum them up
+    if !failed_lines.is_empty() && !stdin().is_terminal() {
+        let lines: Vec<String> = failed_lines.iter().map(|line| line.to_string()).collect();
+        let noun = if failed_lines.len() == 1 { "line" } else { "lines" };
+        eprintln!("{} of {} lines failed: {} {}", failed_lines.len(), line_number, noun, lines.join(", "));
+    }
 
-                // if `.parse()` fails, then we return an `Err` with some context
-                Err(error) => return Err(format!("Failed to parse operation: {}", error).into()),
-            },
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
 
-            // if there is nothing then return an error
-            None => return Err("Failed to parse operation: Missing operator".into()),
-        };
-        current_index += 1; // we have accounted for the operation character so increment to the next character index
+    Ok(())
+}
 
-        // the remaining slice of `string` should be rhs
-        let rhs: f64 = match string[current_index..].parse() { // parse the remainder of `string`
-            Ok(parsed_rhs) => parsed_rhs,
-            Err(error) => return Err(format!("Failed to parse right hand side: {}", error).into()),
-        };
+// open the `--output` file. a new run starts it empty, but a resumed one cuts it back to `resume_length`,
+// the length it had when the checkpoint was saved, and continues from there
+fn open_output(path: &str, resume_length: Option<u64>) -> Result<File, io::Error> {
+    match resume_length {
+        Some(length) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.set_len(length)?;
+            Ok(file)
+        },
+        None => File::create(path),
+    }
+}
+
+// record that the first `line` lines of input are done, reporting rather than stopping on failure
+fn save_checkpoint(path: &str, line: usize, environment: &Environment, output: &Option<(String, File)>) {
+    let output_length = output.as_ref().and_then(|(_, file)| file.metadata().ok()).map(|metadata| metadata.len());
+    if let Err(error) = Checkpoint::new(line, output_length, environment).save(Path::new(path)) {
+        eprintln!("Error saving checkpoint {}: {}", path, error);
+    }
+}
+
+// evaluate the `name=value` definition of a `--var` argument and store it in `environment`.
+// the value can be any expression, including ones using earlier `--var`s
+fn define_variable(definition: &str, environment: &mut Environment) -> Result<(), Box<dyn std::error::Error>> {
+    match definition.parse()? {
+        Statement::Assignment { name, expression } => {
+            let value = expression.evaluate(environment)?;
+            environment.assign(&name, value)
+        },
+        Statement::Expression(_) => Err("expected name=value".into()),
+    }
+}
+
+/// What the calculator does when a line of input can't be evaluated
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ErrorPolicy {
+    /// report the error and carry on with the next line
+    Skip,
+    /// report the error and quit with exit code 1
+    Stop,
+    /// report the error and print `NaN` as the line's result
+    EmitNan,
+}
+
+// parse and evaluate one line of input, assigning it when it is an assignment.
+// returns the label to show the result under, or a message explaining what went wrong
+fn evaluate_input(input: &str, environment: &mut Environment, deny_warnings: bool) -> Result<(String, Evaluation), String> {
+    let statement: Statement = input.parse().map_err(|error| format!("Invalid input:\n{}", error))?;
+    let evaluation = statement
+        .expression()
+        .evaluate_detailed(environment)
+        .map_err(|error| format!("Error evaluating expression:\n{}", error))?;
+
+    // in strict mode a warning is reported like any other evaluation error
+    if deny_warnings && !evaluation.warnings.is_empty() {
+        let denied: Vec<String> = evaluation
+            .warnings
+            .iter()
+            .map(|warning| format!("{} (denied by --deny-warnings)", warning))
+            .collect();
+        return Err(format!("Error evaluating expression:\n{}", denied.join("\n")));
+    }
+
+    // an assignment stores the value and shows it under the variable's name
+    if let Statement::Assignment { name, .. } = &statement {
+        environment
+            .assign(name, evaluation.value.clone())
+            .map_err(|error| format!("Error assigning variable:\n{}", error))?;
+    }
+    let label = statement_label(&statement);
+
+    // the result can be continued from on the next line as `ans`
+    environment.set_answer(evaluation.value.clone());
+
+    Ok((label, evaluation))
+}
+
+// the label a statement's result is shown under: the variable's name for an assignment, otherwise the formatted expression
+fn statement_label(statement: &Statement) -> String {
+    match statement {
+        Statement::Assignment { name, .. } => name.clone(),
+        Statement::Expression(expression) => expression.to_string(),
+    }
+}
 
-        Ok(Expression { lhs, rhs, operation })
+// the label for `input`, falling back to the input as typed when it doesn't parse
+fn input_label(input: &str) -> String {
+    match input.parse::<Statement>() {
+        Ok(statement) => statement_label(&statement),
+        Err(_) => input.trim().to_owned(),
     }
 }
-impl Display for Expression { // allows for `println!()` and `.to_string()`
 
-    /// writes the the expression to the formatter `f`
-    /// # Parameters
-    ///  - `f`: the `Formatter` that we will write the expression to. (can be a string or stdout) 
-    /// # Returns
-    ///  - `Ok(())`: if `write!` succeeds
-    ///  - `Err(format_error)`: if `write!` fails
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {} {}", self.lhs, self.operation, self.rhs)
+// fill in the template at `path`, writing the document to the `--output` file or else to stdout
+fn run_template(path: &str, environment: &mut Environment, output: Option<(String, File)>) -> Result<(), Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path).map_err(|error| format!("Can't read template `{}`: {}", path, error))?;
+    let document = template::render(&text, environment).map_err(|error| format!("Error in template `{}` at {}", path, error))?;
+
+    match output {
+        Some((output_path, mut file)) => file
+            .write_all(document.as_bytes())
+            .map_err(|error| format!("Error writing to {}: {}", output_path, error))?,
+        None => io::stdout().write_all(document.as_bytes())?,
     }
+    Ok(())
 }
 
-/// An enumeration representing each supported operation
-enum Operation {
-    Add,
-    Subtract,
-    Multiply,
-    Divide,
-    Exponential,
+// copy stdin to stdout a line at a time, replacing every `$((expression))` with its value.
+// returns whether every expression could be evaluated; the ones that couldn't are reported and left unchanged
+fn run_filter(environment: &mut Environment, mut progress: Progress) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut stdout = io::stdout().lock();
+    let mut all_evaluated = true;
+
+    let mut line = String::new();
+    let mut line_number = 0;
+    while stdin().read_line(&mut line)? != 0 {
+        line_number += 1;
+        progress.advance(line.len());
+        let (filtered, errors) = template::filter_line(&line, environment);
+        if !errors.is_empty() {
+            progress.clear();
+        }
+        for error in &errors {
+            eprintln!("line {}: {}", line_number, error);
+            all_evaluated = false;
+        }
+
+        // the reader stopping early, like `head`, isn't an error
+        match stdout.write_all(filtered.as_bytes()).and_then(|_| stdout.flush()) {
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => break,
+            result => result?,
+        }
+        line.clear();
+    }
+
+    progress.clear();
+    Ok(all_evaluated)
 }
-impl FromStr for Operation { // Trait that allows `.parse()` to work
 
-    type Err = Box<dyn std::error::Error>; // parse error type
+// evaluate a single expression given on the command line, printing only its value so scripts can capture it
+fn run_once(input: &str, environment: &Environment, deny_warnings: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let expression: Expression = input.parse()?;
+    let evaluation = expression.evaluate_detailed(environment)?;
 
-    /// Creates a new instance of Operation if the `s` is a supported operation.<br>
-    /// supported operation characters: `+` `-` `*` `/` `^`
-    /// # Parameters
-    ///  - `s`: The string slice to be parsed
-    /// # Returns
-    ///  - `Ok(operation)`: When `s` is one of the supported operation characters,
-    ///  - `Err(from_str_error)`: When `s` is not one of the supported operation characters,
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "+" => Ok(Operation::Add),
-            "-" => Ok(Operation::Subtract),
-            "*" => Ok(Operation::Multiply),
-            "/" => Ok(Operation::Divide),
-            "^" => Ok(Operation::Exponential),
-            _ => Err("Invalid operator. Supported operators: + - * / ^".into())
+    if deny_warnings {
+        if let Some(warning) = evaluation.warnings.first() {
+            return Err(format!("{} (denied by --deny-warnings)", warning).into());
+        }
+    }
+
+    println!("{}", evaluation);
+    for warning in &evaluation.warnings {
+        report_warning(warning);
+    }
+    Ok(())
+}
+
+// format `label = result`, followed by `<type, exactness>` when `verbose` is set,
+// with the units of a quantity and the base of a result that isn't written in base 10 after them.
+// a matrix with more than one row starts on the line after the `=`, so its rows line up.
+// a real result is written as a hexadecimal floating point literal when `hex_float` is set,
+// and a complex one in polar form when `polar` is set
+fn format_result(label: &str, evaluation: &Evaluation, verbose: bool, hex_float: bool, polar: bool) -> String {
+    let formatted_result = match &evaluation.value {
+        _ if evaluation.boolean => evaluation.to_string(),
+        Value::Real(result) if hex_float => format::format_hex_float(*result),
+        Value::Integer(integer) if hex_float => format::format_hex_float(integer.to_f64()),
+        Value::Complex(z) if polar => complex::format_polar(*z),
+        _ => evaluation.to_string(),
+    };
+    let separator = match &evaluation.value {
+        Value::Matrix(matrix) if matrix.rows() > 1 && !evaluation.boolean => " =\n",
+        _ => " = ",
+    };
+    if verbose {
+        let kind = match &evaluation.value {
+            _ if evaluation.boolean => "bool",
+            Value::Complex(_) => "complex",
+            Value::Integer(_) => "int",
+            Value::Rational(fraction) if fraction.is_integer() => "int",
+            Value::Rational(_) => "fraction",
+            Value::Ratio(_) => "ratio",
+            Value::Decimal(decimal) if decimal.is_integer() => "int",
+            Value::Decimal(_) => "decimal",
+            Value::Interval(_) => "interval",
+            Value::Quantity(_) => "quantity",
+            Value::Date(_) => "date",
+            Value::Matrix(_) => "matrix",
+            Value::Expression(_) => "expression",
+            Value::List(_) => "list",
+            Value::Real(result) if result.is_finite() && result.fract() == 0.0 => "int",
+            Value::Real(_) => "float",
+        };
+        let exactness = if evaluation.exact { "exact" } else { "inexact" };
+        let mut metadata = vec![kind.to_owned(), exactness.to_owned()];
+
+        // a quantity also shows the unit it is in, and a result not written in base 10 shows its base
+        if let Value::Quantity(quantity) = &evaluation.value {
+            metadata.push(match quantity.unit() {
+                Some(unit) => unit.name.clone(),
+                None => quantity.dimension().to_string(),
+            });
         }
+        if hex_float && !evaluation.boolean && matches!(evaluation.value, Value::Real(_) | Value::Integer(_)) {
+            metadata.push("base 16".to_owned());
+        }
+        format!("{}{}{}  <{}>", label, separator, formatted_result, metadata.join(", "))
+    } else {
+        format!("{}{}{}", label, separator, formatted_result)
+    }
+}
+
+// add `line` to the end of the file at `path`, creating the file if it doesn't exist
+fn append_line(path: &str, line: &str) -> Result<(), io::Error> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+// print a warning to stderr, in yellow when stderr is a terminal
+fn report_warning(warning: &Warning) {
+    if io::stderr().is_terminal() {
+        eprintln!("\x1b[33mWarning: {}\x1b[0m", warning);
+    } else {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
+/// Checks whether `input` is a request to quit the calculator.<br>
+/// accepted forms: `exit` `quit` `q` `:q` `:exit` `:quit` and `exit(code)`
+/// # Parameters
+///  - `input`: a trimmed line of user input
+/// # Returns
+///  - `Some(exit_code)`: when `input` is one of the quit commands. `exit(code)` gives `code`, every other form gives `0`
+///  - `None`: when `input` should be treated as an expression
+fn parse_exit_command(input: &str) -> Option<i32> {
+    let input = input.to_lowercase();
+    match input.as_str() {
+        "exit" | "quit" | "q" | ":q" | ":exit" | ":quit" => Some(0),
+        _ => input
+            .strip_prefix("exit(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .and_then(|code| code.trim().parse().ok()),
     }
 }
 
-impl Display for Operation { // allows for `println!()` and `.to_string()`
+/// One step of `calc tutorial`
+struct Lesson {
+    /// what the lesson teaches
+    explanation: &'static str,
+    /// what the user is asked to do
+    task: &'static str,
+    /// the value the user's expression has to evaluate to
+    answer: f64,
+    /// the mode the answer has to be worked out in, or `None` for any mode
+    mode: Option<Mode>,
+}
+
+/// The lessons of `calc tutorial`, in order
+const LESSONS: &[Lesson] = &[
+    Lesson {
+        explanation: "Type an expression like 1 + 2, then press enter. Spaces are optional.",
+        task: "Add 19 and 23",
+        answer: 42.0,
+        mode: None,
+    },
+    Lesson {
+        explanation: "The supported operators are + - * / // % and ^",
+        task: "Multiply 6 by 7",
+        answer: 42.0,
+        mode: None,
+    },
+    Lesson {
+        explanation: "Numbers can have a fractional part after a '.'",
+        task: "Divide 10.5 by 0.25",
+        answer: 42.0,
+        mode: None,
+    },
+    Lesson {
+        explanation: "Longer expressions follow the usual order of operations: ^ first, then * / // and %, then + and -.\nParentheses group the parts you want evaluated first.",
+        task: "Compute 5 + 1 and multiply the sum by 7, in one expression",
+        answer: 42.0,
+        mode: None,
+    },
+    Lesson {
+        explanation: "^ raises the number on its left to the power of the number on its right",
+        task: "Compute 2 to the power of 10",
+        answer: 1024.0,
+        mode: None,
+    },
+    Lesson {
+        explanation: "A fractional power takes a root: x ^ 0.5 is the square root of x",
+        task: "Compute the square root of 81",
+        answer: 9.0,
+        mode: None,
+    },
+    Lesson {
+        explanation: "Functions are called by name with their arguments in parentheses, like sqrt(2) or log(8, 2).\nType :help in the calculator to list every function.",
+        task: "Use sqrt to compute the square root of 1764",
+        answer: 42.0,
+        mode: None,
+    },
+    Lesson {
+        explanation: "A name followed by = and an expression stores the value in a variable, like x = 5.\nVariables are kept for the rest of the session.",
+        task: "Store 6 * 7 in a variable called answer",
+        answer: 42.0,
+        mode: None,
+    },
+    Lesson {
+        explanation: "Once a variable is stored, its name can be used anywhere a number can",
+        task: "Compute half of your answer variable",
+        answer: 21.0,
+        mode: None,
+    },
+    Lesson {
+        explanation: ":mode changes how numbers are represented. In fraction mode results are exact fractions, so 1/3 is 1/3 rather than 0.3333333333333333.\n\
+            :mode float goes back to floating point, and :mode on its own shows the current mode.",
+        task: "Type :mode fraction, then add 1/3 and 1/6",
+        answer: 0.5,
+        mode: Some(Mode::Fraction),
+    },
+];
+
+/// Walks the user through each `Lesson`, checking their answers with the same parser and evaluator as the calculator
+/// # Returns
+///  - `Ok(())`: when the tutorial is finished or the user quits
+///  - `Err(io_error)`: when reading input fails
+fn run_tutorial() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Calculator tutorial\ntype skip to skip a lesson or exit to quit");
+
+    // variables assigned during one lesson can be used in the next
+    let mut environment = Environment::new();
+
+    for (i, lesson) in LESSONS.iter().enumerate() {
+        println!("\nLesson {}/{}: {}\nTask: {}", i + 1, LESSONS.len(), lesson.explanation, lesson.task);
+
+        // keep asking until the user gets the answer, skips, or quits
+        loop {
+            let Some(input) = get_input("tutorial> ")? else {
+                return Ok(());
+            };
+
+            if input.to_lowercase() == "skip" {
+                break;
+            }
+            if parse_exit_command(&input).is_some() {
+                println!("Goodbye!");
+                return Ok(());
+            }
+
+            if let Some(mode) = input.strip_prefix(":mode") {
+                match mode.trim() {
+                    "" => println!("mode: {}", environment.mode()),
+                    mode => match mode.parse::<Mode>() {
+                        Ok(mode) => {
+                            environment.set_mode(mode);
+                            println!("mode: {}", mode);
+                        },
+                        Err(error) => println!("{}\nTry again", error),
+                    },
+                }
+                continue;
+            }
 
-    /// writes a character corresponding to self's variant
-    /// # Parameters
-    ///  - `f`: the `Formatter` that we will write the operation character to. (can be a string or stdout) 
-    /// # Returns
-    ///  - `Ok(())`: if `write!` succeeds
-    ///  - `Err(format_error)`: if `write!` fails
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // `write!` the character corresponding to `self`'s variant to `f`
-        write!(f, "{}", match self {
-            Operation::Add => "+",
-            Operation::Subtract => "-",
-            Operation::Multiply => "*",
-            Operation::Divide => "/",
-            Operation::Exponential => "^",
-        })
+            // the same evaluation as the calculator, so `ans` works here too
+            let result = evaluate_input(&input, &mut environment, false);
+            let is_mode = lesson.mode.is_none_or(|mode| environment.mode() == mode);
+            match result {
+                Ok((label, evaluation)) if is_mode && evaluation.value.to_real() == Some(lesson.answer) => {
+                    println!("{} = {}\nCorrect!", label, evaluation);
+                    break;
+                },
+                Ok((label, evaluation)) => println!("{} = {}, not quite. Try again", label, evaluation),
+                Err(message) => println!("That didn't work:\n{}\nTry again", message),
+            }
+        }
     }
+
+    println!("\nTutorial complete! Run calc without arguments to use the calculator");
+    Ok(())
 }
 
-// get user input
-fn get_input(prompt: &str) -> Result<String, io::Error> {
-    io::stdout().write(prompt.as_bytes())?;
+// get user input, or `None` once there is no more input
+fn get_input(prompt: &str) -> Result<Option<String>, io::Error> {
+    io::stdout().write_all(prompt.as_bytes())?;
     io::stdout().flush()?;
 
+    // nothing is read once stdin is closed, like at the end of a piped file or after Ctrl+D
     let mut input = String::new();
-    stdin().read_line(&mut input)?;
+    if stdin().read_line(&mut input)? == 0 {
+        return Ok(None);
+    }
     let input = input.trim().to_owned();
 
-    Ok(input)
+    Ok(Some(input))
 }
\ No newline at end of file
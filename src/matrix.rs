@@ -0,0 +1,153 @@
+use std::fmt::Display;
+
+use crate::value::Value;
+
+/// A grid of real numbers, like `[1, 2; 3, 4]`, with its elements stored row by row.<br>
+/// A single row like `[1, 2, 3]` is a row vector, which also serves as a list, and a single column like `[1; 2; 3]` is a column vector
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    columns: usize,
+    elements: Vec<f64>,
+}
+impl Matrix {
+    /// Creates a matrix from its elements, row by row
+    /// # Parameters
+    ///  - `columns`: how many elements each row has, which is at least 1
+    ///  - `elements`: every element, whose count is a multiple of `columns`
+    pub fn new(columns: usize, elements: Vec<f64>) -> Self {
+        Matrix { rows: elements.len() / columns, columns, elements }
+    }
+
+    /// The square matrix with `1` down its diagonal and `0` everywhere else, which `*` leaves other matrices unchanged by
+    /// # Parameters
+    ///  - `size`: how many rows and columns it has, which is at least 1
+    pub fn identity(size: usize) -> Self {
+        let elements = (0..size * size).map(|i| if i % (size + 1) == 0 { 1.0 } else { 0.0 }).collect();
+        Matrix::new(size, elements)
+    }
+
+    /// How many rows the matrix has
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// How many columns the matrix has
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Every element, row by row
+    pub fn elements(&self) -> &[f64] {
+        &self.elements
+    }
+
+    /// The element in `row` and `column`, counting from 0
+    pub fn get(&self, row: usize, column: usize) -> f64 {
+        self.elements[row * self.columns + column]
+    }
+
+    /// The matrix with its rows and columns swapped, so `[1, 2; 3, 4]` becomes `[1, 3; 2, 4]`
+    pub fn transpose(&self) -> Self {
+        let elements = (0..self.columns)
+            .flat_map(|column| (0..self.rows).map(move |row| self.get(row, column)))
+            .collect();
+        Matrix::new(self.rows, elements)
+    }
+
+    /// Applies `function` to every element
+    pub fn map(&self, function: impl Fn(f64) -> f64) -> Self {
+        Matrix::new(self.columns, self.elements.iter().map(|element| function(*element)).collect())
+    }
+
+    /// Stretches the matrix to `rows` x `columns` by repeating its only row or only column,
+    /// so `[1, 2]` broadcast to 2 x 2 is `[1, 2; 1, 2]`, and a `1 x 1` matrix fills the whole shape
+    /// # Returns
+    ///  - `Some(matrix)`: the stretched matrix
+    ///  - `None`: when the matrix has more than 1 row but not `rows`, or more than 1 column but not `columns`
+    pub fn broadcast(&self, rows: usize, columns: usize) -> Option<Self> {
+        if (self.rows != rows && self.rows != 1) || (self.columns != columns && self.columns != 1) {
+            return None;
+        }
+        // a row or column being stretched is read again for every row or column of the result
+        let elements = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .map(|(row, column)| self.get(row % self.rows, column % self.columns))
+            .collect();
+        Some(Matrix::new(columns, elements))
+    }
+
+    /// The matrix product `self * rhs`, where each element is a row of `self` times a column of `rhs`
+    /// # Returns
+    ///  - `Some(product)`: the product, with the rows of `self` and the columns of `rhs`
+    ///  - `None`: when `self` doesn't have as many columns as `rhs` has rows
+    pub fn mul(&self, rhs: &Matrix) -> Option<Self> {
+        if self.columns != rhs.rows {
+            return None;
+        }
+        let elements = (0..self.rows)
+            .flat_map(|row| (0..rhs.columns).map(move |column| (row, column)))
+            .map(|(row, column)| (0..self.columns).map(|i| self.get(row, i) * rhs.get(i, column)).sum())
+            .collect();
+        Some(Matrix::new(rhs.columns, elements))
+    }
+}
+impl Display for Matrix { // allows for `println!()` and `.to_string()`
+
+    /// writes each row of the matrix on its own line, with the columns lined up, like
+    /// ```text
+    /// [1  -2]
+    /// [3   4]
+    /// ```
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the matrix to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let texts: Vec<String> = self.elements.iter().map(f64::to_string).collect();
+        let widths: Vec<usize> = (0..self.columns)
+            .map(|column| texts.iter().skip(column).step_by(self.columns).map(String::len).max().unwrap_or_default())
+            .collect();
+
+        for (row, texts) in texts.chunks(self.columns).enumerate() {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "[")?;
+            for (column, text) in texts.iter().enumerate() {
+                if column > 0 {
+                    write!(f, "  ")?;
+                }
+                write!(f, "{:>width$}", text, width = widths[column])?;
+            }
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+/// A builtin function that takes a matrix
+pub struct MatrixFunction {
+    /// what the user types before the `(`
+    pub name: &'static str,
+    /// computes the result from the matrix
+    pub call: fn(&Matrix) -> Value,
+}
+
+/// Every builtin function that works on matrices. They all take exactly 1 argument
+pub const MATRIX_FUNCTIONS: &[MatrixFunction] = &[
+    MatrixFunction { name: "transpose", call: |matrix| Value::Matrix(matrix.transpose()) },
+    MatrixFunction { name: "rows", call: |matrix| Value::Real(matrix.rows() as f64) },
+    MatrixFunction { name: "columns", call: |matrix| Value::Real(matrix.columns() as f64) },
+];
+
+/// Looks up a builtin function that works on matrices by name
+/// # Parameters
+///  - `name`: the name the user typed
+/// # Returns
+///  - `Some(function)`: when there is a matrix builtin called `name`
+///  - `None`: when there is no such builtin
+pub fn find_matrix_function(name: &str) -> Option<&'static MatrixFunction> {
+    MATRIX_FUNCTIONS.iter().find(|function| function.name == name)
+}
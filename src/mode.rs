@@ -0,0 +1,105 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+};
+
+/// How numbers are represented while an expression is evaluated, chosen with `:mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// 64 bit floating point, where `1/3` is `0.3333333333333333`
+    #[default]
+    Float,
+    /// exact fractions, where `1/3 + 1/6` is `1/2`.
+    /// Anything a fraction can't hold, like `sqrt(2)` or `pi`, is worked out in floating point instead
+    Fraction,
+    /// exact base 10 numbers of any size, where `0.1 + 0.2` is `0.3`.
+    /// Quotients that don't end, like `1/3`, are rounded to 50 significant digits,
+    /// and anything that isn't a decimal, like `sqrt(2)` or `pi`, is worked out in floating point instead
+    Decimal,
+    /// 64 bit floating point that keeps bounds on the true result, where `0.1 + 0.2` is `0.30000000000000004 ± 2e-16`.
+    /// Anything without an interval version, like `sin` or comparisons, is worked out on the midpoint in floating point instead
+    Interval,
+}
+impl FromStr for Mode { // Trait that allows `.parse()` to work
+
+    type Err = Box<dyn std::error::Error>; // parse error type
+
+    /// Creates a new instance of Mode from its name.<br>
+    /// supported modes: `float` `fraction` `decimal` `interval`
+    /// # Parameters
+    ///  - `s`: The string slice to be parsed
+    /// # Returns
+    ///  - `Ok(mode)`: When `s` names a mode
+    ///  - `Err(from_str_error)`: When `s` isn't the name of a mode
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "float" => Ok(Mode::Float),
+            "fraction" => Ok(Mode::Fraction),
+            "decimal" => Ok(Mode::Decimal),
+            "interval" => Ok(Mode::Interval),
+            _ => Err(format!("Unknown mode `{}`. Supported modes: float fraction decimal interval", s).into()),
+        }
+    }
+}
+impl Display for Mode { // allows for `println!()` and `.to_string()`
+
+    /// writes the name of the mode, as `:mode` accepts it
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the name to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Mode::Float => "float",
+            Mode::Fraction => "fraction",
+            Mode::Decimal => "decimal",
+            Mode::Interval => "interval",
+        })
+    }
+}
+
+/// The unit a plain number given to `sin`, `cos`, or `tan` is in, chosen with `:mode rad` or `:mode deg`.<br>
+/// An angle with units like `30 deg` or `1 rad` is the same in either mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AngleMode {
+    /// radians, where `sin(pi / 2)` is `1`
+    #[default]
+    Radians,
+    /// degrees, where `sin(90)` is `1`
+    Degrees,
+}
+impl FromStr for AngleMode { // Trait that allows `.parse()` to work
+
+    type Err = Box<dyn std::error::Error>; // parse error type
+
+    /// Creates a new instance of AngleMode from its name.<br>
+    /// supported angle modes: `rad` `deg`
+    /// # Parameters
+    ///  - `s`: The string slice to be parsed
+    /// # Returns
+    ///  - `Ok(angle_mode)`: When `s` names an angle mode
+    ///  - `Err(from_str_error)`: When `s` isn't the name of an angle mode
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rad" => Ok(AngleMode::Radians),
+            "deg" => Ok(AngleMode::Degrees),
+            _ => Err(format!("Unknown angle mode `{}`. Supported angle modes: rad deg", s).into()),
+        }
+    }
+}
+impl Display for AngleMode { // allows for `println!()` and `.to_string()`
+
+    /// writes the name of the angle mode, as `:mode` accepts it
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the name to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            AngleMode::Radians => "rad",
+            AngleMode::Degrees => "deg",
+        })
+    }
+}
@@ -0,0 +1,801 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+};
+
+use crate::{
+    bigint::{BigInt, MAX_EXACT_DIGITS},
+    complex::Complex,
+    date::{duration, Date},
+    decimal::Decimal,
+    interval::Interval,
+    matrix::Matrix,
+    rational::Rational,
+    unit::{Dimension, Quantity, TIME},
+    value::Value,
+    warning::Warning,
+};
+
+/// every integer up to 2^53 fits exactly in an f64's 53 bit significand
+pub const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// An enumeration representing each supported operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    IntegerDivide,
+    Modulo,
+    Exponential,
+    ElementwiseMultiply,
+    ElementwiseDivide,
+    ElementwisePower,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    And,
+    Or,
+}
+impl FromStr for Operation { // Trait that allows `.parse()` to work
+
+    type Err = Box<dyn std::error::Error>; // parse error type
+
+    /// Creates a new instance of Operation if the `s` is a supported operation.<br>
+    /// supported operators: `+` `-` `*` `/` `//` `%` `^` `.*` `./` `.^` `&` `|` `xor` `<<` `>>` `==` `!=` `<` `<=` `>` `>=` `and` `or`
+    /// # Parameters
+    ///  - `s`: The string slice to be parsed
+    /// # Returns
+    ///  - `Ok(operation)`: When `s` is one of the supported operation characters,
+    ///  - `Err(from_str_error)`: When `s` is not one of the supported operation characters,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "+" => Ok(Operation::Add),
+            "-" => Ok(Operation::Subtract),
+            "*" => Ok(Operation::Multiply),
+            "/" => Ok(Operation::Divide),
+            "//" => Ok(Operation::IntegerDivide),
+            "%" => Ok(Operation::Modulo),
+            "^" => Ok(Operation::Exponential),
+            ".*" => Ok(Operation::ElementwiseMultiply),
+            "./" => Ok(Operation::ElementwiseDivide),
+            ".^" => Ok(Operation::ElementwisePower),
+            "&" => Ok(Operation::BitwiseAnd),
+            "|" => Ok(Operation::BitwiseOr),
+            "xor" => Ok(Operation::BitwiseXor),
+            "<<" => Ok(Operation::ShiftLeft),
+            ">>" => Ok(Operation::ShiftRight),
+            "==" => Ok(Operation::Equal),
+            "!=" => Ok(Operation::NotEqual),
+            "<" => Ok(Operation::Less),
+            "<=" => Ok(Operation::LessEqual),
+            ">" => Ok(Operation::Greater),
+            ">=" => Ok(Operation::GreaterEqual),
+            "and" => Ok(Operation::And),
+            "or" => Ok(Operation::Or),
+            _ => Err("Invalid operator. Supported operators: + - * / // % ^ .* ./ .^ & | xor << >> == != < <= > >= and or".into())
+        }
+    }
+}
+
+impl Operation {
+    /// The precedence of the loosest binding operations
+    pub const LOWEST_PRECEDENCE: u8 = 1;
+    /// The precedence of the tightest binding operations
+    pub const HIGHEST_PRECEDENCE: u8 = 11;
+    /// The precedence of the prefix `not`, which binds tighter than `and` but looser than comparisons,
+    /// so `not a < b` is `not (a < b)`. No binary operation has this precedence
+    pub const NOT_PRECEDENCE: u8 = 3;
+
+    /// How tightly this operation binds its operands. Higher binds tighter.
+    /// Logic binds looser than comparisons, and the bitwise operations bind looser than arithmetic,
+    /// so `1 << 2 + 1 > 4 and 1` is `((1 << (2 + 1)) > 4) and 1`
+    /// # Returns
+    ///  - `1`: for `or`
+    ///  - `2`: for `and`
+    ///  - `4`: for `==` `!=` `<` `<=` `>` and `>=`
+    ///  - `5`: for `|`
+    ///  - `6`: for `xor`
+    ///  - `7`: for `&`
+    ///  - `8`: for `<<` and `>>`
+    ///  - `9`: for `+` and `-`
+    ///  - `10`: for `*` `/` `//` `%` `.*` and `./`
+    ///  - `11`: for `^` and `.^`
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Operation::Or => 1,
+            Operation::And => 2,
+            Operation::Equal
+            | Operation::NotEqual
+            | Operation::Less
+            | Operation::LessEqual
+            | Operation::Greater
+            | Operation::GreaterEqual => 4,
+            Operation::BitwiseOr => 5,
+            Operation::BitwiseXor => 6,
+            Operation::BitwiseAnd => 7,
+            Operation::ShiftLeft | Operation::ShiftRight => 8,
+            Operation::Add | Operation::Subtract => 9,
+            Operation::Multiply
+            | Operation::Divide
+            | Operation::IntegerDivide
+            | Operation::Modulo
+            | Operation::ElementwiseMultiply
+            | Operation::ElementwiseDivide => 10,
+            Operation::Exponential | Operation::ElementwisePower => 11,
+        }
+    }
+
+    /// Whether this operation compares its operands. A chain of comparisons checks each neighbouring pair,
+    /// so `1 < x <= 3` is `1 < x and x <= 3`
+    pub fn is_comparison(&self) -> bool {
+        self.precedence() == Operation::Equal.precedence()
+    }
+
+    /// Whether this operation always results in a boolean, which is `1` for true and `0` for false
+    pub fn is_boolean(&self) -> bool {
+        self.is_comparison() || matches!(self, Operation::And | Operation::Or)
+    }
+
+    /// Whether a chain of this operation groups from the right, e.g. `2 ^ 3 ^ 2` is `2 ^ (3 ^ 2)`.<br>
+    /// Every other operation groups from the left, e.g. `8 - 4 - 2` is `(8 - 4) - 2`.
+    /// Operations with the same precedence always have the same associativity
+    pub fn is_right_associative(&self) -> bool {
+        matches!(self, Operation::Exponential | Operation::ElementwisePower)
+    }
+
+    /// The operation that an element-wise operation applies to each pair of elements, so `.*` is `*`.
+    /// On numbers that aren't matrices the two are the same, so `2 .* 3` is `6`
+    /// # Returns
+    ///  - `*` `/` or `^`: for `.*` `./` and `.^`
+    ///  - `self`: for every other operation
+    pub fn on_elements(&self) -> Operation {
+        match self {
+            Operation::ElementwiseMultiply => Operation::Multiply,
+            Operation::ElementwiseDivide => Operation::Divide,
+            Operation::ElementwisePower => Operation::Exponential,
+            operation => *operation,
+        }
+    }
+
+    /// Applies this operation to a pair of operands.<br>
+    /// This is the single place where the meaning of each operator is defined,
+    /// so every caller that combines two values goes through here.
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    /// # Returns
+    ///  - `Ok(result)`: the result of `lhs` `self` `rhs`
+    ///  - `Err(evaluation_error)`: when the operation is undefined for the operands (e.g. dividing by zero)
+    ///
+    /// `%` is the Euclidean remainder: the result is always between `0` and `|rhs|`,
+    /// whatever the signs of the operands, so `-7 % 3` is `2` and `7 % -3` is `1`.
+    /// `//` is the matching Euclidean quotient, so `lhs == rhs * (lhs // rhs) + lhs % rhs`.
+    /// For a positive `rhs` that is the same as rounding `lhs / rhs` down, e.g. `-7 // 2` is `-4`
+    ///
+    /// The bitwise operations work on the two's complement bits of whole numbers between -2^53 and 2^53,
+    /// and `>>` keeps the sign, so `-8 >> 1` is `-4`
+    ///
+    /// Comparisons and logic result in `1` for true and `0` for false, and treat any nonzero operand as true
+    pub fn apply(&self, lhs: f64, rhs: f64) -> Result<f64, Box<dyn std::error::Error>> {
+        match self {
+            Operation::Equal => Ok(boolean(lhs == rhs)),
+            Operation::NotEqual => Ok(boolean(lhs != rhs)),
+            Operation::Less => Ok(boolean(lhs < rhs)),
+            Operation::LessEqual => Ok(boolean(lhs <= rhs)),
+            Operation::Greater => Ok(boolean(lhs > rhs)),
+            Operation::GreaterEqual => Ok(boolean(lhs >= rhs)),
+            Operation::And => Ok(boolean(lhs != 0.0 && rhs != 0.0)),
+            Operation::Or => Ok(boolean(lhs != 0.0 || rhs != 0.0)),
+            Operation::BitwiseAnd => Ok((integer_operand(lhs, "&")? & integer_operand(rhs, "&")?) as f64),
+            Operation::BitwiseOr => Ok((integer_operand(lhs, "|")? | integer_operand(rhs, "|")?) as f64),
+            Operation::BitwiseXor => Ok((integer_operand(lhs, "xor")? ^ integer_operand(rhs, "xor")?) as f64),
+            Operation::ShiftLeft => Ok(((integer_operand(lhs, "<<")? as i128) << shift_amount(rhs, "<<")?) as f64),
+            Operation::ShiftRight => Ok((integer_operand(lhs, ">>")? >> shift_amount(rhs, ">>")?) as f64),
+            Operation::Add         => Ok(lhs + rhs),
+            Operation::Subtract    => Ok(lhs - rhs),
+            Operation::Multiply    => Ok(lhs * rhs),
+            Operation::Exponential => Ok(lhs.powf(rhs)),
+            Operation::Divide
+                if rhs != 0.0      => Ok(lhs / rhs),
+            Operation::Divide      => Err("Divide by zero error".into()),
+            Operation::IntegerDivide
+                if rhs != 0.0      => Ok(lhs.div_euclid(rhs)),
+            Operation::IntegerDivide => Err("Divide by zero error".into()),
+            Operation::Modulo
+                if rhs != 0.0      => Ok(lhs.rem_euclid(rhs)),
+            Operation::Modulo      => Err("Modulo by zero error".into()),
+            Operation::ElementwiseMultiply
+            | Operation::ElementwiseDivide
+            | Operation::ElementwisePower => self.on_elements().apply(lhs, rhs),
+        }
+    }
+
+    /// Checks whether adding/subtracting `lhs` and `rhs` cancelled out most of the significant digits.
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    ///  - `result`: the value returned by `apply` for the operands
+    /// # Returns
+    ///  - `Some(warning)`: when the operands were nearly equal and most digits of `result` may be wrong
+    ///  - `None`: when no precision loss was detected
+    pub fn precision_warning(&self, lhs: f64, rhs: f64, result: f64) -> Option<Warning> {
+        // if the result is this many times smaller than the operands, at least 8 of the ~16 digits were cancelled
+        const CANCELLATION_RATIO: f64 = 1e-8;
+
+        let is_cancellation = match self {
+            Operation::Add => lhs.signum() != rhs.signum(),
+            Operation::Subtract => lhs.signum() == rhs.signum(),
+            _ => false,
+        };
+        let largest_operand = lhs.abs().max(rhs.abs());
+        if is_cancellation && result != 0.0 && result.abs() < largest_operand * CANCELLATION_RATIO {
+            return Some(Warning::Cancellation { lhs, rhs });
+        }
+
+        None
+    }
+
+    /// Checks whether computing `result` in floating point rounded away any of the true result.
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    ///  - `result`: the value returned by `apply` for the operands
+    /// # Returns
+    ///  - `true`: when `result` is exactly `lhs` `self` `rhs`
+    ///  - `false`: when `result` was rounded, or isn't finite
+    pub fn is_exact(&self, lhs: f64, rhs: f64, result: f64) -> bool {
+        if !result.is_finite() {
+            return false;
+        }
+
+        match self {
+            Operation::Add => sum_is_exact(lhs, rhs, result),
+            Operation::Subtract => sum_is_exact(lhs, -rhs, result),
+            // a fused multiply-add computes the product without rounding, so any difference is rounding error
+            Operation::Multiply => lhs.mul_add(rhs, -result) == 0.0,
+            Operation::Divide => result.mul_add(rhs, -lhs) == 0.0,
+            // the quotient is right when the remainder it leaves is in range
+            Operation::IntegerDivide => {
+                let remainder = (-result).mul_add(rhs, lhs);
+                result.abs() <= MAX_EXACT_INTEGER && remainder >= 0.0 && remainder < rhs.abs()
+            },
+            // the truncated remainder is always exact, but making a negative one positive is an addition that can round
+            Operation::Modulo => {
+                let remainder = lhs % rhs;
+                remainder >= 0.0 || sum_is_exact(remainder, rhs.abs(), result)
+            },
+            // whole powers of whole numbers are exact as long as they fit in the significand
+            Operation::Exponential => {
+                lhs.fract() == 0.0
+                    && rhs.fract() == 0.0
+                    && rhs >= 0.0
+                    && result.abs() <= MAX_EXACT_INTEGER
+            },
+            // bitwise results are whole numbers, which are exact as long as they fit in the significand
+            Operation::BitwiseAnd
+            | Operation::BitwiseOr
+            | Operation::BitwiseXor
+            | Operation::ShiftLeft
+            | Operation::ShiftRight => result.abs() <= MAX_EXACT_INTEGER,
+            // comparisons and logic don't round, they only look at their operands
+            Operation::Equal
+            | Operation::NotEqual
+            | Operation::Less
+            | Operation::LessEqual
+            | Operation::Greater
+            | Operation::GreaterEqual
+            | Operation::And
+            | Operation::Or => true,
+            Operation::ElementwiseMultiply
+            | Operation::ElementwiseDivide
+            | Operation::ElementwisePower => self.on_elements().is_exact(lhs, rhs, result),
+        }
+    }
+
+    /// Applies `self` to exact integers, keeping the result exact.
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    /// # Returns
+    ///  - `Ok(Some(result))`: the exact result of `lhs` `self` `rhs`
+    ///  - `Ok(None)`: when the result isn't a whole number, like `7 / 2` or `2 ^ -1`, so it has to be worked out in floating point
+    ///  - `Err(evaluation_error)`: when dividing by zero, shifting by a negative number of places,
+    ///    or a power or left shift would have more than `MAX_EXACT_DIGITS` digits
+    pub fn apply_integer(&self, lhs: &BigInt, rhs: &BigInt) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+        let result = match self {
+            Operation::Add => lhs.add(rhs),
+            Operation::Subtract => lhs.sub(rhs),
+            Operation::Multiply => lhs.mul(rhs),
+            Operation::Divide => match lhs.div_rem(rhs) {
+                Some((quotient, remainder)) if remainder.is_zero() => quotient,
+                Some(_) => return Ok(None),
+                None => return Err("Divide by zero error".into()),
+            },
+            Operation::IntegerDivide => lhs.div_rem_euclid(rhs).ok_or("Divide by zero error")?.0,
+            Operation::Modulo => lhs.div_rem_euclid(rhs).ok_or("Modulo by zero error")?.1,
+            // a negative power isn't whole, so it is worked out in floating point
+            Operation::Exponential if rhs.is_negative() => return Ok(None),
+            // 0, 1, and -1 stay that size whatever the power, so only whether the power is 0, odd, or even matters
+            Operation::Exponential if lhs.abs() <= BigInt::from_i128(1) => match rhs.div_rem(&BigInt::from_i128(2)) {
+                _ if rhs.is_zero() => BigInt::from_i128(1),
+                Some((_, remainder)) if remainder.is_zero() => lhs.mul(lhs),
+                _ => lhs.clone(),
+            },
+            Operation::Exponential => {
+                // the power has 1 more digit than `exponent * log10(|lhs|)` rounded down, and `lhs` is only too big for an `f64` when it has hundreds of them
+                let too_big = || format!("`^` by {} would give a result with more than {} digits", rhs, MAX_EXACT_DIGITS);
+                let exponent = rhs.to_i128().and_then(|exponent| u32::try_from(exponent).ok()).ok_or_else(too_big)?;
+                let log10 = match lhs.to_f64().abs().log10() {
+                    log10 if log10.is_finite() => log10,
+                    _ => lhs.digit_count() as f64,
+                };
+                if exponent as f64 * log10 >= MAX_EXACT_DIGITS as f64 {
+                    return Err(too_big().into());
+                }
+                lhs.pow(exponent)
+            },
+            Operation::BitwiseAnd => lhs.and(rhs),
+            Operation::BitwiseOr => lhs.or(rhs),
+            Operation::BitwiseXor => lhs.xor(rhs),
+            Operation::ShiftLeft | Operation::ShiftRight => {
+                let Some(places) = rhs.to_i128().and_then(|places| u32::try_from(places).ok()) else {
+                    return Err(format!("`{}` can only shift by a whole number of places that isn't negative, but was given {}", self, rhs).into());
+                };
+                if *self == Operation::ShiftRight {
+                    lhs.shr(places)
+                } else if lhs.digit_count() as f64 + places as f64 * std::f64::consts::LOG10_2 > MAX_EXACT_DIGITS as f64 {
+                    // each place doubles the integer, which adds about 0.3 of a digit
+                    return Err(format!("`<<` by {} places would give a result with more than {} digits", places, MAX_EXACT_DIGITS).into());
+                } else {
+                    lhs.shl(places)
+                }
+            },
+            Operation::Equal => return Ok(Some(Value::Real(boolean(lhs == rhs)))),
+            Operation::NotEqual => return Ok(Some(Value::Real(boolean(lhs != rhs)))),
+            Operation::Less => return Ok(Some(Value::Real(boolean(lhs < rhs)))),
+            Operation::LessEqual => return Ok(Some(Value::Real(boolean(lhs <= rhs)))),
+            Operation::Greater => return Ok(Some(Value::Real(boolean(lhs > rhs)))),
+            Operation::GreaterEqual => return Ok(Some(Value::Real(boolean(lhs >= rhs)))),
+            _ => return Ok(None),
+        };
+        Ok(Some(Value::Integer(result)))
+    }
+
+    /// Applies `self` to exact fractions, keeping the result exact.
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    /// # Returns
+    ///  - `Ok(Some(result))`: the exact result of `lhs` `self` `rhs`
+    ///  - `Ok(None)`: when the result isn't a fraction, or is too big for one, so it has to be worked out in floating point.
+    ///    That includes the bitwise operations and powers that aren't whole
+    ///  - `Err(evaluation_error)`: when dividing by zero
+    pub fn apply_rational(&self, lhs: Rational, rhs: Rational) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+        let zero = Rational::integer(0);
+        let result = match self {
+            Operation::Add => lhs.checked_add(rhs),
+            Operation::Subtract => lhs.checked_sub(rhs),
+            Operation::Multiply => lhs.checked_mul(rhs),
+            Operation::Divide if rhs == zero => return Err("Divide by zero error".into()),
+            Operation::Divide => lhs.checked_div(rhs),
+            Operation::IntegerDivide if rhs == zero => return Err("Divide by zero error".into()),
+            Operation::IntegerDivide => lhs.checked_div_euclid(rhs),
+            Operation::Modulo if rhs == zero => return Err("Modulo by zero error".into()),
+            Operation::Modulo => lhs.checked_rem_euclid(rhs),
+            Operation::Exponential if rhs.is_integer() => lhs.checked_pow(rhs.numerator()),
+            Operation::Equal => return Ok(Some(Value::Real(boolean(lhs == rhs)))),
+            Operation::NotEqual => return Ok(Some(Value::Real(boolean(lhs != rhs)))),
+            Operation::Less | Operation::LessEqual | Operation::Greater | Operation::GreaterEqual => {
+                let Some(ordering) = lhs.checked_cmp(rhs) else {
+                    return Ok(None);
+                };
+                let is_true = match self {
+                    Operation::Less => ordering.is_lt(),
+                    Operation::LessEqual => ordering.is_le(),
+                    Operation::Greater => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                };
+                return Ok(Some(Value::Real(boolean(is_true))));
+            },
+            _ => None,
+        };
+        Ok(result.map(Value::Rational))
+    }
+
+    /// Applies `self` to exact decimals, keeping the result exact where it can be.
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    /// # Returns
+    ///  - `Ok(Some((result, exact)))`: the result of `lhs` `self` `rhs`, and whether it is exact.
+    ///    Only a quotient that doesn't end, like `1 / 3`, is rounded
+    ///  - `Ok(None)`: when the result isn't a decimal, so it has to be worked out in floating point.
+    ///    That includes the bitwise operations and powers that aren't whole
+    ///  - `Err(evaluation_error)`: when dividing by zero
+    pub fn apply_decimal(&self, lhs: &Decimal, rhs: &Decimal) -> Result<Option<(Value, bool)>, Box<dyn std::error::Error>> {
+        let exact = |decimal| Some((Value::Decimal(decimal), true));
+        match self {
+            Operation::Add => Ok(exact(lhs.add(rhs))),
+            Operation::Subtract => Ok(exact(lhs.sub(rhs))),
+            Operation::Multiply => Ok(exact(lhs.mul(rhs))),
+            Operation::Divide => match lhs.div(rhs) {
+                Some((quotient, is_exact)) => Ok(Some((Value::Decimal(quotient), is_exact))),
+                None => Err("Divide by zero error".into()),
+            },
+            Operation::IntegerDivide => match lhs.div_rem_euclid(rhs) {
+                Some((quotient, _)) => Ok(exact(quotient)),
+                None => Err("Divide by zero error".into()),
+            },
+            Operation::Modulo => match lhs.div_rem_euclid(rhs) {
+                Some((_, remainder)) => Ok(exact(remainder)),
+                None => Err("Modulo by zero error".into()),
+            },
+            Operation::Exponential => Ok(lhs.pow(rhs).map(|(power, is_exact)| (Value::Decimal(power), is_exact))),
+            Operation::Equal => Ok(Some((Value::Real(boolean(lhs == rhs)), true))),
+            Operation::NotEqual => Ok(Some((Value::Real(boolean(lhs != rhs)), true))),
+            Operation::Less => Ok(Some((Value::Real(boolean(lhs < rhs)), true))),
+            Operation::LessEqual => Ok(Some((Value::Real(boolean(lhs <= rhs)), true))),
+            Operation::Greater => Ok(Some((Value::Real(boolean(lhs > rhs)), true))),
+            Operation::GreaterEqual => Ok(Some((Value::Real(boolean(lhs >= rhs)), true))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Applies `self` to intervals, rounding the bounds of the result outward.
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    /// # Returns
+    ///  - `Ok(Some(result))`: the interval holding every result of `lhs` `self` `rhs`
+    ///  - `Ok(None)`: when there is no interval version of the operation, so it has to be worked out on the midpoints in floating point.
+    ///    That includes the comparisons, the integer and bitwise operations, and powers of numbers that aren't positive
+    ///  - `Err(evaluation_error)`: when dividing by exactly zero
+    pub fn apply_interval(&self, lhs: Interval, rhs: Interval) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+        match self {
+            Operation::Add => Ok(Some(Value::Interval(lhs.add(rhs)))),
+            Operation::Subtract => Ok(Some(Value::Interval(lhs.sub(rhs)))),
+            Operation::Multiply => Ok(Some(Value::Interval(lhs.mul(rhs)))),
+            Operation::Divide => match lhs.div(rhs) {
+                Some(quotient) => Ok(Some(Value::Interval(quotient))),
+                None => Err("Divide by zero error".into()),
+            },
+            Operation::Exponential if rhs.is_point() && rhs.midpoint().fract() == 0.0 && rhs.midpoint().abs() <= i32::MAX as f64 => {
+                Ok(lhs.powi(rhs.midpoint() as i32).map(Value::Interval))
+            },
+            Operation::Exponential => Ok(lhs.powf(rhs).map(Value::Interval)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Applies `self` to quantities with units, working on their amounts in SI base units.
+    /// # Parameters
+    ///  - `lhs`: the left hand operand, which is a plain number when it has no units
+    ///  - `rhs`: the right hand operand, which is a plain number when it has no units
+    /// # Returns
+    ///  - `Ok(result)`: the result of `lhs` `self` `rhs`, shown in the unit of an operand when it measures the same thing.
+    ///    Units that cancel out, like in `1 km / 1 m`, leave a plain number
+    ///  - `Err(evaluation_error)`: when adding, subtracting, or comparing quantities that measure different things,
+    ///    raising to a power that isn't a whole number without units, using a bitwise operation, or dividing by zero
+    pub fn apply_quantity(&self, lhs: &Quantity, rhs: &Quantity) -> Result<Value, Box<dyn std::error::Error>> {
+        let same_dimension = || match lhs.dimension() == rhs.dimension() {
+            true => Ok(lhs.dimension()),
+            false => Err(format!("`{}` needs quantities that measure the same thing, but was given {} and {}", self, lhs, rhs)),
+        };
+        let too_big = || format!("The units of `{} {} {}` have too large a power", lhs, self, rhs);
+
+        let (dimension, unit) = match self {
+            Operation::Add | Operation::Subtract | Operation::Modulo => (same_dimension()?, lhs.unit().or(rhs.unit()).cloned()),
+            Operation::IntegerDivide => (same_dimension().map(|_| Dimension::NONE)?, None),
+            _ if self.is_comparison() => (same_dimension().map(|_| Dimension::NONE)?, None),
+            Operation::And | Operation::Or => (Dimension::NONE, None),
+            // scaling by a plain number keeps the unit, as in `2 * 5 km`
+            Operation::Multiply => {
+                let unit = match (lhs.dimension().is_none(), rhs.dimension().is_none()) {
+                    (_, true) => lhs.unit().cloned(),
+                    (true, false) => rhs.unit().cloned(),
+                    (false, false) => None,
+                };
+                (lhs.dimension().mul(rhs.dimension()).ok_or_else(too_big)?, unit)
+            },
+            Operation::Divide => {
+                let unit = rhs.dimension().is_none().then(|| lhs.unit().cloned()).flatten();
+                (lhs.dimension().div(rhs.dimension()).ok_or_else(too_big)?, unit)
+            },
+            Operation::Exponential => {
+                let exponent = rhs.value();
+                if !rhs.dimension().is_none() || exponent.fract() != 0.0 || exponent.abs() > i8::MAX as f64 {
+                    return Err(format!("A quantity with units can only be raised to a whole number, but was given {}", rhs).into());
+                }
+                let exponent = exponent as i32;
+                (lhs.dimension().pow(exponent).ok_or_else(too_big)?, lhs.unit().map(|unit| unit.pow(exponent)))
+            },
+            _ => return Err(format!("`{}` doesn't work on quantities with units", self).into()),
+        };
+
+        let value = self.apply(lhs.value(), rhs.value())?;
+        Ok(Quantity::new(value, dimension, unit).into_value())
+    }
+
+    /// Applies `self` to operands where at least one is a matrix, working in floating point.<br>
+    /// `*` of two matrices is the matrix product, which needs the columns of the left to match the rows of the right,
+    /// and `^` raises a square matrix to a whole power by multiplying it by itself, so `[1, 1; 0, 1] ^ 3` is `[1, 3; 0, 1]`.
+    /// Every other arithmetic or bitwise operation, including `*` and `/` by a number and the element-wise `.*` `./` and `.^`,
+    /// works element by element, broadcasting a number, or a matrix with a single row or column, across the other operand,
+    /// so `[1, 2, 3] + 1` is `[2, 3, 4]` and `[1, 2, 3] .* [4, 5, 6]` is `[4, 10, 18]`
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    /// # Returns
+    ///  - `Ok((result, exact))`: the resulting matrix, or whether two matrices are `==` or `!=`, along with whether every element of the result is exact
+    ///  - `Err(evaluation_error)`: when the shapes don't fit the operation, the other operand isn't real, an element is outside the operation's domain,
+    ///    `/` divides by a matrix, `^` isn't a square matrix raised to a whole number that isn't negative, or `self` is an ordering comparison
+    pub fn apply_matrix(&self, lhs: &Value, rhs: &Value) -> Result<(Value, bool), Box<dyn std::error::Error>> {
+        let shape = |matrix: &Matrix| format!("{}x{}", matrix.rows(), matrix.columns());
+
+        match (self, lhs, rhs) {
+            (Operation::Multiply, Value::Matrix(lhs), Value::Matrix(rhs)) => {
+                let Some(result) = lhs.mul(rhs) else {
+                    return Err(format!(
+                        "`*` of matrices needs as many columns on the left as rows on the right, but was given {} and {}. `.*` multiplies element by element",
+                        shape(lhs),
+                        shape(rhs)
+                    )
+                    .into());
+                };
+                return Ok((Value::Matrix(result), product_is_exact(lhs, rhs)));
+            },
+            (Operation::Divide, _, Value::Matrix(_)) => {
+                return Err("`/` can't divide by a matrix. `./` divides element by element".into());
+            },
+            (Operation::Exponential, Value::Matrix(matrix), exponent) => {
+                let Some(exponent) = exponent.to_real().filter(|exponent| exponent.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(exponent)) else {
+                    return Err(format!("`^` can only raise a matrix to a whole number that isn't negative, but was given {}. `.^` raises each element", exponent).into());
+                };
+                if matrix.rows() != matrix.columns() {
+                    return Err(format!("`^` needs a square matrix, but was given {}. `.^` raises each element", shape(matrix)).into());
+                }
+                let (result, exact) = matrix_power(matrix, exponent as u32);
+                return Ok((Value::Matrix(result), exact));
+            },
+            (Operation::Exponential, _, Value::Matrix(_)) => {
+                return Err("`^` can't raise to the power of a matrix. `.^` raises to each element".into());
+            },
+            _ => {},
+        }
+
+        // a number works like a `1 x 1` matrix, which broadcasts across every element
+        let as_matrix = |value: &Value| match value {
+            Value::Matrix(matrix) => Ok(matrix.clone()),
+            value => value
+                .to_real()
+                .map(|number| Matrix::new(1, vec![number]))
+                .ok_or_else(|| format!("A matrix can only be combined with real numbers, but was given {}", value)),
+        };
+        let (lhs, rhs) = (as_matrix(lhs)?, as_matrix(rhs)?);
+
+        match self {
+            Operation::Equal | Operation::NotEqual => return Ok((Value::Real(boolean((lhs == rhs) == (*self == Operation::Equal))), true)),
+            Operation::And | Operation::Or => {
+                let (lhs, rhs) = (Value::Matrix(lhs).is_truthy(), Value::Matrix(rhs).is_truthy());
+                let result = if *self == Operation::And { lhs && rhs } else { lhs || rhs };
+                return Ok((Value::Real(boolean(result)), true));
+            },
+            _ if self.is_comparison() => return Err(format!("`{}` can't order matrices, only `==` and `!=` compare them", self).into()),
+            _ => {},
+        }
+
+        let operation = self.on_elements();
+        let (rows, columns) = (lhs.rows().max(rhs.rows()), lhs.columns().max(rhs.columns()));
+        let (Some(lhs), Some(rhs)) = (lhs.broadcast(rows, columns), rhs.broadcast(rows, columns)) else {
+            return Err(format!(
+                "`{}` needs matrices of the same shape, or a single row or column where they differ, but was given {} and {}",
+                self,
+                shape(&lhs),
+                shape(&rhs)
+            )
+            .into());
+        };
+        let elements = lhs
+            .elements()
+            .iter()
+            .zip(rhs.elements())
+            .map(|(lhs, rhs)| operation.apply(*lhs, *rhs))
+            .collect::<Result<Vec<_>, _>>()?;
+        let exact = lhs
+            .elements()
+            .iter()
+            .zip(rhs.elements())
+            .zip(&elements)
+            .all(|((lhs, rhs), result)| operation.is_exact(*lhs, *rhs, *result));
+        Ok((Value::Matrix(Matrix::new(columns, elements)), exact))
+    }
+
+    /// Applies `self` to operands where at least one is a date.
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    /// # Returns
+    ///  - `Ok(result)`: a date moved by a length of time, like `2024-03-01 + 45 days`,
+    ///    the time between two dates in days, like `now - 1990-07-14`, or a comparison of two dates
+    ///  - `Err(evaluation_error)`: when a date is moved by something other than a length of time, like a plain `45`,
+    ///    or `self` is any other operation
+    pub fn apply_date(&self, lhs: &Value, rhs: &Value) -> Result<Value, Box<dyn std::error::Error>> {
+        let seconds = |value: &Value| match value {
+            Value::Quantity(quantity) if quantity.dimension() == TIME => Ok(quantity.value()),
+            _ => Err(format!("A date can only be moved by a length of time like `45 days`, but was given {}", value)),
+        };
+
+        match (self, lhs, rhs) {
+            (Operation::Subtract, Value::Date(lhs), Value::Date(rhs)) => Ok(Value::Quantity(duration(lhs.seconds() - rhs.seconds()))),
+            (_, Value::Date(lhs), Value::Date(rhs)) if self.is_comparison() => Ok(Value::Real(self.apply(lhs.seconds(), rhs.seconds())?)),
+            (Operation::Add, Value::Date(date), other) | (Operation::Add, other, Value::Date(date)) => {
+                Ok(Value::Date(Date::from_seconds(date.seconds() + seconds(other)?)))
+            },
+            (Operation::Subtract, Value::Date(date), other) => Ok(Value::Date(Date::from_seconds(date.seconds() - seconds(other)?))),
+            _ => Err(format!("`{}` doesn't work on {} and {}", self, lhs, rhs).into()),
+        }
+    }
+
+    /// Applies `self` to complex operands.
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    /// # Returns
+    ///  - `Ok(result)`: the result of `lhs` `self` `rhs`
+    ///  - `Err(evaluation_error)`: when dividing by zero, or the operation needs real operands,
+    ///    like ordering comparisons and the integer and bitwise operations
+    pub fn apply_complex(&self, lhs: Complex, rhs: Complex) -> Result<Complex, Box<dyn std::error::Error>> {
+        let is_true = |z: Complex| z.re != 0.0 || z.im != 0.0;
+        match self {
+            Operation::Add => Ok(lhs.add(rhs)),
+            Operation::Subtract => Ok(lhs.sub(rhs)),
+            Operation::Multiply => Ok(lhs.mul(rhs)),
+            Operation::Divide => lhs.div(rhs),
+            Operation::Exponential => Ok(lhs.pow(rhs)),
+            Operation::Equal => Ok(Complex::from(boolean(lhs == rhs))),
+            Operation::NotEqual => Ok(Complex::from(boolean(lhs != rhs))),
+            Operation::And => Ok(Complex::from(boolean(is_true(lhs) && is_true(rhs)))),
+            Operation::Or => Ok(Complex::from(boolean(is_true(lhs) || is_true(rhs)))),
+            _ => Err(format!("`{}` isn't defined for complex numbers", self).into()),
+        }
+    }
+
+    /// Checks whether computing the complex `result` rounded away any of the true result.
+    /// Only sums and scaling by a real number are checked part by part, so every other arithmetic result counts as rounded
+    /// # Parameters
+    ///  - `lhs`: the left hand operand
+    ///  - `rhs`: the right hand operand
+    ///  - `result`: the value returned by `apply_complex` for the operands
+    /// # Returns
+    ///  - `true`: when `result` is known to be exactly `lhs` `self` `rhs`
+    ///  - `false`: when `result` may have been rounded
+    pub fn is_exact_complex(&self, lhs: Complex, rhs: Complex, result: Complex) -> bool {
+        match self {
+            Operation::Add | Operation::Subtract => {
+                Operation::is_exact(self, lhs.re, rhs.re, result.re) && Operation::is_exact(self, lhs.im, rhs.im, result.im)
+            },
+            // scaling by a real number multiplies each part once, like `2 * i`
+            Operation::Multiply if lhs.im == 0.0 => {
+                Operation::is_exact(self, lhs.re, rhs.re, result.re) && Operation::is_exact(self, lhs.re, rhs.im, result.im)
+            },
+            Operation::Multiply if rhs.im == 0.0 => {
+                Operation::is_exact(self, lhs.re, rhs.re, result.re) && Operation::is_exact(self, lhs.im, rhs.re, result.im)
+            },
+            Operation::Equal | Operation::NotEqual | Operation::And | Operation::Or => true,
+            _ => false,
+        }
+    }
+}
+
+/// The number that stands for a boolean: `1` for true and `0` for false
+pub fn boolean(value: bool) -> f64 {
+    if value { 1.0 } else { 0.0 }
+}
+
+/// Converts an operand of a bitwise operation to the integer whose bits it works on.
+/// Past 2^53 an `f64` is rounded to a multiple of 2 or more, so its low bits aren't the ones the user meant and it is rejected.
+/// Exact integers of any size don't come through here
+/// # Parameters
+///  - `value`: the operand
+///  - `symbol`: the operator, for the error message
+/// # Returns
+///  - `Ok(integer)`: `value` as an `i64`
+///  - `Err(operand_error)`: when `value` isn't a whole number, or is beyond 2^53
+pub fn integer_operand(value: f64, symbol: &str) -> Result<i64, Box<dyn std::error::Error>> {
+    if value.fract() != 0.0 {
+        return Err(format!("`{}` only works on whole numbers, but was given {}", symbol, value).into());
+    }
+    if value.abs() > MAX_EXACT_INTEGER {
+        return Err(format!("`{}` only works on floating point numbers between -2^53 and 2^53, where every whole number is exact, but was given {}", symbol, value).into());
+    }
+    Ok(value as i64)
+}
+
+// a sum of products of whole numbers is exact as long as no partial sum can pass the largest exact integer
+fn product_is_exact(lhs: &Matrix, rhs: &Matrix) -> bool {
+    let is_whole = |matrix: &Matrix| matrix.elements().iter().all(|element| element.fract() == 0.0);
+    is_whole(lhs)
+        && is_whole(rhs)
+        && lhs.map(f64::abs).mul(&rhs.map(f64::abs)).is_some_and(|bound| bound.elements().iter().all(|sum| *sum <= MAX_EXACT_INTEGER))
+}
+
+// raise a square matrix to `exponent` by repeated squaring, along with whether every product on the way was exact
+fn matrix_power(matrix: &Matrix, mut exponent: u32) -> (Matrix, bool) {
+    let (mut result, mut square, mut exact) = (Matrix::identity(matrix.rows()), matrix.clone(), true);
+    while exponent > 0 {
+        if exponent % 2 == 1 {
+            exact &= product_is_exact(&result, &square);
+            result = result.mul(&square).expect("powers of a square matrix are the same shape");
+        }
+        exponent /= 2;
+        if exponent > 0 {
+            exact &= product_is_exact(&square, &square);
+            square = square.mul(&square).expect("powers of a square matrix are the same shape");
+        }
+    }
+    (result, exact)
+}
+
+// the number of places to shift by, which has to leave some bits of an `i64`
+fn shift_amount(value: f64, symbol: &str) -> Result<u32, Box<dyn std::error::Error>> {
+    match integer_operand(value, symbol)? {
+        places @ 0..=63 => Ok(places as u32),
+        places => Err(format!("`{}` can only shift by 0 to 63 places, but was given {}", symbol, places).into()),
+    }
+}
+
+// checks whether `result` is exactly `lhs + rhs`, using Knuth's two-sum to recover the rounding error of the addition
+fn sum_is_exact(lhs: f64, rhs: f64, result: f64) -> bool {
+    let rhs_part = result - lhs;
+    let lhs_part = result - rhs_part;
+    let error = (lhs - lhs_part) + (rhs - rhs_part);
+    error == 0.0
+}
+
+impl Display for Operation { // allows for `println!()` and `.to_string()`
+
+    /// writes the characters corresponding to self's variant
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the operation characters to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // `write!` the characters corresponding to `self`'s variant to `f`
+        write!(f, "{}", match self {
+            Operation::Add => "+",
+            Operation::Subtract => "-",
+            Operation::Multiply => "*",
+            Operation::Divide => "/",
+            Operation::IntegerDivide => "//",
+            Operation::Modulo => "%",
+            Operation::Exponential => "^",
+            Operation::ElementwiseMultiply => ".*",
+            Operation::ElementwiseDivide => "./",
+            Operation::ElementwisePower => ".^",
+            Operation::BitwiseAnd => "&",
+            Operation::BitwiseOr => "|",
+            Operation::BitwiseXor => "xor",
+            Operation::ShiftLeft => "<<",
+            Operation::ShiftRight => ">>",
+            Operation::Equal => "==",
+            Operation::NotEqual => "!=",
+            Operation::Less => "<",
+            Operation::LessEqual => "<=",
+            Operation::Greater => ">",
+            Operation::GreaterEqual => ">=",
+            Operation::And => "and",
+            Operation::Or => "or",
+        })
+    }
+}
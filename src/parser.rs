@@ -0,0 +1,586 @@
+use crate::{
+    bigint::BigInt,
+    currency::is_currency_code,
+    expression::{Expression, Node, NodeId},
+    lexer::{parse_integer, Token, IMAGINARY_UNIT},
+    operation::Operation,
+    statement::Statement,
+    unit::find_unit,
+};
+
+/// How many parentheses and signs can be nested before parsing gives up instead of overflowing the stack
+const MAX_NESTING: usize = 256;
+
+/// Builds an `Expression` tree out of a list of `Token`s, one precedence level at a time.<br>
+/// grammar:
+/// ```text
+/// statement  := identifier "=" expression | expression
+/// expression := chain(lowest precedence) ("?" expression ":" expression | ("in" chain(lowest precedence))*)
+/// chain(not precedence) := "not" chain(not precedence) | chain(not precedence + 1)
+/// chain(p)   := chain(p + 1) (operator(p) chain(p + 1))*
+/// chain(highest precedence + 1) := unary
+/// unary      := ("-" | "+" | "~") chain(highest precedence) | postfix
+/// postfix    := primary "%"?
+/// primary    := number | date | matrix | call | identifier | "(" expression ")"
+/// matrix     := "[" row (";" row)* "]"
+/// row        := expression ("," expression)*
+/// call       := identifier "(" (argument ("," argument)*)? ")"
+/// argument   := expression ("=" expression)?
+/// ```
+/// where `operator(p)` is any operation whose `Operation::precedence` is `p`.
+/// A `%` that isn't followed by a number, name, `(`, or `~` is a percentage of the operand before it rather than a remainder,
+/// so write `7 % (-3)` for the remainder of dividing by a negative number.
+/// A `*` is implied between a number or `)` and a following `(` or name, so `2(3 + 4)`, `2pi`, and `(1 + 2)(3 + 4)` are products.
+/// A number followed by a unit binds tighter than `*` and `/` though, so `100 km / 2 h` is `(100 * km) / (2 * h)`.
+/// A sign or `~` captures the chain of tightest binding operators after it, so `-2 ^ 2` is `-(2 ^ 2)` and `2 * -3` is `2 * (-3)`.<br>
+/// The tokens borrow their names and digits from the input, which are only copied into the nodes that keep them
+pub struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    /// the nodes of the expression parsed so far
+    nodes: Vec<Node>,
+    /// how many `(` and signs are currently open
+    nesting: usize,
+}
+impl<'a> Parser<'a> {
+    /// Creates a parser that will read `tokens` from the start, with any implied `*` filled in
+    pub fn new(tokens: Vec<Token<'a>>) -> Self {
+        Parser { tokens: insert_implicit_multiplication(tokens), position: 0, nodes: Vec::new(), nesting: 0 }
+    }
+
+    /// Parses every token into a single `Expression`.
+    /// # Returns
+    ///  - `Ok(expression)`: when the tokens form exactly one valid expression
+    ///  - `Err(parse_error)`: when the tokens are empty, out of order, unbalanced, or followed by leftovers
+    pub fn parse(&mut self) -> Result<Expression, Box<dyn std::error::Error>> {
+        let root = self.parse_expression()?;
+
+        // everything has to be consumed, otherwise the input was something like `1 2` or `(1))`
+        match self.peek() {
+            None => Ok(Expression::new(std::mem::take(&mut self.nodes), root)),
+            Some(token) => Err(format!("Unexpected `{}` after the end of the expression", token).into()),
+        }
+    }
+
+    /// Parses every token into a single `Statement`.
+    /// # Returns
+    ///  - `Ok(statement)`: when the tokens form exactly one assignment or expression
+    ///  - `Err(parse_error)`: when the tokens don't form a valid statement
+    pub fn parse_statement(mut self) -> Result<Statement, Box<dyn std::error::Error>> {
+        if let [Token::Identifier(name), Token::Equals, ..] = self.tokens.as_slice() {
+            let name = name.to_string();
+            self.position = 2;
+            let expression = self.parse()?;
+            return Ok(Statement::Assignment { name, expression });
+        }
+        if matches!(self.tokens.as_slice(), [Token::Imaginary(number), Token::Equals, ..] if *number == 1.0) {
+            return Err(format!("Cannot assign to `{}`, it is the imaginary unit", IMAGINARY_UNIT).into());
+        }
+
+        Ok(Statement::Expression(self.parse()?))
+    }
+
+    // look at the next token without consuming it
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.position)
+    }
+
+    // add `node` to the expression, after all of its children
+    fn push(&mut self, node: Node) -> NodeId {
+        self.nodes.push(node);
+        NodeId::new(self.nodes.len() - 1)
+    }
+
+    // consume the next token
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.position).copied();
+        self.position += 1;
+        token
+    }
+
+    /// Parses a whole expression, which may be a conditional like `x < 0 ? -x : x` or a conversion like `5 km in m`.<br>
+    /// A conditional binds looser than every operator, and groups from the right,
+    /// so `a ? b : c ? d : e` is `a ? b : (c ? d : e)`. A conversion also binds looser than every operator,
+    /// so `5 km + 3 mi in m` converts the sum, and `a ? b : c in m` converts only `c`.
+    /// # Returns
+    ///  - `Ok(id)`: the id of the parsed expression
+    ///  - `Err(parse_error)`: when an operand is missing or malformed, or a `?` has no matching `:`
+    fn parse_expression(&mut self) -> Result<NodeId, Box<dyn std::error::Error>> {
+        let condition = self.parse_chain(Operation::LOWEST_PRECEDENCE)?;
+        if self.peek() != Some(&Token::Question) {
+            // `x in km in m` converts to `km` then to `m`
+            let mut quantity = condition;
+            while self.peek() == Some(&Token::In) {
+                self.next();
+                let unit = self.parse_chain(Operation::LOWEST_PRECEDENCE)?;
+                quantity = self.push(Node::Convert { quantity, unit });
+            }
+            return Ok(quantity);
+        }
+        self.next();
+
+        self.enter_nesting()?;
+        let if_true = self.parse_expression()?;
+        match self.next() {
+            Some(Token::Colon) => {},
+            Some(token) => return Err(format!("Expected `:` but found `{}`", token).into()),
+            None => return Err("Missing `:` after the first branch of `?`".into()),
+        }
+        let if_false = self.parse_expression()?;
+        self.nesting -= 1;
+
+        Ok(self.push(Node::Conditional { condition, if_true, if_false }))
+    }
+
+    /// Parses a run of operands joined by operators of exactly `precedence`.<br>
+    /// The run is collected into a single `Node::Chain` no matter how long it is,
+    /// so `1 + 1 + ... + 1` never nests deeper than one level.
+    /// # Parameters
+    ///  - `precedence`: the precedence of the operators joining this run
+    /// # Returns
+    ///  - `Ok(id)`: the id of the run, or its only operand when there are no operators at this precedence
+    ///  - `Err(parse_error)`: when an operand is missing or malformed
+    fn parse_chain(&mut self, precedence: u8) -> Result<NodeId, Box<dyn std::error::Error>> {
+        // operands of the tightest binding operators are signed or unsigned primaries
+        if precedence > Operation::HIGHEST_PRECEDENCE {
+            return self.parse_unary();
+        }
+
+        // `not` sits between `and` and the comparisons, and has no binary operators of its own
+        if precedence == Operation::NOT_PRECEDENCE {
+            if self.peek() != Some(&Token::Not) {
+                return self.parse_chain(precedence + 1);
+            }
+            self.next();
+
+            self.enter_nesting()?;
+            let operand = self.parse_chain(precedence)?;
+            self.nesting -= 1;
+            return Ok(self.push(Node::Not(operand)));
+        }
+
+        let first = self.parse_operand(precedence)?;
+
+        // operators that bind tighter were already consumed by the operands,
+        // and operators that bind looser end this run so a caller can claim them
+        let mut rest = Vec::new();
+        while let Some(&Token::Operator(operation)) = self.peek() {
+            if operation.precedence() != precedence {
+                break;
+            }
+            self.next();
+            rest.push((operation, self.parse_operand(precedence)?));
+        }
+
+        if rest.is_empty() {
+            Ok(first)
+        } else {
+            Ok(self.push(Node::Chain { first, rest }))
+        }
+    }
+
+    // parse one operand of a run of operators of `precedence`.
+    // a number directly followed by a unit is a single operand of `*` and `/`, so `100 km / 2 h` divides by `2 h`,
+    // while `^` still binds tighter so `5 m^2` is `5 * m^2`
+    fn parse_operand(&mut self, precedence: u8) -> Result<NodeId, Box<dyn std::error::Error>> {
+        let operand = self.parse_chain(precedence + 1)?;
+
+        let follows_number = matches!(self.tokens.get(self.position.wrapping_sub(1)), Some(Token::Number(_) | Token::Integer(_)));
+        let is_unit = matches!(self.peek(), Some(Token::Identifier(name)) if is_unit_name(name));
+        if precedence != Operation::Multiply.precedence() || !follows_number || !is_unit {
+            return Ok(operand);
+        }
+
+        let unit = self.parse_chain(precedence + 1)?;
+        Ok(self.push(Node::Chain { first: operand, rest: vec![(Operation::Multiply, unit)] }))
+    }
+
+    /// Parses an operand that may be preceded by a `-` or `+` sign, or a `~`.
+    /// # Returns
+    ///  - `Ok(id)`: the id of the operand, wrapped in `Node::Negate` for a `-` sign or `Node::BitwiseNot` for a `~`
+    ///  - `Err(parse_error)`: when the operand is missing or malformed, or signs are nested too deeply
+    fn parse_unary(&mut self) -> Result<NodeId, Box<dyn std::error::Error>> {
+        // a `+` sign leaves its operand as it is
+        let wrap: Option<fn(NodeId) -> Node> = match self.peek() {
+            Some(Token::Operator(Operation::Subtract)) => Some(Node::Negate),
+            Some(Token::Tilde) => Some(Node::BitwiseNot),
+            Some(Token::Operator(Operation::Add)) => None,
+            _ => return self.parse_postfix(),
+        };
+        self.next();
+
+        self.enter_nesting()?;
+        let operand = self.parse_chain(Operation::HIGHEST_PRECEDENCE)?;
+        self.nesting -= 1;
+
+        match wrap {
+            Some(wrap) => Ok(self.push(wrap(operand))),
+            None => Ok(operand),
+        }
+    }
+
+    // count one more level of nesting, failing once there are too many
+    fn enter_nesting(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.nesting += 1;
+        if self.nesting > MAX_NESTING {
+            return Err(format!("Expression is nested too deeply (at most {} levels of parentheses, signs, and conditionals)", MAX_NESTING).into());
+        }
+        Ok(())
+    }
+
+    /// Parses an operand that may be followed by `!` making it a factorial, then by a `%` making it a percentage.
+    /// # Returns
+    ///  - `Ok(id)`: the id of the operand, wrapped in `Node::Factorial` for each trailing `!` and `Node::Percent` for a trailing `%`
+    ///  - `Err(parse_error)`: when the operand is missing or malformed, or has too many `!`
+    fn parse_postfix(&mut self) -> Result<NodeId, Box<dyn std::error::Error>> {
+        let mut operand = self.parse_primary()?;
+
+        // `n!!` is the factorial of `n!`, so each `!` wraps everything before it
+        let mut factorials = 0;
+        while self.peek() == Some(&Token::Exclamation) {
+            self.next();
+            self.enter_nesting()?;
+            factorials += 1;
+            operand = self.push(Node::Factorial(operand));
+        }
+        self.nesting -= factorials;
+
+        // `%` followed by another operand is the remainder operator, so leave it for `parse_chain`
+        let is_percent = self.peek() == Some(&Token::Operator(Operation::Modulo))
+            && !matches!(
+                self.tokens.get(self.position + 1),
+                Some(
+                    Token::Number(_)
+                    | Token::Integer(_)
+                    | Token::Imaginary(_)
+                    | Token::Identifier(_)
+                    | Token::LeftParenthesis
+                    | Token::LeftBracket
+                    | Token::Tilde
+                    | Token::Not
+                )
+            );
+        if !is_percent {
+            return Ok(operand);
+        }
+        self.next();
+
+        Ok(self.push(Node::Percent(operand)))
+    }
+
+    /// Parses a single operand: a number, a function call, a name, or a parenthesized expression.
+    /// # Returns
+    ///  - `Ok(id)`: the id of the parsed operand
+    ///  - `Err(parse_error)`: when the next token can't start an operand, or a `(` is never closed
+    fn parse_primary(&mut self) -> Result<NodeId, Box<dyn std::error::Error>> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(self.push(Node::Number(number))),
+            Some(Token::Integer(literal)) => {
+                let integer = parse_integer(literal).map_err(|error| format!("Invalid whole number `{}`: {}", literal, error))?;
+                Ok(self.push(Node::Integer(integer)))
+            },
+            Some(Token::Imaginary(number)) => Ok(self.push(Node::Imaginary(number))),
+            Some(Token::Date(date)) => Ok(self.push(Node::Date(date))),
+            // a name followed by `(` is a function call, otherwise it names a value like `pi`
+            Some(Token::Identifier(name)) => {
+                let name = name.to_owned();
+                if self.peek() != Some(&Token::LeftParenthesis) {
+                    return Ok(self.push(Node::Identifier(name)));
+                }
+                self.next();
+
+                self.enter_nesting()?;
+                let arguments = self.parse_arguments()?;
+                self.nesting -= 1;
+                Ok(self.push(Node::Call { name, arguments }))
+            },
+            Some(Token::LeftParenthesis) => {
+                self.enter_nesting()?;
+                let expression = self.parse_expression()?;
+                self.nesting -= 1;
+
+                match self.next() {
+                    Some(Token::RightParenthesis) => Ok(expression),
+                    Some(token) => Err(format!("Expected `)` but found `{}`", token).into()),
+                    None => Err("Missing `)`".into()),
+                }
+            },
+            Some(Token::LeftBracket) => {
+                self.enter_nesting()?;
+                let matrix = self.parse_matrix()?;
+                self.nesting -= 1;
+                Ok(matrix)
+            },
+            Some(token) => Err(format!("Expected a number, name, `(`, or `[` but found `{}`", token).into()),
+            None => Err("Unexpected end of input, expected a number, name, `(`, or `[`".into()),
+        }
+    }
+
+    /// Parses the elements of a matrix like `[1, 2; 3, 4]`, after its `[`.
+    /// Elements in a row are separated by `,` and rows by `;`
+    /// # Returns
+    ///  - `Ok(id)`: the id of the matrix, once the closing `]` is consumed
+    ///  - `Err(parse_error)`: when an element is malformed, a row is a different length than the first, or the `]` is missing
+    fn parse_matrix(&mut self) -> Result<NodeId, Box<dyn std::error::Error>> {
+        let mut elements = Vec::new();
+        let mut columns = None;
+        let mut row_length = 0;
+
+        loop {
+            elements.push(self.parse_expression()?);
+            row_length += 1;
+
+            let token = self.next();
+            // every row has to be as long as the first
+            if matches!(token, Some(Token::Semicolon | Token::RightBracket)) {
+                match columns {
+                    None => columns = Some(row_length),
+                    Some(columns) if columns != row_length => {
+                        let row = (elements.len() - row_length) / columns + 1;
+                        return Err(format!("Every row of a matrix needs {} elements like the first, but row {} has {}", columns, row, row_length).into());
+                    },
+                    Some(_) => {},
+                }
+                row_length = 0;
+            }
+
+            match token {
+                Some(Token::Comma | Token::Semicolon) => continue,
+                Some(Token::RightBracket) => break,
+                Some(token) => return Err(format!("Expected `,`, `;`, or `]` but found `{}`", token).into()),
+                None => return Err("Missing `]`".into()),
+            }
+        }
+
+        Ok(self.push(Node::Matrix { columns: columns.unwrap_or(1), elements }))
+    }
+
+    /// Parses the comma separated arguments of a function call, after its `(`.
+    /// An argument can be an equation like `2x + 3 = 11`, which is how `solve` is given one
+    /// # Returns
+    ///  - `Ok(arguments)`: the arguments in order, once the closing `)` is consumed
+    ///  - `Err(parse_error)`: when an argument is malformed or the `)` is missing
+    fn parse_arguments(&mut self) -> Result<Vec<NodeId>, Box<dyn std::error::Error>> {
+        let mut arguments = Vec::new();
+        if self.peek() == Some(&Token::RightParenthesis) {
+            self.next();
+            return Ok(arguments);
+        }
+
+        loop {
+            let argument = self.parse_expression()?;
+            if self.peek() == Some(&Token::Equals) {
+                self.next();
+                let rhs = self.parse_expression()?;
+                arguments.push(self.push(Node::Equation { lhs: argument, rhs }));
+            } else {
+                arguments.push(argument);
+            }
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::RightParenthesis) => return Ok(arguments),
+                Some(token) => return Err(format!("Expected `,` or `)` but found `{}`", token).into()),
+                None => return Err("Missing `)`".into()),
+            }
+        }
+    }
+}
+
+/// Parses input that is only `number operator number`, like `2 + 3` or `1.5 <= 2`, without the lexer or the full parser.<br>
+/// This is most of what gets typed, so it is checked first with a cheap scan.
+/// The result is the same `Expression` that `tokenize` and `Parser::parse` would build, so it evaluates and displays the same way
+/// # Parameters
+///  - `input`: the text typed by the user
+/// # Returns
+///  - `Some(expression)`: when `input` is two plain decimal numbers joined by an operator
+///  - `None`: for anything else, which has to go through `tokenize` and `Parser`
+pub fn parse_simple(input: &str) -> Option<Expression> {
+    let is_digit = |character: char| character.is_ascii_digit() || character == '.';
+
+    let input = input.trim();
+    let (lhs, after_lhs) = input.split_at(input.find(|character| !is_digit(character))?);
+    let rest = after_lhs.trim_start();
+    let (operator, rhs) = rest.split_at(rest.find(|character: char| is_digit(character) || character.is_whitespace())?);
+    // a word operator touching a number is lexed as something else, like the hex prefix of `0xor 1` or the name `xor3`
+    if operator.starts_with(char::is_alphabetic) && !(after_lhs.starts_with(char::is_whitespace) && rhs.starts_with(char::is_whitespace)) {
+        return None;
+    }
+    let operation: Operation = operator.parse().ok()?;
+
+    // the parser pushes both operands before the chain joining them
+    let nodes = vec![
+        simple_number(lhs)?,
+        simple_number(rhs.trim_start())?,
+        Node::Chain { first: NodeId::new(0), rest: vec![(operation, NodeId::new(1))] },
+    ];
+    Some(Expression::new(nodes, NodeId::new(2)))
+}
+
+// the node the lexer and parser would make for `literal` when it is plain decimal digits with at most one `.`, like `12` or `2.5`
+fn simple_number(literal: &str) -> Option<Node> {
+    if !literal.bytes().all(|digit| digit.is_ascii_digit() || digit == b'.') {
+        return None;
+    }
+    match BigInt::parse(literal) {
+        Some(integer) => Some(Node::Integer(integer)),
+        None => literal.parse().ok().map(Node::Number),
+    }
+}
+
+// whether `name` is a builtin unit, or a currency that rates could be loaded for
+fn is_unit_name(name: &str) -> bool {
+    find_unit(name).is_some() || is_currency_code(name)
+}
+
+// put the `*` the user left out between a number, `)`, or `!` and a following `(`, name, or imaginary number like `2 i`.
+// a name followed by `(` is left alone because that is a function call,
+// and so is a unit right after a number, which the parser multiplies in before any `*` or `/`
+fn insert_implicit_multiplication(tokens: Vec<Token<'_>>) -> Vec<Token<'_>> {
+    let mut result = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let is_quantity = matches!(result.last(), Some(Token::Number(_) | Token::Integer(_)))
+            && matches!(&token, Token::Identifier(name) if is_unit_name(name));
+        let is_implied = matches!(result.last(), Some(Token::Number(_) | Token::Integer(_) | Token::Imaginary(_) | Token::RightParenthesis | Token::Exclamation))
+            && matches!(token, Token::LeftParenthesis | Token::Identifier(_) | Token::Imaginary(_))
+            && !is_quantity;
+        if is_implied {
+            result.push(Token::Operator(Operation::Multiply));
+        }
+        result.push(token);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::Environment;
+
+    use super::*;
+
+    // the value of `input` as a real number
+    fn evaluate(input: &str) -> f64 {
+        let expression: Expression = input.parse().unwrap();
+        expression.evaluate(&Environment::new()).unwrap().to_real().unwrap()
+    }
+
+    // `input` written back with only the parentheses its meaning needs
+    fn written(input: &str) -> String {
+        input.parse::<Expression>().unwrap().to_string()
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(evaluate("2 + 3 * (4 - 1) ^ 2"), 29.0);
+        assert_eq!(evaluate("2 * 3 + 4"), 10.0);
+        assert_eq!(evaluate("2 + 3 * 4"), 14.0);
+        assert_eq!(evaluate("1 << 2 + 1"), 8.0);
+        assert_eq!(evaluate("6 & 3 | 8"), 10.0);
+        assert_eq!(written("(2 + 3) * 4"), "(2 + 3) * 4");
+        assert_eq!(written("2 + (3 * 4)"), "2 + 3 * 4");
+    }
+
+    #[test]
+    fn associativity() {
+        assert_eq!(evaluate("10 - 4 - 3"), 3.0);
+        assert_eq!(evaluate("100 / 10 / 5"), 2.0);
+        assert_eq!(evaluate("2 ^ 3 ^ 2"), 512.0);
+        assert_eq!(evaluate("(2 ^ 3) ^ 2"), 64.0);
+        assert_eq!(written("10 - (4 - 3)"), "10 - (4 - 3)");
+        assert_eq!(written("2 ^ (3 ^ 2)"), "2 ^ 3 ^ 2");
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        // the limit is meant for the main thread's 8 MiB stack, which is more than a test thread gets
+        let checks = || {
+            let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+            assert_eq!(evaluate(&nested(MAX_NESTING - 1)), 1.0);
+            assert!(nested(MAX_NESTING + 1).parse::<Expression>().is_err());
+            assert!(format!("{}1", "-".repeat(100_000)).parse::<Expression>().is_err());
+            assert!(format!("{}1", "(".repeat(100_000)).parse::<Expression>().is_err());
+        };
+        std::thread::Builder::new().stack_size(8 << 20).spawn(checks).unwrap().join().unwrap();
+    }
+
+    #[test]
+    fn malformed_input_is_an_error() {
+        for input in ["", "1 +", "(1", "1)", "* 2", "1 2", "2 +* 3", "()", "sqrt(", "sqrt(1,", "[1, 2", "1 ? 2", "@"] {
+            assert!(input.parse::<Expression>().is_err(), "`{}` parsed", input);
+        }
+    }
+
+    #[test]
+    fn every_prefix_parses_without_panicking() {
+        let input = "-2 ^ -x! + sqrt(0x1.8p3, [1, 2; 3, 4]) // 7 % (3%) * 2(pi) < 1e-3 ? ~5 : not 1 in m";
+        for (end, _) in input.char_indices() {
+            let _ = input[..end].parse::<Expression>();
+        }
+    }
+
+    #[test]
+    fn long_chains_evaluate_left_to_right() {
+        assert_eq!(evaluate("1 + 2 + 3 + 4"), 10.0);
+        assert_eq!(evaluate("1 - 2 + 3 - 4 + 5"), 3.0);
+        assert_eq!(evaluate("2 * 3 / 4 * 8"), 12.0);
+        assert_eq!(evaluate("1 + 2 * 3 - 4 / 2 + 1"), 6.0);
+    }
+
+    #[test]
+    fn unary_minus_against_powers() {
+        assert_eq!(evaluate("-2 ^ 2"), -4.0);
+        assert_eq!(evaluate("(-2) ^ 2"), 4.0);
+        assert_eq!(evaluate("2 ^ -1"), 0.5);
+        assert_eq!(evaluate("-2 ^ -2"), -0.25);
+        assert_eq!(evaluate("2 * -4"), -8.0);
+        assert_eq!(evaluate("-5 + 3"), -2.0);
+        assert_eq!(evaluate("--3"), 3.0);
+        assert_eq!(evaluate("3 - -3"), 6.0);
+        assert_eq!(written("-2 ^ 2"), "-2 ^ 2");
+        assert_eq!(written("(-2) ^ 2"), "(-2) ^ 2");
+    }
+
+    #[test]
+    fn remainder_is_euclidean() {
+        assert_eq!(evaluate("17 % 5"), 2.0);
+        assert_eq!(evaluate("-7 % 3"), 2.0);
+        assert_eq!(evaluate("7 % (-3)"), 1.0);
+        assert_eq!(evaluate("-7 % (-3)"), 2.0);
+        assert_eq!(evaluate("7.5 % 2"), 1.5);
+        assert!("7 % 0".parse::<Expression>().unwrap().evaluate(&Environment::new()).is_err());
+    }
+
+    #[test]
+    fn integer_division_matches_remainder() {
+        assert_eq!(evaluate("7 // 2"), 3.0);
+        assert_eq!(evaluate("-7 // 2"), -4.0);
+        // it matches `%`, so `7 = -2 * -3 + 1`
+        assert_eq!(evaluate("7 // -2"), -3.0);
+        assert_eq!(evaluate("7.5 // 2"), 3.0);
+        assert_eq!(evaluate("1 + 7 // 2 * 2"), 7.0);
+        assert_eq!(written("7//2"), "7 // 2");
+    }
+
+    #[test]
+    fn implicit_multiplication() {
+        assert_eq!(evaluate("2(3 + 4)"), 14.0);
+        assert_eq!(evaluate("(1 + 2)(3 + 4)"), 21.0);
+        assert_eq!(evaluate("2pi"), 2.0 * std::f64::consts::PI);
+        assert_eq!(evaluate("3!(2)"), 12.0);
+        assert_eq!(written("2(3 + 4)"), "2 * (3 + 4)");
+        assert_eq!(written("2pi ^ 2"), "2 * pi ^ 2");
+        // a name followed by `(` is a call rather than a product
+        assert_eq!(evaluate("sqrt(16)"), 4.0);
+    }
+
+    #[test]
+    fn percent_against_remainder() {
+        assert_eq!(evaluate("200 * 15%"), 30.0);
+        assert_eq!(evaluate("100 + 10%"), 110.0);
+        assert_eq!(evaluate("100 - 10%"), 90.0);
+        assert_eq!(evaluate("50%"), 0.5);
+        // a `%` followed by an operand is a remainder
+        assert_eq!(evaluate("7 % 3"), 1.0);
+        assert_eq!(evaluate("7 % (3)"), 1.0);
+        assert_eq!(written("7% - 3"), "7% - 3");
+    }
+}
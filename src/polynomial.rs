@@ -0,0 +1,193 @@
+use crate::{
+    complex::Complex,
+    lexer::scale_by_power_of_two,
+};
+
+/// How many rounds of Durand–Kerner are run before giving up on the roots converging
+const MAX_ITERATIONS: usize = 1000;
+
+/// The coefficients of `lhs + sign * rhs`, where each polynomial is its coefficients starting with the constant term
+pub fn add_polynomials(lhs: &[f64], rhs: &[f64], sign: f64) -> Vec<f64> {
+    (0..lhs.len().max(rhs.len()))
+        .map(|power| lhs.get(power).copied().unwrap_or(0.0) + sign * rhs.get(power).copied().unwrap_or(0.0))
+        .collect()
+}
+
+/// The coefficients of `lhs * rhs`, where each polynomial is its coefficients starting with the constant term
+pub fn multiply_polynomials(lhs: &[f64], rhs: &[f64]) -> Vec<f64> {
+    let mut product = vec![0.0; lhs.len() + rhs.len() - 1];
+    for (i, lhs) in lhs.iter().enumerate() {
+        for (j, rhs) in rhs.iter().enumerate() {
+            product[i + j] += lhs * rhs;
+        }
+    }
+    product
+}
+
+/// Finds every root of a polynomial, real or complex, with the Durand–Kerner method,
+/// which moves a guess for each root towards it at the same time.<br>
+/// Roots of `0` are split off first, and the variable is scaled so the rest lie inside the unit circle,
+/// which keeps the powers of the guesses from overflowing however big the coefficients are.
+/// A root near a whole number where the polynomial is exactly `0` is made that whole number, so the roots of `x ^ 2 - 4` are exactly `-2` and `2`,
+/// and a part of a root that is only rounding error is dropped, so a root is real when its imaginary part is tiny next to it.
+/// A root repeated `m` times can only be found to about `16 / m` digits, so its copies may come out slightly apart
+/// # Parameters
+///  - `coefficients`: the coefficients of the polynomial starting with the constant term, so `x ^ 2 - 4` is `[-4, 0, 1]`
+/// # Returns
+///  - `Ok(roots)`: a root for each power of the highest power, repeated for a repeated root, sorted by real then imaginary part
+///  - `Err(roots_error)`: when the polynomial is a constant, which has no roots to find, has a coefficient that isn't finite,
+///    has coefficients too far apart in size to scale, or the guesses don't settle on the roots
+pub fn roots(coefficients: &[f64]) -> Result<Vec<Complex>, Box<dyn std::error::Error>> {
+    if coefficients.iter().any(|coefficient| !coefficient.is_finite()) {
+        return Err("Every coefficient of the polynomial has to be a finite number".into());
+    }
+    let degree = coefficients.iter().rposition(|coefficient| *coefficient != 0.0).unwrap_or(0);
+    if degree == 0 {
+        return Err("A constant has no roots to find".into());
+    }
+
+    // every missing low power is a root of `0`, like the `x` that `x ^ 3 - x` is a multiple of
+    let zeros = coefficients.iter().position(|coefficient| *coefficient != 0.0).unwrap_or(0);
+    let mut roots = vec![Complex::from(0.0); zeros];
+    let reduced = &coefficients[zeros..=degree];
+    let degree = degree - zeros;
+    if degree > 0 {
+        roots.extend(scaled_roots(reduced)?);
+    }
+
+    let evaluate_real = |x: f64| coefficients.iter().rev().fold(0.0, |sum, coefficient| sum * x + coefficient);
+    let mut roots: Vec<Complex> = roots
+        .into_iter()
+        .map(|root| {
+            let rounded = root.re.round();
+            if root.sub(Complex::from(rounded)).abs() <= 1e-4 * rounded.abs().max(1.0) && evaluate_real(rounded) == 0.0 {
+                return Complex::from(rounded + 0.0);
+            }
+            // a double root is only found to about 8 digits, so an imaginary part smaller than that is taken as rounding error
+            let size = root.abs();
+            let re = if root.re.abs() <= 1e-12 * size { 0.0 } else { root.re };
+            let im = if root.im.abs() <= 1e-7 * size { 0.0 } else { root.im };
+            Complex::new(re, im)
+        })
+        .collect();
+    roots.sort_by(|lhs, rhs| lhs.re.total_cmp(&rhs.re).then(lhs.im.total_cmp(&rhs.im)));
+    Ok(roots)
+}
+
+// the roots of the polynomial with `coefficients`, whose constant term isn't `0`, by Durand–Kerner.
+// `x` is replaced by `2 ^ scale * y`, where `2 ^ scale` is at least the Fujiwara bound on the size of the roots,
+// so every root in `y` is inside the unit circle. scaling by a power of 2 never rounds
+fn scaled_roots(coefficients: &[f64]) -> Result<Vec<Complex>, Box<dyn std::error::Error>> {
+    let degree = coefficients.len() - 1;
+    let leading = coefficients[degree];
+
+    // the bound is `2 * max |a(n - k) / a(n)| ^ (1 / k)`, worked out with logarithms so dividing a huge coefficient by a tiny one can't overflow
+    let scale = (1..=degree)
+        .map(|k| 1.0 + (coefficients[degree - k].abs().log2() - leading.abs().log2()) / k as f64)
+        .fold(f64::NEG_INFINITY, f64::max)
+        .ceil() as i64;
+    // each coefficient becomes `a(k) * 2 ^ (scale * (k - n)) / a(n)`, with `a(n)` brought near 1 first so the division can't overflow either
+    let shift = leading.abs().log2().floor() as i64;
+    let divisor = scale_by_power_of_two(leading, -shift);
+    let monic: Vec<Complex> = coefficients
+        .iter()
+        .enumerate()
+        .map(|(power, coefficient)| Complex::from(scale_by_power_of_two(*coefficient, scale * (power as i64 - degree as i64) - shift) / divisor))
+        .collect();
+    // a coefficient too small for the scaled polynomial to hold would lose the roots it decides
+    if coefficients.iter().zip(&monic).any(|(coefficient, scaled)| *coefficient != 0.0 && !scaled.re.is_normal()) {
+        return Err("The coefficients of the polynomial are too far apart in size to find its roots".into());
+    }
+
+    let evaluate = |z: Complex| monic.iter().rev().fold(Complex::from(0.0), |sum, coefficient| sum.mul(z).add(*coefficient));
+    // the most rounding error evaluating the polynomial at `z` can have, so a smaller value can't be told apart from `0`
+    let noise = |z: Complex| {
+        let size = z.abs();
+        4.0 * f64::EPSILON * degree as f64 * monic.iter().rev().fold(0.0, |sum, coefficient| sum * size + coefficient.abs())
+    };
+
+    // the first guesses are spread around the unit circle, turned off the real axis so no guess starts on a line of symmetry
+    let mut guesses: Vec<Complex> = (0..degree)
+        .map(|k| {
+            let angle = std::f64::consts::TAU * k as f64 / degree as f64 + 0.4;
+            Complex::new(angle.cos(), angle.sin())
+        })
+        .collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut converged = true;
+        for k in 0..degree {
+            let denominator = (0..degree)
+                .filter(|j| *j != k)
+                .fold(Complex::from(1.0), |product, j| product.mul(guesses[k].sub(guesses[j])));
+            // two guesses that landed on each other are nudged apart instead of dividing by zero
+            let value = evaluate(guesses[k]);
+            let step = value.div(denominator).unwrap_or(Complex::new(1e-8, 1e-8));
+            // a guess has settled once the polynomial there is within rounding error of `0`, or it barely moves
+            converged &= value.abs() <= noise(guesses[k]) || step.abs() <= f64::EPSILON * guesses[k].abs();
+            guesses[k] = guesses[k].sub(step);
+            if !guesses[k].re.is_finite() || !guesses[k].im.is_finite() {
+                return Err("The roots of the polynomial couldn't be found, the guesses for them stopped being finite numbers".into());
+            }
+        }
+        if converged {
+            let roots: Vec<Complex> = guesses
+                .into_iter()
+                .map(|root| Complex::new(scale_by_power_of_two(root.re, scale), scale_by_power_of_two(root.im, scale)))
+                .collect();
+            if roots.iter().any(|root| !root.re.is_finite() || !root.im.is_finite()) {
+                return Err("The roots of the polynomial are too big for floating point numbers".into());
+            }
+            return Ok(roots);
+        }
+    }
+    Err(format!("The roots of the polynomial couldn't be found, the guesses for them didn't settle after {} rounds", MAX_ITERATIONS).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the roots of the polynomial with `coefficients`, as `(re, im)` pairs
+    fn parts(coefficients: &[f64]) -> Vec<(f64, f64)> {
+        roots(coefficients).unwrap().iter().map(|root| (root.re, root.im)).collect()
+    }
+
+    #[test]
+    fn whole_roots_are_exact() {
+        assert_eq!(parts(&[-4.0, 0.0, 1.0]), vec![(-2.0, 0.0), (2.0, 0.0)]);
+        assert_eq!(parts(&[0.0, -1.0, 0.0, 1.0]), vec![(-1.0, 0.0), (0.0, 0.0), (1.0, 0.0)]);
+        assert_eq!(parts(&[-6.0, 11.0, -6.0, 1.0]), vec![(1.0, 0.0), (2.0, 0.0), (3.0, 0.0)]);
+    }
+
+    #[test]
+    fn complex_roots_come_in_conjugate_pairs() {
+        assert_eq!(parts(&[1.0, 0.0, 1.0]), vec![(0.0, -1.0), (0.0, 1.0)]);
+        // x ^ 2 - 2x + 5 has the roots 1 ± 2i
+        let roots = parts(&[5.0, -2.0, 1.0]);
+        assert_eq!(roots.len(), 2);
+        for ((re, im), expected) in roots.into_iter().zip([-2.0, 2.0]) {
+            assert!((re - 1.0).abs() < 1e-12 && (im - expected).abs() < 1e-12, "{} {}", re, im);
+        }
+    }
+
+    #[test]
+    fn roots_of_widely_scaled_coefficients() {
+        // (x - 1e-8)(x - 1e8)
+        let roots = parts(&[1.0, -(1e8 + 1e-8), 1.0]);
+        assert!((roots[0].0 - 1e-8).abs() < 1e-20 && (roots[1].0 - 1e8).abs() < 1e-4, "{:?}", roots);
+    }
+
+    #[test]
+    fn polynomials_without_roots_to_find_are_errors() {
+        assert!(roots(&[5.0]).is_err());
+        assert!(roots(&[0.0, 0.0]).is_err());
+        assert!(roots(&[1.0, f64::NAN]).is_err());
+    }
+
+    #[test]
+    fn polynomial_arithmetic() {
+        assert_eq!(add_polynomials(&[1.0, 2.0], &[3.0], -1.0), vec![-2.0, 2.0]);
+        assert_eq!(multiply_polynomials(&[1.0, 1.0], &[-1.0, 1.0]), vec![-1.0, 0.0, 1.0]);
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    time::{Duration, Instant},
+};
+
+/// A progress line on stderr for long batch jobs, showing how many lines were processed and how fast.<br>
+/// When the size of the input is known, like for `calc < data.csv`, a bar and percentage are shown too.
+/// Nothing is drawn unless stderr is a terminal, so redirected logs stay clean,
+/// or when stdout is one too, since the results scrolling by would break up the line
+pub struct Progress {
+    /// whether `--progress` was given and the terminals allow it, and so whether anything is drawn
+    enabled: bool,
+    /// the size of the input in bytes, when it is a regular file
+    total_bytes: Option<u64>,
+    /// how many bytes have been processed so far
+    bytes: u64,
+    /// how many lines have been processed so far
+    lines: u64,
+    /// when processing started, for the rate
+    start: Instant,
+    /// when the line was last drawn, so it isn't redrawn for every input line
+    last_draw: Option<Instant>,
+}
+impl Progress {
+    /// Creates a progress line for processing stdin
+    /// # Parameters
+    ///  - `requested`: whether `--progress` was given. When it wasn't, nothing is ever drawn
+    pub fn for_stdin(requested: bool) -> Self {
+        // a redirected file has a size, but a pipe doesn't
+        let total_bytes = std::fs::metadata("/dev/stdin")
+            .ok()
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len());
+
+        Progress {
+            enabled: requested && io::stderr().is_terminal() && !io::stdout().is_terminal(),
+            total_bytes,
+            bytes: 0,
+            lines: 0,
+            start: Instant::now(),
+            last_draw: None,
+        }
+    }
+
+    /// Records that a line of `bytes` bytes was processed, redrawing the progress line at most 10 times a second
+    pub fn advance(&mut self, bytes: usize) {
+        const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+        self.lines += 1;
+        self.bytes += bytes as u64;
+        if self.enabled && self.last_draw.is_none_or(|last_draw| last_draw.elapsed() >= REDRAW_INTERVAL) {
+            self.draw();
+        }
+    }
+
+    /// Erases the progress line so other messages on stderr start on a clean line. It is drawn again on the next `advance`
+    pub fn clear(&mut self) {
+        if self.enabled && self.last_draw.is_some() {
+            eprint!("\r\x1b[K");
+            self.last_draw = None;
+        }
+    }
+
+    // write the progress line over the previous one
+    fn draw(&mut self) {
+        const BAR_WIDTH: usize = 30;
+
+        let seconds = self.start.elapsed().as_secs_f64();
+        let rate = if seconds > 0.0 { self.lines as f64 / seconds } else { 0.0 };
+        let counts = format!("{} lines  {:.0} lines/s", self.lines, rate);
+
+        let line = match self.total_bytes {
+            Some(total_bytes) if total_bytes > 0 => {
+                let fraction = (self.bytes as f64 / total_bytes as f64).min(1.0);
+                let filled = (fraction * BAR_WIDTH as f64) as usize;
+                format!("[{}{}] {:3.0}%  {}", "#".repeat(filled), " ".repeat(BAR_WIDTH - filled), fraction * 100.0, counts)
+            },
+            _ => counts,
+        };
+
+        let mut stderr = io::stderr().lock();
+        // progress is only a convenience, so failing to draw it isn't worth stopping the job for
+        let _ = write!(stderr, "\r\x1b[K{}", line).and_then(|_| stderr.flush());
+        self.last_draw = Some(Instant::now());
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+};
+
+/// An exact fraction `numerator / denominator`, always kept in lowest terms with a positive denominator.<br>
+/// Arithmetic that would overflow an `i128` returns `None`, so the caller can fall back to floating point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+impl Rational {
+    /// Creates the fraction `numerator / denominator` in lowest terms
+    /// # Returns
+    ///  - `Some(fraction)`: the reduced fraction
+    ///  - `None`: when `denominator` is zero, or the fraction can't be reduced without overflowing
+    pub fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator, denominator);
+        let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+        if denominator < 0 {
+            Some(Rational { numerator: numerator.checked_neg()?, denominator: denominator.checked_neg()? })
+        } else {
+            Some(Rational { numerator, denominator })
+        }
+    }
+
+    /// Creates the fraction with the same decimal digits as `number`, so `0.1` is exactly `1/10` rather than the
+    /// binary fraction an `f64` rounds it to. This relies on `f64`'s `Display` writing the shortest decimal that parses back to `number`
+    /// # Returns
+    ///  - `Some(fraction)`: the decimal value of `number`
+    ///  - `None`: when `number` isn't finite, or has too many digits for an `i128`
+    pub fn from_decimal(number: f64) -> Option<Self> {
+        if !number.is_finite() {
+            return None;
+        }
+
+        let text = number.to_string();
+        let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+        let digits: i128 = format!("{}{}", whole, fraction).parse().ok()?;
+        let denominator = 10i128.checked_pow(fraction.len() as u32)?;
+        Rational::new(digits, denominator)
+    }
+
+    /// The fraction equal to the whole number `integer`
+    pub fn integer(integer: i128) -> Self {
+        Rational { numerator: integer, denominator: 1 }
+    }
+
+    /// The top of the fraction, which carries its sign
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    /// The bottom of the fraction, which is always positive
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    /// Whether the fraction is a whole number
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The nearest `f64` to the fraction
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// `-self`
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Rational { numerator: self.numerator.checked_neg()?, denominator: self.denominator })
+    }
+
+    /// `self + rhs`
+    pub fn checked_add(&self, rhs: Rational) -> Option<Self> {
+        let numerator = self
+            .numerator
+            .checked_mul(rhs.denominator)?
+            .checked_add(rhs.numerator.checked_mul(self.denominator)?)?;
+        Rational::new(numerator, self.denominator.checked_mul(rhs.denominator)?)
+    }
+
+    /// `self - rhs`
+    pub fn checked_sub(&self, rhs: Rational) -> Option<Self> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    /// `self * rhs`
+    pub fn checked_mul(&self, rhs: Rational) -> Option<Self> {
+        // cancelling across the fractions first keeps the products small
+        let left = gcd(self.numerator, rhs.denominator);
+        let right = gcd(rhs.numerator, self.denominator);
+        Rational::new(
+            (self.numerator / left).checked_mul(rhs.numerator / right)?,
+            (self.denominator / right).checked_mul(rhs.denominator / left)?,
+        )
+    }
+
+    /// `self / rhs`, which is `None` when `rhs` is zero
+    pub fn checked_div(&self, rhs: Rational) -> Option<Self> {
+        self.checked_mul(Rational::new(rhs.denominator, rhs.numerator)?)
+    }
+
+    /// `self ^ exponent` for a whole `exponent`, which is `None` for `0` to a negative power
+    pub fn checked_pow(&self, exponent: i128) -> Option<Self> {
+        let power = u32::try_from(exponent.unsigned_abs()).ok()?;
+        let result = Rational {
+            numerator: self.numerator.checked_pow(power)?,
+            denominator: self.denominator.checked_pow(power)?,
+        };
+        if exponent < 0 {
+            Rational::new(result.denominator, result.numerator)
+        } else {
+            Some(result)
+        }
+    }
+
+    /// The Euclidean quotient of `self / rhs`, matching `f64::div_euclid`, which is `None` when `rhs` is zero
+    pub fn checked_div_euclid(&self, rhs: Rational) -> Option<Self> {
+        // `self / rhs` is `(a * d) / (b * c)` for `self = a / b` and `rhs = c / d`, and both share the quotient
+        let dividend = self.numerator.checked_mul(rhs.denominator)?;
+        let divisor = self.denominator.checked_mul(rhs.numerator)?;
+        if divisor == 0 {
+            return None;
+        }
+        Some(Rational::integer(dividend.div_euclid(divisor)))
+    }
+
+    /// The Euclidean remainder of `self / rhs`, matching `f64::rem_euclid`, which is `None` when `rhs` is zero
+    pub fn checked_rem_euclid(&self, rhs: Rational) -> Option<Self> {
+        self.checked_sub(rhs.checked_mul(self.checked_div_euclid(rhs)?)?)
+    }
+
+    /// Compares two fractions exactly, which is `None` if cross multiplying them overflows
+    pub fn checked_cmp(&self, rhs: Rational) -> Option<Ordering> {
+        let lhs = self.numerator.checked_mul(rhs.denominator)?;
+        let rhs = rhs.numerator.checked_mul(self.denominator)?;
+        Some(lhs.cmp(&rhs))
+    }
+}
+impl Display for Rational { // allows for `println!()` and `.to_string()`
+
+    /// writes the fraction like `1/2` or `-3/4`, or just the numerator for a whole number
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the fraction to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+// the greatest common divisor of `a` and `b`, which is never 0 so it is always safe to divide by
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    // `i128::MIN` has no positive counterpart, so it can only be divided by a smaller power of 2
+    i128::try_from(a).unwrap_or(1 << 126).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_reduces_and_keeps_the_denominator_positive() {
+        let half = Rational::new(-2, -4).unwrap();
+        assert_eq!((half.numerator(), half.denominator()), (1, 2));
+        let negative = Rational::new(3, -6).unwrap();
+        assert_eq!((negative.numerator(), negative.denominator()), (-1, 2));
+        assert_eq!(Rational::new(0, -5), Some(Rational::integer(0)));
+        assert_eq!(Rational::new(1, 0), None);
+        assert_eq!(Rational::new(1, i128::MIN), None);
+    }
+
+    #[test]
+    fn from_decimal_keeps_the_written_digits() {
+        assert_eq!(Rational::from_decimal(0.1), Rational::new(1, 10));
+        assert_eq!(Rational::from_decimal(-2.5), Rational::new(-5, 2));
+        assert_eq!(Rational::from_decimal(f64::NAN), None);
+        assert_eq!(Rational::from_decimal(f64::INFINITY), None);
+    }
+
+    #[test]
+    fn arithmetic_stays_exact() {
+        let (third, sixth) = (Rational::new(1, 3).unwrap(), Rational::new(1, 6).unwrap());
+        assert_eq!(third.checked_add(sixth), Rational::new(1, 2));
+        assert_eq!(third.checked_sub(sixth), Some(sixth));
+        assert_eq!(third.checked_mul(sixth), Rational::new(1, 18));
+        assert_eq!(third.checked_div(sixth), Some(Rational::integer(2)));
+        assert_eq!(third.checked_div(Rational::integer(0)), None);
+        assert_eq!(third.checked_pow(-2), Some(Rational::integer(9)));
+        assert_eq!(Rational::integer(0).checked_pow(-1), None);
+    }
+
+    #[test]
+    fn overflow_is_none() {
+        assert_eq!(Rational::integer(i128::MAX).checked_add(Rational::integer(1)), None);
+        assert_eq!(Rational::integer(i128::MIN).checked_neg(), None);
+        assert_eq!(Rational::integer(2).checked_pow(127), None);
+    }
+
+    #[test]
+    fn euclidean_division_matches_f64_for_every_sign() {
+        for (lhs, rhs) in [(7.5, 2.0), (-7.5, 2.0), (7.5, -2.0), (-7.5, -2.0), (1.25, 0.5), (-1.25, 0.5)] {
+            let (lhs_fraction, rhs_fraction) = (Rational::from_decimal(lhs).unwrap(), Rational::from_decimal(rhs).unwrap());
+            assert_eq!(lhs_fraction.checked_div_euclid(rhs_fraction).unwrap().to_f64(), lhs.div_euclid(rhs), "{} div_euclid {}", lhs, rhs);
+            assert_eq!(lhs_fraction.checked_rem_euclid(rhs_fraction).unwrap().to_f64(), lhs.rem_euclid(rhs), "{} rem_euclid {}", lhs, rhs);
+        }
+        assert_eq!(Rational::integer(1).checked_div_euclid(Rational::integer(0)), None);
+    }
+
+    #[test]
+    fn display_omits_a_denominator_of_one() {
+        assert_eq!(Rational::new(-3, 4).unwrap().to_string(), "-3/4");
+        assert_eq!(Rational::new(4, 2).unwrap().to_string(), "2");
+    }
+}
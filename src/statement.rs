@@ -0,0 +1,46 @@
+use std::str::FromStr;
+
+use crate::{
+    expression::Expression,
+    lexer::tokenize,
+    parser::{parse_simple, Parser},
+};
+
+/// One line of input: either an expression to evaluate, or an assignment to a variable
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    /// `name = expression`
+    Assignment {
+        name: String,
+        expression: Expression,
+    },
+    /// an expression whose value is shown
+    Expression(Expression),
+}
+impl Statement {
+    /// The expression that has to be evaluated to run this statement
+    pub fn expression(&self) -> &Expression {
+        match self {
+            Statement::Assignment { expression, .. } | Statement::Expression(expression) => expression,
+        }
+    }
+}
+impl FromStr for Statement { // Trait that allows .parse to work
+
+    type Err = Box<dyn std::error::Error>; // parse error type
+
+    /// Parse a `Statement` from `s`.<br>
+    /// `s` is an assignment when it starts with a name followed by `=`, and an expression otherwise
+    /// # Parameters
+    ///  - `s`: The string slice to be parsed
+    /// # Returns
+    ///  - `Ok(statement)`: When `s` is a well formed assignment or expression
+    ///  - `Err(from_str_error)`: When `s` contains unknown characters or isn't well formed
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(expression) = parse_simple(s) {
+            return Ok(Statement::Expression(expression));
+        }
+        let tokens = tokenize(s)?;
+        Parser::new(tokens).parse_statement()
+    }
+}
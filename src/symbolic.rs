@@ -0,0 +1,951 @@
+use crate::{
+    bigint::BigInt,
+    complex::Complex,
+    environment::Environment,
+    expression::{Expression, Node, NodeId},
+    mode::Mode,
+    operation::Operation,
+    polynomial::{add_polynomials, multiply_polynomials, roots},
+    rational::Rational,
+    value::Value,
+};
+
+/// The builtin functions that work on an expression as written, rather than on its value, so its variables don't need values
+pub const SYMBOLIC_FUNCTIONS: &[&str] = &["diff", "simplify", "solve", "roots"];
+
+/// The highest power of the variable `solve` and `roots` expand a polynomial to, which keeps `(x + 1) ^ 1000000` from using up memory
+const MAX_DEGREE: usize = 64;
+
+/// Calls one of the `SYMBOLIC_FUNCTIONS` on the unevaluated `arguments` of a call in `expression`
+/// # Parameters
+///  - `expression`: the expression holding the call
+///  - `name`: the function, like `diff`
+///  - `arguments`: the arguments of the call, as written
+///  - `environment`: the variables the names in a `solve` equation other than the one solved for refer to
+///  - `exact`: cleared when a solution was rounded
+/// # Returns
+///  - `Ok(value)`: the result, like the derivative `2 * x + 3` of `diff(x ^ 2 + 3x, x)`, `5 * x` from `simplify(2x + 3x * 1)`,
+///    `[4]` from `solve(2x + 3 = 11, x)`, or `[-1, 0, 1]` from `roots(x ^ 3 - x)`
+///  - `Err(symbolic_error)`: when the arguments are the wrong shape, or the function can't be worked out for them
+pub fn call(expression: &Expression, name: &str, arguments: &[NodeId], environment: &Environment, exact: &mut bool) -> Result<Value, Box<dyn std::error::Error>> {
+    // the name of the variable given as the second argument of `diff` or `solve`
+    let variable = |id: NodeId| match expression.get(id) {
+        Node::Identifier(variable) => Ok(variable),
+        _ => Err(format!("{} needs the name of a variable like x as its second argument, but was given `{}`", name, expression.node(id))),
+    };
+
+    match name {
+        "diff" => {
+            let [function, variable_id] = arguments else {
+                return Err(format!("diff takes 2 arguments but was given {}", arguments.len()).into());
+            };
+            let variable = variable(*variable_id)?;
+            let mut builder = Builder::new(expression);
+            let (_, derivative) = builder.differentiate(*function, variable)?;
+            Ok(Value::Expression(Expression::new(builder.nodes, derivative)))
+        },
+        "simplify" => {
+            let [operand] = arguments else {
+                return Err(format!("simplify takes 1 argument but was given {}", arguments.len()).into());
+            };
+            let mut builder = Builder::new(expression);
+            let simplified = builder.rebuild(*operand)?;
+            Ok(Value::Expression(Expression::new(builder.nodes, simplified)))
+        },
+        "solve" => {
+            let [equation, variable_id] = arguments else {
+                return Err(format!("solve takes 2 arguments but was given {}", arguments.len()).into());
+            };
+            let variable = variable(*variable_id)?;
+            // an expression on its own is solved for where it is `0`
+            let mut builder = Builder::new(expression);
+            let difference = match expression.get(*equation) {
+                Node::Equation { lhs, rhs } => {
+                    let (lhs, rhs) = (builder.rebuild(*lhs)?, builder.rebuild(*rhs)?);
+                    builder.subtract(lhs, rhs)
+                },
+                _ => builder.rebuild(*equation)?,
+            };
+            let coefficients = builder.polynomial(name, difference, variable, environment, exact)?;
+            solve_polynomial(&coefficients, variable, exact)
+        },
+        // the polynomial is in `x` unless another variable is given
+        "roots" => {
+            let (polynomial, variable) = match arguments {
+                [polynomial] => (*polynomial, "x"),
+                [polynomial, variable_id] => (*polynomial, variable(*variable_id)?.as_str()),
+                _ => return Err(format!("roots takes 1 to 2 arguments but was given {}", arguments.len()).into()),
+            };
+            let mut builder = Builder::new(expression);
+            let polynomial = builder.rebuild(polynomial)?;
+            let coefficients = if builder.contains(polynomial, variable) {
+                builder.polynomial(name, polynomial, variable, environment, exact)?
+            } else {
+                // without the variable it is a list of coefficients, starting with the highest power like `[1, 0, -4]` for `x ^ 2 - 4`
+                match Expression::new(builder.nodes.clone(), polynomial).evaluate(environment)? {
+                    Value::Matrix(matrix) if matrix.rows() == 1 || matrix.columns() == 1 => matrix.elements().iter().rev().copied().collect(),
+                    value => return Err(format!("roots needs a polynomial in {} like x ^ 3 - x, or its coefficients like [1, 0, -4], but was given {}", variable, value).into()),
+                }
+            };
+            let roots = roots(&coefficients)?;
+
+            // whole real roots where the polynomial is exactly `0` are exact, and every other root was rounded
+            let is_exact = roots.iter().all(|root| {
+                root.im == 0.0 && root.re.fract() == 0.0 && coefficients.iter().rev().fold(0.0, |sum, coefficient| sum * root.re + coefficient) == 0.0
+            });
+            if !is_exact {
+                *exact = false;
+            }
+            // the roots are always a list, whether or not any are complex, so what can be done with them doesn't depend on the polynomial
+            Ok(Value::List(roots.into_iter().map(Value::from).collect()))
+        },
+        _ => Err(format!("Unknown function `{}`", name).into()),
+    }
+}
+
+// builds a new expression out of the nodes of `source`, folding away the terms that don't change its value,
+// like `x * 1`, `x + 0`, and `x ^ 1`, working out operations on numbers and parts without names when the result is exact,
+// adding the exponents of powers of the same base, and collecting like terms, so `2 * x + 3 * x` becomes `5 * x`
+struct Builder<'a> {
+    source: &'a Expression,
+    nodes: Vec<Node>,
+}
+impl<'a> Builder<'a> {
+    fn new(source: &'a Expression) -> Self {
+        Builder { source, nodes: Vec::new() }
+    }
+
+    fn get(&self, id: NodeId) -> &Node {
+        &self.nodes[id.index()]
+    }
+
+    fn push(&mut self, node: Node) -> NodeId {
+        self.nodes.push(node);
+        NodeId::new(self.nodes.len() - 1)
+    }
+
+    // a negative number is pushed as the negation of its size, so `(-2) ^ x` keeps its parentheses when written,
+    // and zero is never written `-0`
+    fn number(&mut self, number: f64) -> NodeId {
+        if number == 0.0 {
+            self.push(Node::Number(0.0))
+        } else if number < 0.0 {
+            let size = self.push(Node::Number(-number));
+            self.push(Node::Negate(size))
+        } else {
+            self.push(Node::Number(number))
+        }
+    }
+
+    // the value of the node `id` when it is a plain number
+    fn as_number(&self, id: NodeId) -> Option<f64> {
+        match self.get(id) {
+            Node::Number(number) => Some(*number),
+            Node::Integer(integer) => Some(integer.to_f64()),
+            Node::Negate(operand) => self.as_number(*operand).map(|number| -number),
+            _ => None,
+        }
+    }
+
+    fn is_number(&self, id: NodeId, number: f64) -> bool {
+        self.as_number(id) == Some(number)
+    }
+
+    // whether the node `id` is sure to be a finite number, taking names to stand for finite numbers,
+    // so `0 * (1 / 0)` isn't folded to `0` when its value is NaN
+    fn is_finite(&self, id: NodeId) -> bool {
+        match self.get(id) {
+            Node::Number(number) => number.is_finite(),
+            Node::Integer(_) | Node::Imaginary(_) | Node::Identifier(_) => true,
+            Node::Negate(operand) => self.is_finite(*operand),
+            Node::Chain { first, rest } => {
+                self.is_finite(*first)
+                    && rest.iter().all(|(operation, term)| match operation {
+                        Operation::Add | Operation::Subtract | Operation::Multiply => self.is_finite(*term),
+                        // only a whole power that isn't negative can't divide by zero
+                        Operation::Exponential => self.as_number(*term).is_some_and(|exponent| exponent >= 0.0 && exponent.fract() == 0.0),
+                        _ => false,
+                    })
+            },
+            _ => false,
+        }
+    }
+
+    // `lhs operation rhs` worked out, when both are numbers and the result is exact
+    fn fold(&mut self, operation: Operation, lhs: NodeId, rhs: NodeId) -> Option<NodeId> {
+        let (lhs, rhs) = (self.as_number(lhs)?, self.as_number(rhs)?);
+        let result = operation.apply(lhs, rhs).ok()?;
+        operation.is_exact(lhs, rhs, result).then(|| self.number(result))
+    }
+
+    // `lhs operation rhs`, joining the chain `lhs` is already part of when it has the same precedence and groups from the left
+    fn chain(&mut self, operation: Operation, lhs: NodeId, rhs: NodeId) -> NodeId {
+        if let Node::Chain { first, rest } = &self.get(lhs) {
+            let joins = rest.first().is_some_and(|(other, _)| other.precedence() == operation.precedence()) && !operation.is_right_associative();
+            if joins {
+                let (first, mut rest) = (*first, rest.clone());
+                rest.push((operation, rhs));
+                return self.push(Node::Chain { first, rest });
+            }
+        }
+        self.push(Node::Chain { first: lhs, rest: vec![(operation, rhs)] })
+    }
+
+    fn add(&mut self, lhs: NodeId, rhs: NodeId) -> NodeId {
+        let mut terms = self.terms(lhs);
+        terms.extend(self.terms(rhs));
+        self.sum(terms)
+    }
+
+    fn subtract(&mut self, lhs: NodeId, rhs: NodeId) -> NodeId {
+        let mut terms = self.terms(lhs);
+        terms.extend(self.terms(rhs).into_iter().map(|(coefficient, term)| (-coefficient, term)));
+        self.sum(terms)
+    }
+
+    // whether the nodes `lhs` and `rhs` are written the same, so `2 * x` and `x` are terms of the same kind
+    fn same(&self, lhs: NodeId, rhs: NodeId) -> bool {
+        match (self.get(lhs), self.get(rhs)) {
+            (Node::Number(lhs), Node::Number(rhs)) => lhs == rhs,
+            (Node::Integer(lhs), Node::Integer(rhs)) => lhs == rhs,
+            (Node::Imaginary(lhs), Node::Imaginary(rhs)) => lhs == rhs,
+            (Node::Identifier(lhs), Node::Identifier(rhs)) => lhs == rhs,
+            (Node::Negate(lhs), Node::Negate(rhs)) => self.same(*lhs, *rhs),
+            (Node::Call { name: lhs_name, arguments: lhs }, Node::Call { name: rhs_name, arguments: rhs }) => {
+                lhs_name == rhs_name && lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| self.same(*lhs, *rhs))
+            },
+            (Node::Chain { first: lhs_first, rest: lhs }, Node::Chain { first: rhs_first, rest: rhs }) => {
+                self.same(*lhs_first, *rhs_first)
+                    && lhs.len() == rhs.len()
+                    && lhs.iter().zip(rhs).all(|((lhs_operation, lhs), (rhs_operation, rhs))| lhs_operation == rhs_operation && self.same(*lhs, *rhs))
+            },
+            _ => false,
+        }
+    }
+
+    // the product `id` with its factors in the order they are written, so `y * x` becomes `x * y`
+    // and is collected with it as a like term. anything else is left as it is
+    fn sort_factors(&mut self, id: NodeId) -> NodeId {
+        let Node::Chain { first, rest } = self.get(id) else {
+            return id;
+        };
+        if !rest.iter().all(|(operation, _)| *operation == Operation::Multiply) {
+            return id;
+        }
+        let mut factors: Vec<NodeId> = std::iter::once(*first).chain(rest.iter().map(|(_, factor)| *factor)).collect();
+        let mut sorted = factors.clone();
+        sorted.sort_by_cached_key(|factor| self.display(*factor));
+        if sorted == factors {
+            return id;
+        }
+        factors = sorted;
+        let first = factors.remove(0);
+        let rest = factors.into_iter().map(|factor| (Operation::Multiply, factor)).collect();
+        self.push(Node::Chain { first, rest })
+    }
+
+    // the node `id` as a number times a term, like `(3, x)` for `3 * x`, where a plain number has no term.
+    // the factors of the term are put in order, so terms written in a different order are still alike
+    fn split_term(&mut self, id: NodeId) -> (f64, Option<NodeId>) {
+        if let Some(number) = self.as_number(id) {
+            return (number, None);
+        }
+        match self.get(id) {
+            Node::Negate(operand) => {
+                let (coefficient, term) = self.split_term(*operand);
+                (-coefficient, term)
+            },
+            Node::Chain { first, rest } if rest.iter().all(|(operation, _)| *operation == Operation::Multiply) && self.as_number(*first).is_some() => {
+                let coefficient = self.as_number(*first).unwrap_or(f64::NAN);
+                let term = match rest.as_slice() {
+                    [(_, term)] => *term,
+                    [(_, first), rest @ ..] => {
+                        let (first, rest) = (*first, rest.to_vec());
+                        self.push(Node::Chain { first, rest })
+                    },
+                    [] => return (coefficient, None),
+                };
+                (coefficient, Some(self.sort_factors(term)))
+            },
+            // the signs of the factors belong to the whole term, so `a + x * -y` becomes `a - x * y`
+            Node::Chain { first, rest } if rest.iter().all(|(operation, _)| *operation == Operation::Multiply) => {
+                let factors: Vec<NodeId> = std::iter::once(*first).chain(rest.iter().map(|(_, factor)| *factor)).collect();
+                if !factors.iter().any(|factor| matches!(self.get(*factor), Node::Negate(_))) {
+                    return (1.0, Some(self.sort_factors(id)));
+                }
+                let mut sign = 1.0;
+                let mut unsigned = Vec::with_capacity(factors.len());
+                for factor in factors {
+                    match self.get(factor) {
+                        Node::Negate(operand) => {
+                            sign = -sign;
+                            unsigned.push(*operand);
+                        },
+                        _ => unsigned.push(factor),
+                    }
+                }
+                let product = unsigned.into_iter().reduce(|product, factor| self.multiply(product, factor)).unwrap_or(id);
+                let (coefficient, term) = self.split_term(product);
+                (sign * coefficient, term)
+            },
+            _ => (1.0, Some(self.sort_factors(id))),
+        }
+    }
+
+    // the node `id` as the terms of a sum, each a number times a term, with the terms of `-` negated
+    fn terms(&mut self, id: NodeId) -> Vec<(f64, Option<NodeId>)> {
+        let Node::Chain { first, rest } = self.get(id) else {
+            return vec![self.split_term(id)];
+        };
+        if !matches!(rest[0].0, Operation::Add | Operation::Subtract) {
+            return vec![self.split_term(id)];
+        }
+        let (first, rest) = (*first, rest.clone());
+        let mut terms = vec![self.split_term(first)];
+        for (operation, term) in rest {
+            let (coefficient, term) = self.split_term(term);
+            terms.push(if operation == Operation::Subtract { (-coefficient, term) } else { (coefficient, term) });
+        }
+        terms
+    }
+
+    // the sum of `terms`, adding up the numbers of terms written the same, dropping the ones that come to `0`,
+    // and putting the plain number last, so `x + 2 - x + 3 * y + 1` becomes `3 * y + 3`
+    fn sum(&mut self, terms: Vec<(f64, Option<NodeId>)>) -> NodeId {
+        let mut collected: Vec<(f64, Option<NodeId>)> = Vec::new();
+        for (coefficient, term) in terms {
+            let like = collected.iter().position(|(other_coefficient, other)| {
+                let is_like = match (term, other) {
+                    (None, None) => true,
+                    (Some(term), Some(other)) => self.same(term, *other),
+                    _ => false,
+                };
+                // numbers are only added up when no rounding happens
+                is_like && Operation::Add.is_exact(coefficient, *other_coefficient, coefficient + other_coefficient)
+            });
+            match like {
+                Some(like) => collected[like].0 += coefficient,
+                None => collected.push((coefficient, term)),
+            }
+        }
+        collected.retain(|(coefficient, _)| *coefficient != 0.0);
+        collected.sort_by_key(|(_, term)| term.is_none());
+
+        let mut sum: Option<NodeId> = None;
+        for (coefficient, term) in collected {
+            let size = match term {
+                Some(term) => {
+                    let coefficient = self.number(coefficient.abs());
+                    self.multiply(coefficient, term)
+                },
+                None => self.number(coefficient.abs()),
+            };
+            sum = Some(match sum {
+                None if coefficient < 0.0 => self.negate(size),
+                None => size,
+                Some(sum) if coefficient < 0.0 => self.chain(Operation::Subtract, sum, size),
+                Some(sum) => self.chain(Operation::Add, sum, size),
+            });
+        }
+        sum.unwrap_or_else(|| self.number(0.0))
+    }
+
+    // a number is moved in front of what it multiplies, so `x * 2` becomes `2 * x`, `0` times anything finite is `0`,
+    // and joins the number a product already starts with, so `3 * (2 * x)` becomes `6 * x`
+    fn multiply(&mut self, lhs: NodeId, rhs: NodeId) -> NodeId {
+        if let Some(product) = self.fold(Operation::Multiply, lhs, rhs) {
+            return product;
+        }
+        // a sign is moved to the front of the product, so `2 * -x` becomes `-2 * x`
+        if let (Node::Negate(operand), None) = (self.get(lhs), self.as_number(lhs)) {
+            let product = self.multiply(*operand, rhs);
+            return self.negate(product);
+        }
+        if let (Node::Negate(operand), None) = (self.get(rhs), self.as_number(rhs)) {
+            let product = self.multiply(lhs, *operand);
+            return self.negate(product);
+        }
+        if let Some(power) = self.merge_powers(lhs, rhs) {
+            return power;
+        }
+        match (self.as_number(lhs), self.as_number(rhs)) {
+            (Some(0.0), _) if self.is_finite(rhs) => self.number(0.0),
+            (_, Some(0.0)) if self.is_finite(lhs) => self.number(0.0),
+            (Some(1.0), _) => rhs,
+            (_, Some(1.0)) => lhs,
+            (Some(-1.0), _) => self.negate(rhs),
+            (_, Some(-1.0)) => self.negate(lhs),
+            (None, Some(_)) => self.multiply(rhs, lhs),
+            (Some(_), None) => match self.get(rhs) {
+                Node::Chain { first, rest } if rest[0].0 == Operation::Multiply && self.as_number(*first).is_some() => {
+                    let (first, rest) = (*first, rest.clone());
+                    match self.fold(Operation::Multiply, lhs, first) {
+                        Some(first) => self.push(Node::Chain { first, rest }),
+                        None => self.chain(Operation::Multiply, lhs, rhs),
+                    }
+                },
+                // the number starts the product instead of multiplying it in parentheses, so `2 * (x * y)` becomes `2 * x * y`
+                Node::Chain { first, rest } if rest.iter().all(|(operation, _)| *operation == Operation::Multiply) => {
+                    let mut rest = rest.clone();
+                    rest.insert(0, (Operation::Multiply, *first));
+                    self.push(Node::Chain { first: lhs, rest })
+                },
+                _ => self.chain(Operation::Multiply, lhs, rhs),
+            },
+            _ => self.chain(Operation::Multiply, lhs, rhs),
+        }
+    }
+
+    // the node `id` as a base and its exponent, which is `None` when it isn't a power, like the `x` of `x * y`
+    fn split_power(&self, id: NodeId) -> (NodeId, Option<NodeId>) {
+        match self.get(id) {
+            Node::Chain { first, rest } if rest.len() == 1 && rest[0].0 == Operation::Exponential => (*first, Some(rest[0].1)),
+            _ => (id, None),
+        }
+    }
+
+    // `lhs * rhs` as one power when `rhs` has the same base as `lhs`, or as one of the factors of the product `lhs`,
+    // so `x * x` becomes `x ^ 2` and `x ^ 2 * y * x` becomes `x ^ 3 * y`. a number base is left for `fold`
+    fn merge_powers(&mut self, lhs: NodeId, rhs: NodeId) -> Option<NodeId> {
+        let (rhs_base, rhs_exponent) = self.split_power(rhs);
+        if self.as_number(rhs_base).is_some() {
+            return None;
+        }
+
+        let (lhs_base, lhs_exponent) = self.split_power(lhs);
+        if self.same(lhs_base, rhs_base) {
+            let one = self.number(1.0);
+            let exponent = self.add(lhs_exponent.unwrap_or(one), rhs_exponent.unwrap_or(one));
+            return Some(self.power(lhs_base, exponent));
+        }
+
+        let Node::Chain { first, rest } = self.get(lhs) else {
+            return None;
+        };
+        if !rest.iter().all(|(operation, _)| *operation == Operation::Multiply) {
+            return None;
+        }
+        let mut factors: Vec<NodeId> = std::iter::once(*first).chain(rest.iter().map(|(_, factor)| *factor)).collect();
+        let like = factors.iter().position(|factor| self.same(self.split_power(*factor).0, rhs_base))?;
+        let merged = self.merge_powers(factors[like], rhs)?;
+        // a power that comes to a number, like `x ^ -1 * x`, is left for the number in front of the product to take
+        if self.as_number(merged).is_some() {
+            return None;
+        }
+        factors[like] = merged;
+        let first = factors.remove(0);
+        let rest = factors.into_iter().map(|factor| (Operation::Multiply, factor)).collect();
+        Some(self.push(Node::Chain { first, rest }))
+    }
+
+    // anything divided by itself is `1`, taking it not to be `0`
+    fn divide(&mut self, lhs: NodeId, rhs: NodeId) -> NodeId {
+        if let Some(quotient) = self.fold(Operation::Divide, lhs, rhs) {
+            return quotient;
+        }
+        if self.same(lhs, rhs) && self.as_number(lhs).is_none() {
+            return self.number(1.0);
+        }
+        match (self.as_number(lhs), self.as_number(rhs)) {
+            (Some(0.0), None) => self.number(0.0),
+            (None, Some(1.0)) => lhs,
+            _ => self.chain(Operation::Divide, lhs, rhs),
+        }
+    }
+
+    fn power(&mut self, base: NodeId, exponent: NodeId) -> NodeId {
+        if let Some(power) = self.fold(Operation::Exponential, base, exponent) {
+            return power;
+        }
+        match self.as_number(exponent) {
+            Some(0.0) => self.number(1.0),
+            Some(1.0) => base,
+            _ => self.chain(Operation::Exponential, base, exponent),
+        }
+    }
+
+    // the sign of a product goes on its number, so `-(2 * x)` becomes `-2 * x`
+    fn negate(&mut self, operand: NodeId) -> NodeId {
+        match (self.get(operand), self.as_number(operand)) {
+            (_, Some(number)) => self.number(-number),
+            (Node::Negate(operand), None) => *operand,
+            (Node::Chain { first, rest }, None) if rest[0].0 == Operation::Multiply && self.as_number(*first).is_some() => {
+                let rest = rest.clone();
+                let first = self.as_number(*first).map_or(f64::NAN, |number| -number);
+                let first = self.number(first);
+                self.push(Node::Chain { first, rest })
+            },
+            // a product without a number takes the sign on its first factor, so `-(x * y)` becomes `-x * y`
+            (Node::Chain { first, rest }, None) if rest.iter().all(|(operation, _)| *operation == Operation::Multiply) => {
+                let (first, rest) = (*first, rest.clone());
+                let first = self.negate(first);
+                self.push(Node::Chain { first, rest })
+            },
+            _ => self.push(Node::Negate(operand)),
+        }
+    }
+
+    fn call(&mut self, name: &str, argument: NodeId) -> NodeId {
+        self.push(Node::Call { name: name.to_owned(), arguments: vec![argument] })
+    }
+
+    // the natural logarithm of `operand`, which is `1` for `e` itself
+    fn ln(&mut self, operand: NodeId) -> NodeId {
+        match self.get(operand) {
+            Node::Identifier(name) if name == "e" => self.number(1.0),
+            _ => self.call("ln", operand),
+        }
+    }
+
+    // whether a name appears anywhere in the node `id`
+    fn has_names(&self, id: NodeId) -> bool {
+        match self.get(id) {
+            Node::Identifier(_) => true,
+            Node::Negate(operand) => self.has_names(*operand),
+            Node::Call { arguments, .. } | Node::Matrix { elements: arguments, .. } => arguments.iter().any(|argument| self.has_names(*argument)),
+            Node::Chain { first, rest } => self.has_names(*first) || rest.iter().any(|(_, term)| self.has_names(*term)),
+            _ => false,
+        }
+    }
+
+    // the node `id` worked out when it has no names in it and its value is exact, so `1 / 3 + 1 / 6` becomes `0.5`,
+    // `1 / 6 + 1 / 6` becomes `1 / 3`, and `sin(0)` becomes `0`. it is evaluated in fraction mode, which keeps quotients exact
+    fn evaluate_constant(&mut self, id: NodeId) -> NodeId {
+        if self.as_number(id).is_some() || self.has_names(id) {
+            return id;
+        }
+        let mut environment = Environment::new();
+        environment.set_mode(Mode::Fraction);
+        let Ok(evaluation) = Expression::new(self.nodes.clone(), id).evaluate_detailed(&environment) else {
+            return id;
+        };
+        if !evaluation.exact || evaluation.boolean {
+            return id;
+        }
+
+        // a negative result is pushed as a negation, the same way `number` pushes one
+        let integer = |builder: &mut Self, integer: BigInt| {
+            let size = builder.push(Node::Integer(integer.abs()));
+            if integer.is_negative() {
+                builder.push(Node::Negate(size))
+            } else {
+                size
+            }
+        };
+        match evaluation.value {
+            Value::Integer(value) => integer(self, value),
+            Value::Rational(fraction) if fraction.is_integer() => integer(self, BigInt::from_i128(fraction.numerator())),
+            // a fraction with a short decimal form is written like the numbers `fold` works out, so `1 / 4 + 1 / 4` is `0.5`
+            Value::Rational(fraction) if Rational::from_decimal(fraction.to_f64()) == Some(fraction) => self.number(fraction.to_f64()),
+            Value::Rational(fraction) => {
+                let numerator = integer(self, BigInt::from_i128(fraction.numerator()));
+                let denominator = integer(self, BigInt::from_i128(fraction.denominator()));
+                self.push(Node::Chain { first: numerator, rest: vec![(Operation::Divide, denominator)] })
+            },
+            Value::Real(number) if number.is_finite() => self.number(number),
+            _ => id,
+        }
+    }
+
+    // an expression made of the node `id` of this builder, for error messages
+    fn display(&self, id: NodeId) -> String {
+        Expression::new(self.nodes.clone(), id).to_string()
+    }
+
+    // whether `variable` appears in the node `id`
+    fn contains(&self, id: NodeId, variable: &str) -> bool {
+        match self.get(id) {
+            Node::Identifier(name) => name == variable,
+            Node::Negate(operand) => self.contains(*operand, variable),
+            Node::Call { arguments, .. } | Node::Matrix { elements: arguments, .. } => arguments.iter().any(|argument| self.contains(*argument, variable)),
+            Node::Chain { first, rest } => self.contains(*first, variable) || rest.iter().any(|(_, term)| self.contains(*term, variable)),
+            _ => false,
+        }
+    }
+
+    // the coefficients of the node `id` as a polynomial in `variable`, starting with the constant term, with the function `name` in error messages.
+    // anything without the variable in it is evaluated, so it can use the constants and variables of `environment`,
+    // clearing `exact` if it was rounded
+    fn polynomial(&self, name: &str, id: NodeId, variable: &str, environment: &Environment, exact: &mut bool) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+        if !self.contains(id, variable) {
+            let evaluation = Expression::new(self.nodes.clone(), id).evaluate_detailed(environment)?;
+            if !evaluation.exact {
+                *exact = false;
+            }
+            let value = evaluation.value;
+            let number = value
+                .to_real()
+                .ok_or_else(|| format!("{} only works with real numbers, but `{}` is {}", name, self.display(id), value))?;
+            return Ok(vec![number]);
+        }
+
+        let not_polynomial = || format!("{} only works on polynomials in {}, but was given `{}`", name, variable, self.display(id));
+        match self.get(id) {
+            Node::Identifier(_) => Ok(vec![0.0, 1.0]),
+            Node::Negate(operand) => Ok(self.polynomial(name, *operand, variable, environment, exact)?.iter().map(|coefficient| -coefficient).collect()),
+            Node::Chain { first, rest } => {
+                let mut lhs = self.polynomial(name, *first, variable, environment, exact)?;
+                for (operation, term) in rest {
+                    let rhs = self.polynomial(name, *term, variable, environment, exact)?;
+                    lhs = match (operation, rhs.as_slice()) {
+                        (Operation::Add, _) => add_polynomials(&lhs, &rhs, 1.0),
+                        (Operation::Subtract, _) => add_polynomials(&lhs, &rhs, -1.0),
+                        (Operation::Multiply, _) => multiply_polynomials(&lhs, &rhs),
+                        (Operation::Divide, [divisor]) if *divisor != 0.0 => lhs.iter().map(|coefficient| coefficient / divisor).collect(),
+                        // a whole power is the polynomial multiplied by itself that many times
+                        (Operation::Exponential, [exponent]) if exponent.fract() == 0.0 && *exponent >= 0.0 => {
+                            if (lhs.len() - 1) as f64 * exponent > MAX_DEGREE as f64 {
+                                return Err(format!("{} only works up to {} ^ {}, but `{}` is higher", name, variable, MAX_DEGREE, self.display(id)).into());
+                            }
+                            (0..*exponent as usize).fold(vec![1.0], |power, _| multiply_polynomials(&power, &lhs))
+                        },
+                        _ => return Err(not_polynomial().into()),
+                    };
+                }
+                if lhs.len() > MAX_DEGREE + 1 {
+                    return Err(format!("{} only works up to {} ^ {}, but `{}` is higher", name, variable, MAX_DEGREE, self.display(id)).into());
+                }
+                Ok(lhs)
+            },
+            _ => Err(not_polynomial().into()),
+        }
+    }
+
+    // the node `id` of the source rebuilt in this builder, which simplifies it
+    fn rebuild(&mut self, id: NodeId) -> Result<NodeId, Box<dyn std::error::Error>> {
+        let source = self.source;
+        match source.get(id) {
+            node @ (Node::Number(_) | Node::Integer(_) | Node::Imaginary(_) | Node::Identifier(_)) => Ok(self.push(node.clone())),
+            Node::Negate(operand) => {
+                let operand = self.rebuild(*operand)?;
+                Ok(self.negate(operand))
+            },
+            Node::Call { name, arguments } => {
+                let arguments = arguments.iter().map(|argument| self.rebuild(*argument)).collect::<Result<_, _>>()?;
+                let call = self.push(Node::Call { name: name.clone(), arguments });
+                Ok(self.evaluate_constant(call))
+            },
+            Node::Matrix { columns, elements } => {
+                let elements = elements.iter().map(|element| self.rebuild(*element)).collect::<Result<_, _>>()?;
+                Ok(self.push(Node::Matrix { columns: *columns, elements }))
+            },
+            Node::Chain { first, rest } => {
+                let operands: Vec<NodeId> = std::iter::once(*first)
+                    .chain(rest.iter().map(|(_, term)| *term))
+                    .map(|id| self.rebuild(id))
+                    .collect::<Result<_, _>>()?;
+
+                // `^` groups from the right, so its chain is combined from the end
+                if rest[0].0 == Operation::Exponential {
+                    let mut operands = operands.into_iter().rev();
+                    let last = operands.next().ok_or("an empty chain")?;
+                    let power = operands.fold(last, |exponent, base| self.power(base, exponent));
+                    return Ok(self.evaluate_constant(power));
+                }
+                let mut operands = operands.into_iter();
+                let first = operands.next().ok_or("an empty chain")?;
+                let chain = rest
+                    .iter()
+                    .map(|(operation, _)| *operation)
+                    .zip(operands)
+                    .fold(first, |lhs, (operation, rhs)| match operation {
+                        Operation::Add => self.add(lhs, rhs),
+                        Operation::Subtract => self.subtract(lhs, rhs),
+                        Operation::Multiply => self.multiply(lhs, rhs),
+                        Operation::Divide => self.divide(lhs, rhs),
+                        // a chain of comparisons checks each neighbouring pair, so it can't be worked out one pair at a time
+                        operation if operation.is_comparison() => self.chain(operation, lhs, rhs),
+                        operation => self.fold(operation, lhs, rhs).unwrap_or_else(|| self.chain(operation, lhs, rhs)),
+                    });
+                Ok(self.evaluate_constant(chain))
+            },
+            _ => Err(format!("simplify can't simplify `{}`", source.node(id)).into()),
+        }
+    }
+
+    // the node `id` of the source and its derivative by `variable`, both rebuilt in this builder.
+    // derivatives of the trig functions are taken in radians
+    fn differentiate(&mut self, id: NodeId, variable: &str) -> Result<(NodeId, NodeId), Box<dyn std::error::Error>> {
+        let source = self.source;
+        match source.get(id) {
+            Node::Number(_) | Node::Integer(_) | Node::Imaginary(_) => {
+                let (value, zero) = (self.push(source.get(id).clone()), self.number(0.0));
+                Ok((value, zero))
+            },
+            Node::Identifier(name) => {
+                let value = self.push(Node::Identifier(name.clone()));
+                let derivative = self.number(if name == variable { 1.0 } else { 0.0 });
+                Ok((value, derivative))
+            },
+            Node::Negate(operand) => {
+                let (value, derivative) = self.differentiate(*operand, variable)?;
+                Ok((self.negate(value), self.negate(derivative)))
+            },
+            Node::Call { name, arguments } if arguments.len() == 1 => {
+                let (u, du) = self.differentiate(arguments[0], variable)?;
+                let value = self.call(name, u);
+                let outer = match name.as_str() {
+                    "sin" => self.call("cos", u),
+                    "cos" => {
+                        let sine = self.call("sin", u);
+                        self.negate(sine)
+                    },
+                    "tan" => {
+                        let (cosine, two, one) = (self.call("cos", u), self.number(2.0), self.number(1.0));
+                        let square = self.power(cosine, two);
+                        self.divide(one, square)
+                    },
+                    "exp" => value,
+                    "ln" => {
+                        let one = self.number(1.0);
+                        self.divide(one, u)
+                    },
+                    "log" => {
+                        let (one, ten) = (self.number(1.0), self.number(10.0));
+                        let ln_ten = self.call("ln", ten);
+                        let scaled = self.multiply(u, ln_ten);
+                        self.divide(one, scaled)
+                    },
+                    "sqrt" => {
+                        let (one, two) = (self.number(1.0), self.number(2.0));
+                        let twice = self.multiply(two, value);
+                        self.divide(one, twice)
+                    },
+                    "abs" => self.divide(u, value),
+                    _ => return Err(format!("diff doesn't know the derivative of `{}`", name).into()),
+                };
+                // the chain rule
+                Ok((value, self.multiply(outer, du)))
+            },
+            Node::Chain { first, rest } => {
+                let operands: Vec<(NodeId, NodeId)> = std::iter::once(*first)
+                    .chain(rest.iter().map(|(_, term)| *term))
+                    .map(|id| self.differentiate(id, variable))
+                    .collect::<Result<_, _>>()?;
+                let operations: Vec<Operation> = rest.iter().map(|(operation, _)| *operation).collect();
+
+                // `^` groups from the right, so its chain is combined from the end
+                if operations.first() == Some(&Operation::Exponential) {
+                    let mut operands = operands.into_iter().rev();
+                    let last = operands.next().ok_or("an empty chain")?;
+                    return operands.try_fold(last, |rhs, lhs| self.differentiate_operation(Operation::Exponential, lhs, rhs));
+                }
+                let mut operands = operands.into_iter();
+                let first = operands.next().ok_or("an empty chain")?;
+                operations
+                    .into_iter()
+                    .zip(operands)
+                    .try_fold(first, |lhs, (operation, rhs)| self.differentiate_operation(operation, lhs, rhs))
+            },
+            _ => Err(format!("diff can't differentiate `{}`", source.node(id)).into()),
+        }
+    }
+
+    // `u operation v` and its derivative, from `u` and `v` paired with their derivatives
+    fn differentiate_operation(&mut self, operation: Operation, (u, du): (NodeId, NodeId), (v, dv): (NodeId, NodeId)) -> Result<(NodeId, NodeId), Box<dyn std::error::Error>> {
+        match operation {
+            Operation::Add => Ok((self.add(u, v), self.add(du, dv))),
+            Operation::Subtract => Ok((self.subtract(u, v), self.subtract(du, dv))),
+            // the product rule
+            Operation::Multiply => {
+                let (du_v, u_dv) = (self.multiply(du, v), self.multiply(u, dv));
+                Ok((self.multiply(u, v), self.add(du_v, u_dv)))
+            },
+            // the quotient rule, which is just `du / v` when `v` is constant
+            Operation::Divide => {
+                let value = self.divide(u, v);
+                if self.is_number(dv, 0.0) {
+                    return Ok((value, self.divide(du, v)));
+                }
+                let (du_v, u_dv, two) = (self.multiply(du, v), self.multiply(u, dv), self.number(2.0));
+                let (numerator, denominator) = (self.subtract(du_v, u_dv), self.power(v, two));
+                Ok((value, self.divide(numerator, denominator)))
+            },
+            // the power rule for a constant exponent, the exponential rule for a constant base, and `u ^ v * (dv * ln(u) + v * du / u)` otherwise
+            Operation::Exponential => {
+                let value = self.power(u, v);
+                let derivative = if self.is_number(dv, 0.0) {
+                    let one = self.number(1.0);
+                    let exponent = self.subtract(v, one);
+                    let lowered = self.power(u, exponent);
+                    let scaled = self.multiply(v, lowered);
+                    self.multiply(scaled, du)
+                } else if self.is_number(du, 0.0) {
+                    let ln_u = self.ln(u);
+                    let scaled = self.multiply(value, ln_u);
+                    self.multiply(scaled, dv)
+                } else {
+                    let ln_u = self.ln(u);
+                    let (dv_ln_u, v_du) = (self.multiply(dv, ln_u), self.multiply(v, du));
+                    let v_du_u = self.divide(v_du, u);
+                    let sum = self.add(dv_ln_u, v_du_u);
+                    self.multiply(value, sum)
+                };
+                Ok((value, derivative))
+            },
+            _ => Err(format!("diff can't differentiate `{}`", operation).into()),
+        }
+    }
+}
+
+// the real solutions of the polynomial with `coefficients` equal to `0`, clearing `exact` when they were rounded.
+// the solutions are always a list, from smallest to largest, like the roots from `roots`
+fn solve_polynomial(coefficients: &[f64], variable: &str, exact: &mut bool) -> Result<Value, Box<dyn std::error::Error>> {
+    let degree = coefficients.iter().rposition(|coefficient| *coefficient != 0.0);
+    match (degree, coefficients) {
+        (None, _) => Err(format!("Every value of {} solves it", variable).into()),
+        (Some(0), _) => Err(format!("No value of {} solves it", variable).into()),
+        (Some(1), [c, b, ..]) => {
+            let root = -c / b;
+            if !Operation::Divide.is_exact(-c, *b, root) {
+                *exact = false;
+            }
+            Ok(Value::List(vec![Value::Real(root + 0.0)]))
+        },
+        (Some(2), [c, b, a, ..]) => {
+            let discriminant = b.mul_add(*b, -4.0 * a * c);
+            if discriminant < 0.0 {
+                let (re, im) = (-b / (2.0 * a), (-discriminant).sqrt() / (2.0 * a).abs());
+                return Err(format!(
+                    "No real value of {} solves it, the complex solutions are {} and {}",
+                    variable,
+                    Complex::new(re, -im),
+                    Complex::new(re, im)
+                )
+                .into());
+            }
+            if discriminant == 0.0 {
+                let root = -b / (2.0 * a);
+                if !Operation::Divide.is_exact(-b, 2.0 * a, root) {
+                    *exact = false;
+                }
+                return Ok(Value::List(vec![Value::Real(root + 0.0)]));
+            }
+
+            // `q` never subtracts nearly equal numbers, so neither solution loses digits to cancellation
+            let root = discriminant.sqrt();
+            let sum = b + b.signum() * root;
+            let q = -sum / 2.0;
+            let (first, second) = (q / a, c / q);
+            let is_exact = root.mul_add(root, -discriminant) == 0.0
+                && Operation::Add.is_exact(*b, b.signum() * root, sum)
+                && Operation::Divide.is_exact(q, *a, first)
+                && Operation::Divide.is_exact(*c, q, second);
+            if !is_exact {
+                *exact = false;
+            }
+            Ok(Value::List(vec![Value::Real(first.min(second) + 0.0), Value::Real(first.max(second) + 0.0)]))
+        },
+        (Some(degree), _) => Err(format!("solve only works on linear and quadratic equations, but this one has {} ^ {}; roots finds the solutions of any polynomial", variable, degree).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the result of calling one of the `SYMBOLIC_FUNCTIONS` as written in `input`
+    fn call(input: &str) -> String {
+        let expression: Expression = input.parse().unwrap();
+        expression.evaluate(&Environment::new()).unwrap().to_string()
+    }
+
+    // the error from calling one of the `SYMBOLIC_FUNCTIONS` as written in `input`
+    fn call_error(input: &str) -> String {
+        let expression: Expression = input.parse().unwrap();
+        expression.evaluate(&Environment::new()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn simplify_folds_identities_and_collects_terms() {
+        assert_eq!(call("simplify(x * 1 + 0)"), "x");
+        assert_eq!(call("simplify(x + 2 - x + 3y + 1)"), "3 * y + 3");
+        assert_eq!(call("simplify(2x + 3x * 1)"), "5 * x");
+        assert_eq!(call("simplify(x * y + y * x)"), "2 * x * y");
+        assert_eq!(call("simplify(3 * (x * y))"), "3 * x * y");
+    }
+
+    #[test]
+    fn simplify_only_drops_finite_terms_times_zero() {
+        assert_eq!(call("simplify(0 * x)"), "0");
+        assert_eq!(call("simplify(0 * (1 / 0))"), "0 * (1 / 0)");
+        assert_eq!(call("simplify(0 * sqrt(x))"), "0 * sqrt(x)");
+    }
+
+    #[test]
+    fn simplify_works_out_constant_parts() {
+        assert_eq!(call("simplify(1/3 + 1/6)"), "0.5");
+        assert_eq!(call("simplify(1/6 + 1/6)"), "1 / 3");
+        assert_eq!(call("simplify(sin(0))"), "0");
+        assert_eq!(call("simplify(2 ^ 100)"), "1267650600228229401496703205376");
+        assert_eq!(call("simplify(x * (1/6 + 1/6))"), "x * (1 / 3)");
+        // inexact values are left as written
+        assert_eq!(call("simplify(sin(1))"), "sin(1)");
+        assert_eq!(call("simplify(2 * pi)"), "2 * pi");
+    }
+
+    #[test]
+    fn simplify_adds_the_exponents_of_the_same_base() {
+        assert_eq!(call("simplify(x ^ 2 * x)"), "x ^ 3");
+        assert_eq!(call("simplify(x * x)"), "x ^ 2");
+        assert_eq!(call("simplify(x ^ a * x ^ b)"), "x ^ (a + b)");
+        assert_eq!(call("simplify(x ^ 2 * y * x ^ 3)"), "x ^ 5 * y");
+        assert_eq!(call("simplify(x ^ -1 * x)"), "1");
+    }
+
+    #[test]
+    fn diff_applies_the_rules_of_differentiation() {
+        assert_eq!(call("diff(x ^ 2 + 3x, x)"), "2 * x + 3");
+        assert_eq!(call("diff(5, x)"), "0");
+        assert_eq!(call("diff(y * x, x)"), "y");
+        assert_eq!(call("diff(sin(x), x)"), "cos(x)");
+        assert_eq!(call("diff(ln(x), x)"), "1 / x");
+        assert_eq!(call("diff(exp(2x), x)"), "2 * exp(2 * x)");
+        assert_eq!(call("diff(2 ^ x, x)"), "2 ^ x * ln(2)");
+        assert_eq!(call("diff(x ^ x, x)"), "x ^ x * (ln(x) + 1)");
+        assert_eq!(call("diff(1 / x, x)"), "-1 / x ^ 2");
+        assert_eq!(call("diff(sqrt(x), x)"), "1 / (2 * sqrt(x))");
+    }
+
+    #[test]
+    fn diff_writes_negated_terms_as_subtraction() {
+        assert_eq!(call("diff(sin(x) * cos(x), x)"), "cos(x) ^ 2 - sin(x) ^ 2");
+        assert_eq!(call("diff(x * cos(x), x)"), "cos(x) - sin(x) * x");
+        assert_eq!(call("diff(cos(x) ^ 2, x)"), "-2 * cos(x) * sin(x)");
+        assert_eq!(call("simplify(a + x * -y)"), "a - x * y");
+        assert_eq!(call("simplify((-x) * (-y))"), "x * y");
+    }
+
+    #[test]
+    fn diff_needs_a_variable() {
+        let expression: Expression = "diff(x, 2)".parse().unwrap();
+        assert!(expression.evaluate(&Environment::new()).is_err());
+    }
+
+    #[test]
+    fn solve_linear_and_quadratic_equations() {
+        assert_eq!(call("solve(2x + 3 = 11, x)"), "[4]");
+        assert_eq!(call("solve(2x + 3, x)"), "[-1.5]");
+        assert_eq!(call("solve(x ^ 2 = 4, x)"), "[-2, 2]");
+        assert_eq!(call("solve(x ^ 2 - 2x + 1 = 0, x)"), "[1]");
+        assert_eq!(call("solve(x ^ 2 = 2 ^ 2 + pi - pi, x)"), "[-2, 2]");
+    }
+
+    #[test]
+    fn solve_explains_equations_it_cant_solve() {
+        assert!(call_error("solve(x ^ 2 + 1 = 0, x)").contains("the complex solutions are -i and i"));
+        assert!(call_error("solve(x = x, x)").contains("Every value of x solves it"));
+        assert!(call_error("solve(x = x + 1, x)").contains("No value"));
+        assert!(call_error("solve(x ^ 3 = 1, x)").contains("roots finds the solutions of any polynomial"));
+        assert!(call_error("solve(sin(x) = 0, x)").contains("only works on polynomials"));
+    }
+
+    #[test]
+    fn roots_of_expressions_and_coefficient_lists() {
+        assert_eq!(call("roots(x ^ 3 - x)"), "[-1, 0, 1]");
+        assert_eq!(call("roots([1, 0, -4])"), "[-2, 2]");
+        assert_eq!(call("roots(x ^ 2 + 1)"), "[-i, i]");
+        assert_eq!(call("roots(x ^ 2 - 2x + 5)"), "[1-2i, 1+2i]");
+        assert!(call_error("roots(sin(x))").contains("only works on polynomials"));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    environment::Environment,
+    expression::Evaluation,
+    statement::Statement,
+};
+
+/// Fills in every `{{ expression }}` placeholder of a template with the value of its expression.<br>
+/// Placeholders are evaluated in order and share `environment`, so `{{ r = 2 }}` can be used by a later `{{ pi * r ^ 2 }}`,
+/// and `ans` holds the value of the previous placeholder.
+/// Text outside the placeholders is copied unchanged.
+/// # Parameters
+///  - `template`: the text of the document
+///  - `environment`: the variables the placeholders can use and assign
+/// # Returns
+///  - `Ok(document)`: the template with each placeholder replaced by its value
+///  - `Err(template_error)`: when a placeholder is never closed, or its expression can't be parsed or evaluated
+pub fn render(template: &str, environment: &mut Environment) -> Result<String, Box<dyn std::error::Error>> {
+    let mut document = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        document.push_str(&rest[..start]);
+
+        let line = template[..template.len() - rest.len() + start].matches('\n').count() + 1;
+        let after_open = &rest[start + 2..];
+        let end = after_open
+            .find("}}")
+            .ok_or_else(|| format!("line {}: `{{{{` is never closed with `}}}}`", line))?;
+
+        let source = after_open[..end].trim();
+        let evaluation = evaluate_placeholder(source, environment)
+            .map_err(|error| format!("line {}: `{{{{ {} }}}}`: {}", line, source, error))?;
+        document.push_str(&evaluation.to_string());
+
+        rest = &after_open[end + 2..];
+    }
+
+    document.push_str(rest);
+    Ok(document)
+}
+
+/// Replaces every `$((expression))` in one line of text with the value of its expression, like shell arithmetic.<br>
+/// Expressions share `environment` the same way template placeholders do.
+/// A placeholder that can't be evaluated is left as it was, and one that is never closed is copied unchanged.
+/// # Parameters
+///  - `line`: the text to filter
+///  - `environment`: the variables the expressions can use and assign
+/// # Returns
+///  - `(filtered, errors)`: the line with each placeholder replaced, and a message for each placeholder that couldn't be evaluated
+pub fn filter_line(line: &str, environment: &mut Environment) -> (String, Vec<String>) {
+    let mut filtered = String::with_capacity(line.len());
+    let mut errors = Vec::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find("$((") {
+        filtered.push_str(&rest[..start]);
+
+        let after_open = &rest[start + 3..];
+        let Some(end) = find_closing(after_open) else {
+            filtered.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+
+        let source = &after_open[..end];
+        match evaluate_placeholder(source, environment) {
+            Ok(evaluation) => filtered.push_str(&evaluation.to_string()),
+            Err(error) => {
+                errors.push(format!("`$(({}))`: {}", source, error));
+                filtered.push_str(&rest[start..start + 3 + end + 2]);
+            },
+        }
+
+        rest = &after_open[end + 2..];
+    }
+
+    filtered.push_str(rest);
+    (filtered, errors)
+}
+
+// the index of the `))` that closes a `$((`, skipping over balanced parentheses inside the expression
+fn find_closing(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, character) in text.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            ')' => return text[i + 1..].starts_with(')').then_some(i),
+            _ => {},
+        }
+    }
+    None
+}
+
+// evaluate the statement inside a placeholder, assigning it when it is an assignment
+fn evaluate_placeholder(source: &str, environment: &mut Environment) -> Result<Evaluation, Box<dyn std::error::Error>> {
+    let statement: Statement = source.parse()?;
+    let evaluation = statement.expression().evaluate_detailed(environment)?;
+    if let Statement::Assignment { name, .. } = &statement {
+        environment.assign(name, evaluation.value.clone())?;
+    }
+    environment.set_answer(evaluation.value.clone());
+    Ok(evaluation)
+}
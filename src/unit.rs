@@ -0,0 +1,363 @@
+use std::fmt::Display;
+
+use crate::value::Value;
+
+// the SI base units a dimension counts, in the order they are written, then money, which is counted in US dollars,
+// then angles, which are counted in degrees so whole degrees like `30 deg` stay exact, then amounts of data, which are counted in bits
+const BASE_UNITS: [&str; 8] = ["kg", "m", "s", "A", "K", "USD", "deg", "bit"];
+
+/// How many of each SI base unit a quantity is made of, e.g. a speed is `m/s`, one metre per one second.
+/// Money, angles, and data are counted as base units too, so `USD/h` is a wage, `deg/s` is a rate of turning, and `Mbit/s` is a download speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dimension([i8; 8]);
+impl Dimension {
+    /// The dimension of a plain number
+    pub const NONE: Dimension = Dimension([0; 8]);
+
+    /// The dimension of an amount of money in any currency
+    pub const MONEY: Dimension = Dimension([0, 0, 0, 0, 0, 1, 0, 0]);
+
+    /// The dimension of an angle
+    pub const ANGLE: Dimension = Dimension([0, 0, 0, 0, 0, 0, 1, 0]);
+
+    /// The dimension of an amount of data
+    pub const INFORMATION: Dimension = Dimension([0, 0, 0, 0, 0, 0, 0, 1]);
+
+    /// The dimension made of `powers` of each base unit, in the order `kg` `m` `s` `A` `K` `USD` `deg` `bit`
+    pub fn from_powers(powers: [i8; 8]) -> Self {
+        Dimension(powers)
+    }
+
+    /// The power of each base unit, in the order `kg` `m` `s` `A` `K` `USD` `deg` `bit`
+    pub fn powers(&self) -> [i8; 8] {
+        self.0
+    }
+
+    /// Whether this is the dimension of a plain number
+    pub fn is_none(&self) -> bool {
+        *self == Dimension::NONE
+    }
+
+    /// The dimension of a product, which adds the powers of each base unit
+    /// # Returns
+    ///  - `None`: when a power gets too big to track
+    pub fn mul(&self, rhs: Dimension) -> Option<Self> {
+        self.combine(rhs, i8::checked_add)
+    }
+
+    /// The dimension of a quotient, which subtracts the powers of each base unit
+    /// # Returns
+    ///  - `None`: when a power gets too big to track
+    pub fn div(&self, rhs: Dimension) -> Option<Self> {
+        self.combine(rhs, i8::checked_sub)
+    }
+
+    /// The dimension of a power, which multiplies the power of each base unit
+    /// # Returns
+    ///  - `None`: when a power gets too big to track
+    pub fn pow(&self, exponent: i32) -> Option<Self> {
+        let exponent = i8::try_from(exponent).ok()?;
+        self.combine(Dimension([exponent; 8]), i8::checked_mul)
+    }
+
+    /// The dimension of a square root, which halves the power of each base unit
+    /// # Returns
+    ///  - `None`: when a power is odd, like the `m^3` of a volume
+    pub fn sqrt(&self) -> Option<Self> {
+        self.combine(Dimension([2; 8]), |power, _| (power % 2 == 0).then_some(power / 2))
+    }
+
+    // apply `combine` to each pair of powers
+    fn combine(&self, rhs: Dimension, combine: fn(i8, i8) -> Option<i8>) -> Option<Self> {
+        let mut powers = [0; 8];
+        for (i, power) in powers.iter_mut().enumerate() {
+            *power = combine(self.0[i], rhs.0[i])?;
+        }
+        Some(Dimension(powers))
+    }
+}
+impl Display for Dimension { // allows for `println!()` and `.to_string()`
+
+    /// writes the dimension as SI base units the way they would be typed, like `kg*m/s^2`
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the dimension to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let write_power = |f: &mut std::fmt::Formatter<'_>, unit: &str, power: i8| match power {
+            1 => write!(f, "{}", unit),
+            power => write!(f, "{}^{}", unit, power),
+        };
+
+        // the units with positive powers are multiplied, then each unit with a negative power divides them
+        let mut wrote_numerator = false;
+        for (unit, power) in BASE_UNITS.iter().zip(self.0).filter(|(_, power)| *power > 0) {
+            if wrote_numerator {
+                write!(f, "*")?;
+            }
+            write_power(f, unit, power)?;
+            wrote_numerator = true;
+        }
+        if !wrote_numerator {
+            write!(f, "1")?;
+        }
+        for (unit, power) in BASE_UNITS.iter().zip(self.0).filter(|(_, power)| *power < 0) {
+            write!(f, "/")?;
+            write_power(f, unit, -power)?;
+        }
+        Ok(())
+    }
+}
+
+/// A builtin unit like `km` that can follow a number, as in `5 km`
+pub struct Unit {
+    /// every name the user can type for the unit
+    pub names: &'static [&'static str],
+    /// how many of the SI base units of `dimension` one of this unit is
+    pub size: f64,
+    /// what the unit measures
+    pub dimension: Dimension,
+}
+
+// the dimension `kg^mass m^length s^time A^current K^temperature`
+const fn dimension(mass: i8, length: i8, time: i8, current: i8, temperature: i8) -> Dimension {
+    Dimension([mass, length, time, current, temperature, 0, 0, 0])
+}
+const LENGTH: Dimension = dimension(0, 1, 0, 0, 0);
+const AREA: Dimension = dimension(0, 2, 0, 0, 0);
+const VOLUME: Dimension = dimension(0, 3, 0, 0, 0);
+const MASS: Dimension = dimension(1, 0, 0, 0, 0);
+/// The dimension of a length of time
+pub const TIME: Dimension = dimension(0, 0, 1, 0, 0);
+const FREQUENCY: Dimension = dimension(0, 0, -1, 0, 0);
+const SPEED: Dimension = dimension(0, 1, -1, 0, 0);
+const FORCE: Dimension = dimension(1, 1, -2, 0, 0);
+const PRESSURE: Dimension = dimension(1, -1, -2, 0, 0);
+const ENERGY: Dimension = dimension(1, 2, -2, 0, 0);
+const POWER: Dimension = dimension(1, 2, -3, 0, 0);
+const CURRENT: Dimension = dimension(0, 0, 0, 1, 0);
+const VOLTAGE: Dimension = dimension(1, 2, -3, -1, 0);
+const TEMPERATURE: Dimension = dimension(0, 0, 0, 0, 1);
+/// The dimension of an amount of data per length of time, like `Mbit/s`
+pub const DATA_RATE: Dimension = Dimension([0, 0, -1, 0, 0, 0, 0, 1]);
+
+/// Every builtin unit. `in` is the conversion keyword, so inches are `inch`
+pub const UNITS: &[Unit] = &[
+    Unit { names: &["m", "meter", "meters", "metre", "metres"], size: 1.0, dimension: LENGTH },
+    Unit { names: &["km", "kilometer", "kilometers", "kilometre", "kilometres"], size: 1e3, dimension: LENGTH },
+    Unit { names: &["cm", "centimeter", "centimeters", "centimetre", "centimetres"], size: 1e-2, dimension: LENGTH },
+    Unit { names: &["mm", "millimeter", "millimeters", "millimetre", "millimetres"], size: 1e-3, dimension: LENGTH },
+    Unit { names: &["inch", "inches"], size: 0.0254, dimension: LENGTH },
+    Unit { names: &["ft", "foot", "feet"], size: 0.3048, dimension: LENGTH },
+    Unit { names: &["yd", "yard", "yards"], size: 0.9144, dimension: LENGTH },
+    Unit { names: &["mi", "mile", "miles"], size: 1609.344, dimension: LENGTH },
+    Unit { names: &["ha", "hectare", "hectares"], size: 1e4, dimension: AREA },
+    Unit { names: &["acre", "acres"], size: 4046.8564224, dimension: AREA },
+    Unit { names: &["L", "liter", "liters", "litre", "litres"], size: 1e-3, dimension: VOLUME },
+    Unit { names: &["mL", "ml", "milliliter", "milliliters", "millilitre", "millilitres"], size: 1e-6, dimension: VOLUME },
+    Unit { names: &["gal", "gallon", "gallons"], size: 3.785411784e-3, dimension: VOLUME },
+    Unit { names: &["kg", "kilogram", "kilograms"], size: 1.0, dimension: MASS },
+    Unit { names: &["g", "gram", "grams"], size: 1e-3, dimension: MASS },
+    Unit { names: &["mg", "milligram", "milligrams"], size: 1e-6, dimension: MASS },
+    Unit { names: &["tonne", "tonnes"], size: 1e3, dimension: MASS },
+    Unit { names: &["lb", "lbs", "pound", "pounds"], size: 0.45359237, dimension: MASS },
+    Unit { names: &["oz", "ounce", "ounces"], size: 0.028349523125, dimension: MASS },
+    Unit { names: &["s", "sec", "second", "seconds"], size: 1.0, dimension: TIME },
+    Unit { names: &["ms", "millisecond", "milliseconds"], size: 1e-3, dimension: TIME },
+    Unit { names: &["min", "minute", "minutes"], size: 60.0, dimension: TIME },
+    Unit { names: &["h", "hr", "hour", "hours"], size: 3600.0, dimension: TIME },
+    Unit { names: &["day", "days"], size: 86400.0, dimension: TIME },
+    Unit { names: &["week", "weeks"], size: 604800.0, dimension: TIME },
+    Unit { names: &["Hz", "hertz"], size: 1.0, dimension: FREQUENCY },
+    Unit { names: &["kHz"], size: 1e3, dimension: FREQUENCY },
+    Unit { names: &["MHz"], size: 1e6, dimension: FREQUENCY },
+    Unit { names: &["GHz"], size: 1e9, dimension: FREQUENCY },
+    Unit { names: &["mph"], size: 0.44704, dimension: SPEED },
+    Unit { names: &["kph", "kmh"], size: 1e3 / 3600.0, dimension: SPEED },
+    Unit { names: &["knot", "knots"], size: 1852.0 / 3600.0, dimension: SPEED },
+    Unit { names: &["N", "newton", "newtons"], size: 1.0, dimension: FORCE },
+    Unit { names: &["Pa", "pascal", "pascals"], size: 1.0, dimension: PRESSURE },
+    Unit { names: &["kPa"], size: 1e3, dimension: PRESSURE },
+    Unit { names: &["bar"], size: 1e5, dimension: PRESSURE },
+    Unit { names: &["atm"], size: 101325.0, dimension: PRESSURE },
+    Unit { names: &["psi"], size: 6894.757293168361, dimension: PRESSURE },
+    Unit { names: &["J", "joule", "joules"], size: 1.0, dimension: ENERGY },
+    Unit { names: &["kJ"], size: 1e3, dimension: ENERGY },
+    Unit { names: &["cal", "calorie", "calories"], size: 4.184, dimension: ENERGY },
+    Unit { names: &["kcal"], size: 4184.0, dimension: ENERGY },
+    Unit { names: &["Wh"], size: 3600.0, dimension: ENERGY },
+    Unit { names: &["kWh"], size: 3.6e6, dimension: ENERGY },
+    Unit { names: &["W", "watt", "watts"], size: 1.0, dimension: POWER },
+    Unit { names: &["kW"], size: 1e3, dimension: POWER },
+    Unit { names: &["A", "amp", "amps", "ampere", "amperes"], size: 1.0, dimension: CURRENT },
+    Unit { names: &["V", "volt", "volts"], size: 1.0, dimension: VOLTAGE },
+    Unit { names: &["K", "kelvin"], size: 1.0, dimension: TEMPERATURE },
+    Unit { names: &["deg", "degree", "degrees"], size: 1.0, dimension: Dimension::ANGLE },
+    Unit { names: &["rad", "radian", "radians"], size: 180.0 / std::f64::consts::PI, dimension: Dimension::ANGLE },
+    Unit { names: &["bit", "bits"], size: 1.0, dimension: Dimension::INFORMATION },
+    Unit { names: &["kbit"], size: 1e3, dimension: Dimension::INFORMATION },
+    Unit { names: &["Mbit"], size: 1e6, dimension: Dimension::INFORMATION },
+    Unit { names: &["Gbit"], size: 1e9, dimension: Dimension::INFORMATION },
+    Unit { names: &["B", "byte", "bytes"], size: 8.0, dimension: Dimension::INFORMATION },
+    Unit { names: &["kB", "KB"], size: 8e3, dimension: Dimension::INFORMATION },
+    Unit { names: &["MB"], size: 8e6, dimension: Dimension::INFORMATION },
+    Unit { names: &["GB"], size: 8e9, dimension: Dimension::INFORMATION },
+    Unit { names: &["TB"], size: 8e12, dimension: Dimension::INFORMATION },
+    Unit { names: &["KiB"], size: 8.0 * 1024.0, dimension: Dimension::INFORMATION },
+    Unit { names: &["MiB"], size: 8.0 * 1024.0 * 1024.0, dimension: Dimension::INFORMATION },
+    Unit { names: &["GiB"], size: 8.0 * 1024.0 * 1024.0 * 1024.0, dimension: Dimension::INFORMATION },
+    Unit { names: &["TiB"], size: 8.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0, dimension: Dimension::INFORMATION },
+    Unit { names: &["bps"], size: 1.0, dimension: DATA_RATE },
+    Unit { names: &["kbps"], size: 1e3, dimension: DATA_RATE },
+    Unit { names: &["Mbps"], size: 1e6, dimension: DATA_RATE },
+    Unit { names: &["Gbps"], size: 1e9, dimension: DATA_RATE },
+];
+
+/// Looks up a builtin unit by any of its names
+/// # Parameters
+///  - `name`: the name the user typed
+/// # Returns
+///  - `Some(unit)`: when there is a builtin unit called `name`
+///  - `None`: when there is no such unit
+pub fn find_unit(name: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|unit| unit.names.contains(&name))
+}
+
+/// The unit a quantity is shown in, like `km`, and how many SI base units one of it is
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShownUnit {
+    /// the unit as the user typed it
+    pub name: String,
+    /// how many SI base units one of this unit is
+    pub size: f64,
+}
+impl ShownUnit {
+    /// The unit raised to a whole power, like `km^2`
+    pub fn pow(&self, exponent: i32) -> Self {
+        // a compound name like `km/h` has to be grouped before it is raised
+        let name = if self.name.contains(['*', '/', '^']) {
+            format!("({})^{}", self.name, exponent)
+        } else {
+            format!("{}^{}", self.name, exponent)
+        };
+        ShownUnit { name, size: self.size.powi(exponent) }
+    }
+}
+
+/// A number with units, like `5 km`. Arithmetic works on its amount in SI base units,
+/// and `unit` only decides how the result is shown
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    /// the amount in SI base units, so `5 km` is `5000`
+    value: f64,
+    /// what the quantity measures
+    dimension: Dimension,
+    /// the unit the amount is shown in, or `None` to show it in SI base units
+    unit: Option<ShownUnit>,
+}
+impl Quantity {
+    /// Creates a quantity of `value` SI base units of `dimension`, shown in `unit`
+    pub fn new(value: f64, dimension: Dimension, unit: Option<ShownUnit>) -> Self {
+        Quantity { value, dimension, unit }
+    }
+
+    /// Creates one of the builtin `unit`, shown by the `name` the user typed for it
+    pub fn of_unit(name: &str, unit: &Unit) -> Self {
+        Quantity::new(unit.size, unit.dimension, Some(ShownUnit { name: name.to_owned(), size: unit.size }))
+    }
+
+    /// Creates a plain number, without any units
+    pub fn number(number: f64) -> Self {
+        Quantity::new(number, Dimension::NONE, None)
+    }
+
+    /// The amount in SI base units
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// What the quantity measures
+    pub fn dimension(&self) -> Dimension {
+        self.dimension
+    }
+
+    /// The unit the quantity is shown in, or `None` for SI base units
+    pub fn unit(&self) -> Option<&ShownUnit> {
+        self.unit.as_ref()
+    }
+
+    /// The same quantity with `value` replaced, like the result of `abs`
+    pub fn with_value(&self, value: f64) -> Self {
+        Quantity::new(value, self.dimension, self.unit.clone())
+    }
+
+    /// This quantity shown in `unit` instead, as `5 km in m` asks for
+    /// # Parameters
+    ///  - `unit`: the quantity the unit stands for, like one `m`
+    ///  - `name`: the unit as the user typed it
+    /// # Returns
+    ///  - `Ok(quantity)`: the same amount, shown in `unit`
+    ///  - `Err(conversion_error)`: when `self` has no units, or `unit` measures something else, like converting metres to seconds
+    pub fn convert(&self, unit: &Quantity, name: String) -> Result<Self, Box<dyn std::error::Error>> {
+        if self.dimension.is_none() {
+            return Err(format!("Cannot convert {} to {}, it has no units", self.value, name).into());
+        }
+        if self.dimension != unit.dimension {
+            return Err(format!("Cannot convert {} to {}, they measure different things", self.dimension, unit.dimension).into());
+        }
+        Ok(Quantity::new(self.value, self.dimension, Some(ShownUnit { name, size: unit.value })))
+    }
+
+    /// The quantity as a `Value`, which is a plain real number once its units have cancelled out, like `1 km / 1 m`
+    pub fn into_value(self) -> Value {
+        if self.dimension.is_none() {
+            Value::Real(self.value)
+        } else {
+            Value::Quantity(self)
+        }
+    }
+}
+impl Display for Quantity { // allows for `println!()` and `.to_string()`
+
+    /// writes the amount followed by its unit, like `5 km`, or in SI base units like `2.5 m/s`,
+    /// or just the amount when it is a plain number, like the `0.5` of `(2 m) ^ 0.5`
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the quantity to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.unit {
+            Some(unit) => write!(f, "{} {}", shown_amount(self.value, unit.size), unit.name),
+            None if self.dimension.is_none() => write!(f, "{}", self.value),
+            None => write!(f, "{} {}", self.value, self.dimension),
+        }
+    }
+}
+
+// the amount of units of `size` that `value` SI base units is, with the fewest digits that give back `value` when multiplied by `size`.
+// an amount like `1.08 min` is kept as `1.08 * 60` seconds, which divides back to `1.0800000000000003`, so the division alone isn't enough
+fn shown_amount(value: f64, size: f64) -> f64 {
+    let amount = value / size;
+    (0..f64::DIGITS as usize)
+        .filter_map(|precision| format!("{:.*e}", precision, amount).parse::<f64>().ok())
+        .find(|shorter| shorter * size == value)
+        .unwrap_or(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        environment::Environment,
+        expression::Expression,
+    };
+
+    #[test]
+    fn plain_numbers_in_errors_have_no_units() {
+        let error = |input: &str| input.parse::<Expression>().unwrap().evaluate(&Environment::new()).unwrap_err().to_string();
+        assert_eq!(error("(2 m) ^ 0.5"), "A quantity with units can only be raised to a whole number, but was given 0.5");
+        assert_eq!(error("(2 m) ^ (1 m)"), "A quantity with units can only be raised to a whole number, but was given 1 m");
+        assert_eq!(error("2 m + 3"), "`+` needs quantities that measure the same thing, but was given 2 m and 3");
+    }
+}
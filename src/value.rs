@@ -0,0 +1,216 @@
+use std::fmt::Display;
+
+use crate::{
+    bigint::BigInt,
+    complex::Complex,
+    date::Date,
+    decimal::Decimal,
+    expression::Expression,
+    interval::Interval,
+    matrix::Matrix,
+    rational::Rational,
+    unit::Quantity,
+};
+
+/// A value an expression can evaluate to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// A real number
+    Real(f64),
+    /// An exact whole number of any size, from a literal like `12` or arithmetic on them like `2 ^ 200`
+    Integer(BigInt),
+    /// An exact fraction, from the fraction mode
+    Rational(Rational),
+    /// A ratio of two numbers in lowest terms, like the aspect ratio `16:9` from `aspect(1920, 1080)`,
+    /// which works like the fraction it stands for in arithmetic
+    Ratio(Rational),
+    /// An exact base 10 number, from the decimal mode
+    Decimal(Decimal),
+    /// A number known to lie between two bounds, from the interval mode
+    Interval(Interval),
+    /// A complex number with a nonzero imaginary part
+    Complex(Complex),
+    /// A real number with units, like `5 km`
+    Quantity(Quantity),
+    /// A moment in time, like `2024-03-01` or `now`
+    Date(Date),
+    /// A grid of real numbers, like `[1, 2; 3, 4]`
+    Matrix(Matrix),
+    /// An expression with variables that have no value, like the derivative `2 * x` from `diff(x ^ 2, x)`
+    Expression(Expression),
+    /// Values listed together, which can be complex unlike the elements of a matrix, like the roots `[-1, -i, i]` from `roots(x ^ 3 + x ^ 2 + x + 1)`
+    List(Vec<Value>),
+}
+impl Value {
+    /// The real number this value stands for
+    /// # Parameters
+    ///  - `context`: what needs a real number, for the error message, e.g. "`<`"
+    /// # Returns
+    ///  - `Ok(number)`: when the value is real
+    ///  - `Err(type_error)`: when the value is complex or has units
+    pub fn as_real(&self, context: &str) -> Result<f64, Box<dyn std::error::Error>> {
+        self.to_real()
+            .ok_or_else(|| format!("{} only works on real numbers, but was given {}", context, self).into())
+    }
+
+    /// The real number this value stands for, rounding an integer, fraction, or decimal to the nearest `f64`,
+    /// and taking the midpoint of an interval
+    /// # Returns
+    ///  - `Some(number)`: when the value is real
+    ///  - `None`: when the value is complex, has units, or is a date, matrix, expression, or list
+    pub fn to_real(&self) -> Option<f64> {
+        match self {
+            Value::Real(number) => Some(*number),
+            Value::Integer(integer) => Some(integer.to_f64()),
+            Value::Rational(fraction) | Value::Ratio(fraction) => Some(fraction.to_f64()),
+            Value::Decimal(decimal) => Some(decimal.to_f64()),
+            Value::Interval(interval) => Some(interval.midpoint()),
+            Value::Complex(_) | Value::Quantity(_) | Value::Date(_) | Value::Matrix(_) | Value::Expression(_) | Value::List(_) => None,
+        }
+    }
+
+    /// This value as a quantity, which is a plain number without units unless it is one
+    /// # Returns
+    ///  - `Some(quantity)`: when the value is real or a quantity
+    ///  - `None`: when the value is complex, a date, a matrix, an expression, or a list
+    pub fn to_quantity(&self) -> Option<Quantity> {
+        match self {
+            Value::Quantity(quantity) => Some(quantity.clone()),
+            value => value.to_real().map(Quantity::number),
+        }
+    }
+
+    /// This value as an interval holding it
+    /// # Returns
+    ///  - `Some(interval)`: when the value is real. A float is taken as exact, and anything an `f64` can't hold exactly is widened to the `f64`s around it
+    ///  - `None`: when the value is complex, has units, or is a date, matrix, expression, or list
+    pub fn to_interval(&self) -> Option<Interval> {
+        match self {
+            Value::Real(number) => Some(Interval::point(*number)),
+            Value::Integer(integer) => Some(Interval::from_integer(integer)),
+            Value::Rational(fraction) | Value::Ratio(fraction) => Some(Interval::around(fraction.to_f64())),
+            Value::Decimal(decimal) => Some(Interval::around(decimal.to_f64())),
+            Value::Interval(interval) => Some(*interval),
+            Value::Complex(_) | Value::Quantity(_) | Value::Date(_) | Value::Matrix(_) | Value::Expression(_) | Value::List(_) => None,
+        }
+    }
+
+    /// This value as an exact fraction
+    /// # Returns
+    ///  - `Some(fraction)`: when the value is a fraction or ratio, or an integer small enough to be one
+    ///  - `None`: for any other value
+    pub fn to_rational(&self) -> Option<Rational> {
+        match self {
+            Value::Rational(fraction) | Value::Ratio(fraction) => Some(*fraction),
+            Value::Integer(integer) => integer.to_i128().map(Rational::integer),
+            _ => None,
+        }
+    }
+
+    /// This value as an exact whole number
+    /// # Returns
+    ///  - `Some(integer)`: when the value is an integer, or a fraction that is a whole number
+    ///  - `None`: for any other value
+    pub fn to_integer(&self) -> Option<BigInt> {
+        match self {
+            Value::Integer(integer) => Some(integer.clone()),
+            Value::Rational(fraction) if fraction.is_integer() => Some(BigInt::from_i128(fraction.numerator())),
+            _ => None,
+        }
+    }
+
+    /// This value as an exact decimal
+    /// # Returns
+    ///  - `Some(decimal)`: when the value is a decimal or an integer
+    ///  - `None`: for any other value
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        match self {
+            Value::Decimal(decimal) => Some(decimal.clone()),
+            Value::Integer(integer) => Some(Decimal::integer(integer.clone())),
+            _ => None,
+        }
+    }
+
+    /// This value as a complex number, with an imaginary part of `0` when it is real, the amount in SI base units when it has units,
+    /// and the seconds since `1970-01-01` when it is a date. A matrix, expression, or list has no single number, so it is `NaN`
+    pub fn as_complex(&self) -> Complex {
+        match self {
+            Value::Real(number) => Complex::from(*number),
+            Value::Integer(integer) => Complex::from(integer.to_f64()),
+            Value::Rational(fraction) | Value::Ratio(fraction) => Complex::from(fraction.to_f64()),
+            Value::Decimal(decimal) => Complex::from(decimal.to_f64()),
+            Value::Interval(interval) => Complex::from(interval.midpoint()),
+            Value::Complex(z) => *z,
+            Value::Quantity(quantity) => Complex::from(quantity.value()),
+            Value::Date(date) => Complex::from(date.seconds()),
+            Value::Matrix(_) | Value::Expression(_) | Value::List(_) => Complex::from(f64::NAN),
+        }
+    }
+
+    /// Whether this value counts as true in a condition, which is whenever it isn't zero, always for a date,
+    /// for a matrix or list with any element that isn't zero, and always for an expression
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Real(number) => *number != 0.0,
+            Value::Integer(integer) => !integer.is_zero(),
+            Value::Rational(fraction) | Value::Ratio(fraction) => *fraction != Rational::integer(0),
+            Value::Decimal(decimal) => !decimal.is_zero(),
+            Value::Interval(interval) => interval.midpoint() != 0.0,
+            Value::Complex(z) => z.re != 0.0 || z.im != 0.0,
+            Value::Quantity(quantity) => quantity.value() != 0.0,
+            Value::Date(_) | Value::Expression(_) => true,
+            Value::Matrix(matrix) => matrix.elements().iter().any(|element| *element != 0.0),
+            Value::List(values) => values.iter().any(Value::is_truthy),
+        }
+    }
+}
+impl From<f64> for Value {
+    fn from(number: f64) -> Self {
+        Value::Real(number)
+    }
+}
+impl From<Complex> for Value {
+    /// a complex result whose imaginary part is exactly `0` is real, so `(1 + 2i) * (1 - 2i)` is `5`
+    fn from(z: Complex) -> Self {
+        if z.im == 0.0 {
+            Value::Real(z.re)
+        } else {
+            Value::Complex(z)
+        }
+    }
+}
+impl Display for Value { // allows for `println!()` and `.to_string()`
+
+    /// writes the value, as a fraction like `1/2` when it is one, a ratio like `16:9` when it is one, an interval like `0.1 ± 2e-17` when it is one, in rectangular form like `3+4i` when it is complex,
+    /// one row per line when it is a matrix, and like `[1, -i, i]` when it is a list
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the value to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Real(number) => write!(f, "{}", number),
+            Value::Integer(integer) => write!(f, "{}", integer),
+            Value::Rational(fraction) => write!(f, "{}", fraction),
+            Value::Ratio(ratio) => write!(f, "{}:{}", ratio.numerator(), ratio.denominator()),
+            Value::Decimal(decimal) => write!(f, "{}", decimal),
+            Value::Interval(interval) => write!(f, "{}", interval),
+            Value::Complex(z) => write!(f, "{}", z),
+            Value::Quantity(quantity) => write!(f, "{}", quantity),
+            Value::Date(date) => write!(f, "{}", date),
+            Value::Matrix(matrix) => write!(f, "{}", matrix),
+            Value::Expression(expression) => write!(f, "{}", expression),
+            Value::List(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+        }
+    }
+}
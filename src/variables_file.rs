@@ -0,0 +1,610 @@
+use std::{
+    iter::Peekable,
+    path::Path,
+    str::Chars,
+};
+
+use crate::{
+    environment::Environment,
+    value::Value,
+};
+
+/// Loads every number in a TOML or JSON file into `environment` as a variable.<br>
+/// Nested keys are flattened by joining them with `_`, so `[db]` `port = 5432` in TOML
+/// or `{"db": {"port": 5432}}` in JSON both become the variable `db_port`.
+/// Array elements are named by their index, so `sizes = [1, 2]` in TOML or `{"sizes": [1, 2]}` in JSON become `sizes_0` and `sizes_1`,
+/// and the keys of a TOML inline table like `db = { port = 5432 }` are joined the same way as a table's.
+/// Strings, booleans, dates, and other values that aren't numbers are skipped.
+///
+/// Only part of each format is read, and a file that uses the rest is rejected rather than partly loaded:
+///  - TOML: tables, dotted and quoted keys, arrays and inline tables over any number of lines, `"` and `'` strings,
+///    integers with `_` or a `0x`, `0o`, or `0b` prefix, floats, `inf`, booleans, and dates and times.
+///    Arrays of tables like `[[servers]]` and multi-line `"""` or `'''` strings aren't supported
+///  - JSON: the whole document has to be one object. Anything inside it is read, with `true`, `false`, and `null` skipped
+///  - both: values can be nested at most 256 levels deep, and no two values can flatten to the same name,
+///    so a key given twice, or `a_b` next to `[a]` `b`, is an error
+/// # Parameters
+///  - `path`: the file to read. A `.json` file, or one whose text starts with `{`, is read as JSON, anything else as TOML
+///  - `environment`: where the variables are assigned
+/// # Returns
+///  - `Ok(())`: when every number was assigned
+///  - `Err(load_error)`: when the file can't be read, isn't valid, has a number that doesn't fit in an `i64` or `f64` or is `nan`,
+///    gives two values the same name, or names a builtin constant or function
+pub fn load(path: &Path, environment: &mut Environment) -> Result<(), Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path)?;
+
+    let is_json = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    for (name, value) in &parse_numbers(&text, is_json)? {
+        environment.assign(name, Value::Real(*value))?;
+    }
+    Ok(())
+}
+
+/// A number or string read from a TOML or JSON document
+#[derive(Debug, Clone, PartialEq)]
+pub enum Entry {
+    Number(f64),
+    Text(String),
+}
+
+/// Finds every number in a TOML or JSON document, with its key flattened the way `load` names variables
+/// # Parameters
+///  - `text`: the document
+///  - `is_json`: whether the document is JSON. Text that starts with `{` is read as JSON either way
+/// # Returns
+///  - `Ok(numbers)`: the `(name, value)` of every number, in the order they appear
+///  - `Err(parse_error)`: when the document isn't valid
+pub fn parse_numbers(text: &str, is_json: bool) -> Result<Vec<(String, f64)>, Box<dyn std::error::Error>> {
+    let numbers = parse_entries(text, is_json)?
+        .into_iter()
+        .filter_map(|(name, entry)| match entry {
+            Entry::Number(number) => Some((name, number)),
+            Entry::Text(_) => None,
+        })
+        .collect();
+    Ok(numbers)
+}
+
+/// Finds every number and string in a TOML or JSON document, with its key flattened the way `load` names variables
+/// # Parameters
+///  - `text`: the document
+///  - `is_json`: whether the document is JSON. Text that starts with `{` is read as JSON either way
+/// # Returns
+///  - `Ok(entries)`: the `(name, entry)` of every number and string, in the order they appear
+///  - `Err(parse_error)`: when the document isn't valid, or two of its values flatten to the same name
+pub fn parse_entries(text: &str, is_json: bool) -> Result<Vec<(String, Entry)>, Box<dyn std::error::Error>> {
+    let entries = if is_json || text.trim_start().starts_with('{') {
+        parse_json(text)?
+    } else {
+        parse_toml(text)?
+    };
+
+    // a repeated key would silently replace the earlier value, so the file is ambiguous
+    let mut names = std::collections::HashSet::new();
+    if let Some((name, _)) = entries.iter().find(|(name, _)| !names.insert(name.as_str())) {
+        return Err(format!("`{}` is given more than once", name).into());
+    }
+    Ok(entries)
+}
+
+// join the parts of a nested key into one variable name, replacing characters a name can't contain with `_`
+fn flatten_key(parts: &[String]) -> String {
+    let mut name: String = parts
+        .join("_")
+        .chars()
+        .map(|character| if character.is_alphanumeric() { character } else { '_' })
+        .collect();
+    if name.starts_with(|character: char| character.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+// the `(name, entry)` of every number and string in a TOML document, in the order they appear
+fn parse_toml(text: &str) -> Result<Vec<(String, Entry)>, Box<dyn std::error::Error>> {
+    let mut variables = Vec::new();
+    let mut table: Vec<String> = Vec::new();
+
+    let mut lines = text.lines().enumerate();
+    while let Some((i, line)) = lines.next() {
+        let line_number = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with("[[") {
+            return Err(format!("line {}: arrays of tables aren't supported", line_number).into());
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .split('#')
+                .next()
+                .and_then(|header| header.trim_end().strip_suffix(']'))
+                .ok_or_else(|| format!("line {}: missing `]` after the table name", line_number))?;
+            table = split_toml_key(header);
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected `key = value`", line_number))?;
+
+        // an array or inline table carries on over the following lines until its brackets close
+        let mut value = value.to_owned();
+        while toml_value_is_open(&value) {
+            let (_, next) = lines
+                .next()
+                .ok_or_else(|| format!("line {}: the value is missing a closing `]` or `}}`", line_number))?;
+            value.push('\n');
+            value.push_str(next);
+        }
+
+        let mut parts = table.clone();
+        parts.extend(split_toml_key(key));
+        let mut characters = value.chars().peekable();
+        parse_toml_value(&mut characters, &mut parts, &mut variables).map_err(|error| format!("line {}: {}", line_number, error))?;
+        skip_toml_whitespace(&mut characters);
+        if let Some(character) = characters.next() {
+            return Err(format!("line {}: unexpected `{}` after the value", line_number, character).into());
+        }
+    }
+
+    Ok(variables)
+}
+
+// split a possibly dotted and quoted TOML key like `server."max-load"` into its parts
+fn split_toml_key(key: &str) -> Vec<String> {
+    key.split('.')
+        .map(|part| part.trim().trim_matches(|quote| quote == '"' || quote == '\'').to_owned())
+        .collect()
+}
+
+// whether `value` opens more `[` and `{` than it closes, outside of strings and comments
+fn toml_value_is_open(value: &str) -> bool {
+    let mut depth = 0;
+    let mut characters = value.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth -= 1,
+            // a string can hold brackets that don't count, and only a `"` string has escapes
+            '"' => while let Some(character) = characters.next() {
+                match character {
+                    '\\' => {
+                        characters.next();
+                    },
+                    '"' => break,
+                    _ => {},
+                }
+            },
+            '\'' => while characters.next().is_some_and(|character| character != '\'') {},
+            '#' => while characters.next().is_some_and(|character| character != '\n') {},
+            _ => {},
+        }
+    }
+    depth > 0
+}
+
+// parse one TOML value, pushing each number and string in it to `variables` under the key path `key`.
+// arrays and inline tables are named like JSON ones, so `sizes = [1, 2]` gives `sizes_0` and `sizes_1`
+fn parse_toml_value(characters: &mut Peekable<Chars>, key: &mut Vec<String>, variables: &mut Vec<(String, Entry)>) -> Result<(), Box<dyn std::error::Error>> {
+    // every level of nesting is a level of recursion, so stop before the stack runs out
+    const MAX_DEPTH: usize = 256;
+    if key.len() > MAX_DEPTH {
+        return Err(format!("the value is nested too deeply (at most {} levels)", MAX_DEPTH).into());
+    }
+
+    skip_toml_whitespace(characters);
+    match characters.peek().copied() {
+        Some('[') => {
+            characters.next();
+            for index in 0.. {
+                skip_toml_whitespace(characters);
+                // an array can end with a `,` after its last element
+                if characters.next_if_eq(&']').is_some() {
+                    break;
+                }
+                key.push(index.to_string());
+                parse_toml_value(characters, key, variables)?;
+                key.pop();
+
+                skip_toml_whitespace(characters);
+                match characters.next() {
+                    Some(',') => continue,
+                    Some(']') => break,
+                    _ => return Err("expected `,` or `]` in an array".into()),
+                }
+            }
+            Ok(())
+        },
+        Some('{') => {
+            characters.next();
+            skip_toml_whitespace(characters);
+            if characters.next_if_eq(&'}').is_some() {
+                return Ok(());
+            }
+            loop {
+                let name: String = std::iter::from_fn(|| characters.next_if(|character| !matches!(character, '=' | ',' | '}' | '\n'))).collect();
+                if characters.next() != Some('=') {
+                    return Err("expected `key = value` in an inline table".into());
+                }
+                let length = key.len();
+                key.extend(split_toml_key(&name));
+                parse_toml_value(characters, key, variables)?;
+                key.truncate(length);
+
+                skip_toml_whitespace(characters);
+                match characters.next() {
+                    Some(',') => continue,
+                    Some('}') => return Ok(()),
+                    _ => return Err("expected `,` or `}` in an inline table".into()),
+                }
+            }
+        },
+        Some('"') => {
+            let text = parse_json_string(characters)?;
+            if text.is_empty() && characters.peek() == Some(&'"') {
+                return Err("multi-line strings aren't supported".into());
+            }
+            variables.push((flatten_key(key), Entry::Text(text)));
+            Ok(())
+        },
+        Some('\'') => {
+            characters.next();
+            let text: String = std::iter::from_fn(|| characters.next_if(|character| !matches!(character, '\'' | '\n'))).collect();
+            if characters.next() != Some('\'') {
+                return Err("unterminated string".into());
+            }
+            if text.is_empty() && characters.peek() == Some(&'\'') {
+                return Err("multi-line strings aren't supported".into());
+            }
+            variables.push((flatten_key(key), Entry::Text(text)));
+            Ok(())
+        },
+        Some(_) => {
+            // numbers, booleans, and dates end at the next separator, and only numbers are kept
+            let value: String = std::iter::from_fn(|| characters.next_if(|character| !matches!(character, ',' | ']' | '}' | '#' | '\n'))).collect();
+            if let Some(number) = parse_toml_number(value.trim())? {
+                variables.push((flatten_key(key), Entry::Number(number)));
+            }
+            Ok(())
+        },
+        None => Err("expected a value".into()),
+    }
+}
+
+// skip spaces, tabs, newlines, and `#` comments between TOML values
+fn skip_toml_whitespace(characters: &mut Peekable<Chars>) {
+    loop {
+        while characters.next_if(|character| character.is_whitespace()).is_some() {}
+        if characters.next_if_eq(&'#').is_none() {
+            return;
+        }
+        while characters.next_if(|character| *character != '\n').is_some() {}
+    }
+}
+
+// a TOML integer or float, which may contain `_` separators, or be a 64 bit integer with a `0x`, `0o`, or `0b` prefix
+// and no sign. booleans, dates, and times aren't numbers, so they are `None`, and any other word is an error
+fn parse_toml_number(value: &str) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+    let number = value.replace('_', "");
+    let digits = number.trim_start_matches(['+', '-']);
+    let has_sign = digits.len() < number.len();
+
+    let radix = match digits.get(..2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        if has_sign {
+            return Err(format!("`{}` can't have a sign, since it has a base prefix", value).into());
+        }
+        let integer = i64::from_str_radix(&digits[2..], radix).map_err(|_| format!("`{}` isn't a 64 bit integer", value))?;
+        return Ok(Some(integer as f64));
+    }
+
+    match digits {
+        "inf" => return Ok(Some(if number.starts_with('-') { f64::NEG_INFINITY } else { f64::INFINITY })),
+        "nan" => return Err(format!("`{}` isn't a number a variable can hold", value).into()),
+        "true" | "false" if !has_sign => return Ok(None),
+        _ if !digits.starts_with(|character: char| character.is_ascii_digit()) => return Err(format!("invalid value `{}`", value).into()),
+        // a date like `1979-05-27` or a time like `07:32:00`
+        _ if digits.contains(':') || digits.as_bytes().get(4) == Some(&b'-') => return Ok(None),
+        _ => {},
+    }
+
+    let number = number.strip_prefix('+').unwrap_or(&number);
+    if number.trim_start_matches('-').bytes().all(|digit| digit.is_ascii_digit()) {
+        return match number.parse::<i64>() {
+            Ok(integer) => Ok(Some(integer as f64)),
+            Err(_) => Err(format!("`{}` isn't a 64 bit integer", value).into()),
+        };
+    }
+    match number.parse::<f64>() {
+        Ok(float) if float.is_finite() => Ok(Some(float)),
+        Ok(_) => Err(format!("`{}` is too big for a floating point number", value).into()),
+        Err(_) => Err(format!("invalid number `{}`", value).into()),
+    }
+}
+
+// the `(name, entry)` of every number and string in a JSON document, in the order they appear
+fn parse_json(text: &str) -> Result<Vec<(String, Entry)>, Box<dyn std::error::Error>> {
+    let mut characters = text.chars().peekable();
+    let mut variables = Vec::new();
+
+    skip_json_whitespace(&mut characters);
+    if characters.peek() != Some(&'{') {
+        return Err("a JSON variables file must be an object".into());
+    }
+    parse_json_value(&mut characters, &mut Vec::new(), &mut variables)?;
+
+    skip_json_whitespace(&mut characters);
+    if let Some(character) = characters.next() {
+        return Err(format!("unexpected `{}` after the end of the JSON object", character).into());
+    }
+    Ok(variables)
+}
+
+// parse one JSON value, pushing each number and string in it to `variables` under the key path `key`
+fn parse_json_value(characters: &mut Peekable<Chars>, key: &mut Vec<String>, variables: &mut Vec<(String, Entry)>) -> Result<(), Box<dyn std::error::Error>> {
+    // every level of nesting is a level of recursion, so stop before the stack runs out
+    const MAX_DEPTH: usize = 256;
+    if key.len() > MAX_DEPTH {
+        return Err(format!("JSON is nested too deeply (at most {} levels)", MAX_DEPTH).into());
+    }
+
+    skip_json_whitespace(characters);
+    match characters.peek().copied() {
+        Some('{') => {
+            characters.next();
+            skip_json_whitespace(characters);
+            if characters.peek() == Some(&'}') {
+                characters.next();
+                return Ok(());
+            }
+            loop {
+                skip_json_whitespace(characters);
+                key.push(parse_json_string(characters)?);
+                skip_json_whitespace(characters);
+                if characters.next() != Some(':') {
+                    return Err("expected `:` after a JSON key".into());
+                }
+                parse_json_value(characters, key, variables)?;
+                key.pop();
+
+                skip_json_whitespace(characters);
+                match characters.next() {
+                    Some(',') => continue,
+                    Some('}') => return Ok(()),
+                    _ => return Err("expected `,` or `}` in a JSON object".into()),
+                }
+            }
+        },
+        Some('[') => {
+            characters.next();
+            skip_json_whitespace(characters);
+            if characters.peek() == Some(&']') {
+                characters.next();
+                return Ok(());
+            }
+            for index in 0.. {
+                key.push(index.to_string());
+                parse_json_value(characters, key, variables)?;
+                key.pop();
+
+                skip_json_whitespace(characters);
+                match characters.next() {
+                    Some(',') => continue,
+                    Some(']') => break,
+                    _ => return Err("expected `,` or `]` in a JSON array".into()),
+                }
+            }
+            Ok(())
+        },
+        Some('"') => {
+            let text = parse_json_string(characters)?;
+            variables.push((flatten_key(key), Entry::Text(text)));
+            Ok(())
+        },
+        Some('-' | '0'..='9') => {
+            let mut number = String::new();
+            while let Some(&character) = characters.peek() {
+                if !(character.is_ascii_digit() || matches!(character, '-' | '+' | '.' | 'e' | 'E')) {
+                    break;
+                }
+                number.push(character);
+                characters.next();
+            }
+            let value: f64 = number.parse().map_err(|_| format!("invalid JSON number `{}`", number))?;
+            if !value.is_finite() {
+                return Err(format!("JSON number `{}` is too big for a floating point number", number).into());
+            }
+            variables.push((flatten_key(key), Entry::Number(value)));
+            Ok(())
+        },
+        Some(character) if !character.is_ascii_alphabetic() => Err(format!("unexpected `{}` in JSON", character).into()),
+        Some(_) => {
+            // `true`, `false`, and `null` aren't numbers, so they are skipped
+            let word: String = std::iter::from_fn(|| characters.next_if(|character| character.is_ascii_alphabetic())).collect();
+            match word.as_str() {
+                "true" | "false" | "null" => Ok(()),
+                _ => Err(format!("unexpected `{}` in JSON", word).into()),
+            }
+        },
+        None => Err("unexpected end of JSON".into()),
+    }
+}
+
+// parse a JSON string literal, including its quotes, into its text. TOML's `"` strings are read the same way,
+// and neither can go over more than one line
+fn parse_json_string(characters: &mut Peekable<Chars>) -> Result<String, Box<dyn std::error::Error>> {
+    if characters.next() != Some('"') {
+        return Err("expected a JSON string".into());
+    }
+
+    let mut text = String::new();
+    loop {
+        match characters.next() {
+            Some('"') => return Ok(text),
+            Some('\\') => match characters.next() {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some('r') => text.push('\r'),
+                Some('b') => text.push('\u{8}'),
+                Some('f') => text.push('\u{c}'),
+                Some('u') => {
+                    let code: String = characters.by_ref().take(4).collect();
+                    let code = u32::from_str_radix(&code, 16).map_err(|_| format!("invalid escape `\\u{}`", code))?;
+                    text.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                },
+                Some(character) => text.push(character),
+                None => return Err("unterminated string".into()),
+            },
+            Some('\n') | None => return Err("unterminated string".into()),
+            Some(character) => text.push(character),
+        }
+    }
+}
+
+// skip spaces, tabs, and newlines between JSON tokens
+fn skip_json_whitespace(characters: &mut Peekable<Chars>) {
+    while characters.next_if(|character| character.is_whitespace()).is_some() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(text: &str, is_json: bool) -> Vec<(String, f64)> {
+        parse_numbers(text, is_json).unwrap()
+    }
+
+    fn named(pairs: &[(&str, f64)]) -> Vec<(String, f64)> {
+        pairs.iter().map(|(name, number)| (name.to_string(), *number)).collect()
+    }
+
+    #[test]
+    fn toml_tables_and_dotted_keys_are_flattened() {
+        let text = "rate = 1.5\n[db]\nport = 5432 # the default\n[server.limits]\nmax-users = 1_000\n";
+        assert_eq!(numbers(text, false), named(&[("rate", 1.5), ("db_port", 5432.0), ("server_limits_max_users", 1000.0)]));
+    }
+
+    #[test]
+    fn toml_skips_values_that_arent_numbers() {
+        let text = "name = \"calc\"\nenabled = true\nreleased = 1979-05-27\nat = 07:32:00\nsize = 3\n";
+        assert_eq!(numbers(text, false), named(&[("size", 3.0)]));
+        assert_eq!(parse_entries("name = \"calc\"", false).unwrap(), vec![("name".to_owned(), Entry::Text("calc".to_owned()))]);
+    }
+
+    #[test]
+    fn toml_prefixed_and_special_numbers() {
+        let text = "hex = 0xff\noctal = 0o17\nbinary = 0b101\nbig = inf\nsmall = -inf\nfloat = 6.02e23\n";
+        let expected = named(&[("hex", 255.0), ("octal", 15.0), ("binary", 5.0), ("big", f64::INFINITY), ("small", f64::NEG_INFINITY), ("float", 6.02e23)]);
+        assert_eq!(numbers(text, false), expected);
+    }
+
+    #[test]
+    fn toml_arrays_and_inline_tables() {
+        let text = "sizes = [1, 2, [3, 4]]\ndb = { port = 5432, limits = { users = 10 } }\nmultiline = [\n  5,\n  6, # six\n]\n";
+        let expected = named(&[
+            ("sizes_0", 1.0),
+            ("sizes_1", 2.0),
+            ("sizes_2_0", 3.0),
+            ("sizes_2_1", 4.0),
+            ("db_port", 5432.0),
+            ("db_limits_users", 10.0),
+            ("multiline_0", 5.0),
+            ("multiline_1", 6.0),
+        ]);
+        assert_eq!(numbers(text, false), expected);
+    }
+
+    #[test]
+    fn toml_numbers_out_of_range_are_errors() {
+        assert!(parse_numbers("big = 9223372036854775808", false).is_err());
+        assert!(parse_numbers("big = 0x1_0000_0000_0000_0000", false).is_err());
+        assert!(parse_numbers("huge = 1e400", false).is_err());
+        assert!(parse_numbers("missing = nan", false).is_err());
+        assert!(parse_numbers("signed = -0xff", false).is_err());
+    }
+
+    #[test]
+    fn toml_syntax_errors() {
+        assert!(parse_numbers("[[servers]]\nport = 1", false).is_err());
+        assert!(parse_numbers("[db\nport = 1", false).is_err());
+        assert!(parse_numbers("port", false).is_err());
+        assert!(parse_numbers("sizes = [1, 2", false).is_err());
+    }
+
+    #[test]
+    fn json_objects_and_arrays_are_flattened() {
+        let text = r#"{"db": {"port": 5432, "name": "main"}, "sizes": [1, -2.5e1], "ok": true, "none": null}"#;
+        assert_eq!(numbers(text, true), named(&[("db_port", 5432.0), ("sizes_0", 1.0), ("sizes_1", -25.0)]));
+        // text that starts with `{` is JSON even without the extension
+        assert_eq!(numbers(" {\"x\": 1}", false), named(&[("x", 1.0)]));
+    }
+
+    #[test]
+    fn json_strings_and_escapes() {
+        let entries = parse_entries(r#"{"a b": "line\nbreak é"}"#, true).unwrap();
+        assert_eq!(entries, vec![("a_b".to_owned(), Entry::Text("line\nbreak é".to_owned()))]);
+    }
+
+    #[test]
+    fn json_syntax_errors() {
+        assert!(parse_numbers(r#"{"x": 1"#, true).is_err());
+        assert!(parse_numbers(r#"{"x" 1}"#, true).is_err());
+        assert!(parse_numbers(r#"{"x": 1,}"#, true).is_err());
+        assert!(parse_numbers("[1, 2]", true).is_err());
+    }
+
+    #[test]
+    fn keys_starting_with_a_digit_get_a_leading_underscore() {
+        assert_eq!(numbers("[2024]\ntotal = 1", false), named(&[("_2024_total", 1.0)]));
+    }
+
+    #[test]
+    fn unterminated_strings_are_errors() {
+        assert!(parse_numbers("name = \"calc", false).is_err());
+        assert!(parse_numbers("name = 'calc", false).is_err());
+        assert!(parse_numbers("names = [\"a\n\", 1]", false).is_err());
+        assert!(parse_numbers(r#"{"name": "calc}"#, true).is_err());
+        assert!(parse_numbers(r#"{"name: 1}"#, true).is_err());
+    }
+
+    #[test]
+    fn duplicate_names_are_errors() {
+        assert!(parse_numbers("port = 1\nport = 2", false).is_err());
+        assert!(parse_numbers("db_port = 1\n[db]\nport = 2", false).is_err());
+        assert!(parse_numbers("name = \"a\"\nname = 2", false).is_err());
+        assert!(parse_numbers(r#"{"port": 1, "port": 2}"#, true).is_err());
+        assert!(parse_numbers(r#"{"db": {"port": 1}, "db_port": 2}"#, true).is_err());
+    }
+
+    #[test]
+    fn arrays_that_arent_numbers() {
+        // strings and booleans are skipped, but a word that isn't any TOML value is an error
+        assert_eq!(numbers("names = [\"a\", 'b', true]\nsizes = [1, false]", false), named(&[("sizes_0", 1.0)]));
+        assert_eq!(numbers(r#"{"names": ["a", true, null], "sizes": [1]}"#, true), named(&[("sizes_0", 1.0)]));
+        assert!(parse_numbers("sizes = [1, two]", false).is_err());
+        assert!(parse_numbers("size = -true", false).is_err());
+        assert!(parse_numbers(r#"{"sizes": [1, two]}"#, true).is_err());
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        let depth = 10_000;
+        let arrays = format!("{{\"x\": {}1{}}}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse_numbers(&arrays, true).is_err());
+        let objects = format!("{}1{}", "{\"x\": ".repeat(depth), "}".repeat(depth));
+        assert!(parse_numbers(&objects, true).is_err());
+        assert!(parse_numbers(&format!("x = {}1{}", "[".repeat(depth), "]".repeat(depth)), false).is_err());
+
+        // nesting up to the limit is fine
+        let nested = format!("{{\"x\": {}1{}}}", "[".repeat(100), "]".repeat(100));
+        assert_eq!(parse_numbers(&nested, true).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,28 @@
+use std::fmt::Display;
+
+/// Something suspicious about a result that is reported to the user without aborting evaluation
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// The result is an integer larger than 2^53, so an `f64` can't represent every digit
+    InexactInteger,
+    /// `lhs` and `rhs` were nearly equal, so subtracting them cancelled out most significant digits
+    Cancellation { lhs: f64, rhs: f64 },
+    /// `operation` has no interval version for its interval operands, so it was worked out on their midpoints and the bounds were lost
+    DroppedBounds { operation: String },
+}
+impl Display for Warning { // allows for `println!()` and `.to_string()`
+
+    /// writes a description of the warning to the formatter `f`
+    /// # Parameters
+    ///  - `f`: the `Formatter` that we will write the warning to. (can be a string or stdout)
+    /// # Returns
+    ///  - `Ok(())`: if `write!` succeeds
+    ///  - `Err(format_error)`: if `write!` fails
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::InexactInteger => write!(f, "result exceeds 2^53; digits beyond the 16th significant figure may be wrong"),
+            Warning::Cancellation { lhs, rhs } => write!(f, "catastrophic cancellation: {} and {} are nearly equal so most digits of the result may be wrong", lhs, rhs),
+            Warning::DroppedBounds { operation } => write!(f, "interval bounds dropped: `{}` was worked out on the midpoint, so the result has no ± and may be off by more than it shows", operation),
+        }
+    }
+}